@@ -0,0 +1,46 @@
+// MIT License
+//
+// Copyright (c) 2026 Raja Lehtihet & Wael El Oraiby
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+use sketches::simhash::SimHash;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use a 256-bit fingerprint with word shingles as features.
+    let mut left = SimHash::new(256)?;
+    let mut right = SimHash::new(256)?;
+
+    let left_text = "the quick brown fox jumps over the lazy dog";
+    let right_text = "the quick brown fox leaps over the lazy dog";
+    for word in left_text.split_whitespace() {
+        left.add(&word);
+    }
+    for word in right_text.split_whitespace() {
+        right.add(&word);
+    }
+
+    println!("Hamming distance: {}", left.hamming_distance(&right)?);
+    println!(
+        "Estimated cosine similarity: {:.4}",
+        left.estimate_cosine(&right)?
+    );
+
+    Ok(())
+}
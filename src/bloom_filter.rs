@@ -28,13 +28,46 @@
 //! does not report a runtime false-positive rate: that probability also
 //! depends on assumptions about hashing and the distribution of absent
 //! queries, which the bitmap does not retain.
+//!
+//! # Hashing
+//!
+//! Probe positions use Kirsch-Mitzenmacher double hashing: two 64-bit hashes
+//! `h1` and `h2` of an item generate all `k` probes. Both come from the
+//! filter's [`BuildHasher`], keyed by two fixed domain constants. The default
+//! [`DefaultBloomHasher`] is zero-keyed SipHash-1-3, whose output is fixed
+//! across Rust releases, so the bitmap of a default filter is reproducible.
+//! [`BloomFilter::with_hasher`] and [`BloomFilter::with_size_and_hasher`]
+//! accept any other `BuildHasher`, such as a faster or independently keyed
+//! one.
+//!
+//! Bitmaps are only comparable when they were produced by hashers that hash
+//! identically. The type system enforces that both filters use the same
+//! hasher type, but not the same keys: merging or comparing filters whose
+//! hashers are keyed differently silently produces a meaningless result.
+//!
+//! Callers whose keys are already 64-bit hashes can bypass the hasher with
+//! [`BloomFilter::insert_hash`] and [`BloomFilter::contains_hash`], which
+//! derive `h1` and `h2` by mixing the given hash with SplitMix64. The two
+//! paths probe independently: an item added with [`BloomFilter::insert`] is
+//! not found by [`BloomFilter::contains_hash`], and vice versa.
+
+use alloc::{vec, vec::Vec};
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
 
-use std::hash::Hash;
+use siphasher::sip::SipHasher13;
 
-use crate::{SketchError, seeded_hash64};
+use crate::cardinality::ApproxCardinality;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::jacard::{JacardIndex, inclusion_exclusion_estimates};
+use crate::{SketchError, splitmix64};
 
 const HASH_SEED_A: u64 = 0x243F_6A88_85A3_08D3;
 const HASH_SEED_B: u64 = 0x1319_8A2E_0370_7344;
+const INCOMPATIBLE_FILTERS: &str = "bit_len and num_hashes must match";
+
+/// Default [`BuildHasher`] for [`BloomFilter`]: zero-keyed SipHash-1-3.
+pub type DefaultBloomHasher = BuildHasherDefault<SipHasher13>;
 
 /// Probabilistic set-membership filter.
 ///
@@ -46,12 +79,16 @@ const HASH_SEED_B: u64 = 0x1319_8A2E_0370_7344;
 /// filter.insert(&"alice");
 /// assert!(filter.contains(&"alice"));
 /// ```
+///
+/// The hasher parameter `S` selects the hash function behind the probes; see
+/// the [module documentation](self) for when two filters are compatible.
 #[derive(Debug, Clone)]
-pub struct BloomFilter {
+pub struct BloomFilter<S = DefaultBloomHasher> {
     bit_len: usize,
     words: Vec<u64>,
     num_hashes: u32,
     inserted_items: u64,
+    hasher: S,
 }
 
 impl BloomFilter {
@@ -66,29 +103,18 @@ impl BloomFilter {
         Self::with_size(bit_len, num_hashes)
     }
 
+    /// Returns a builder for configuring a filter from any consistent subset
+    /// of sizing parameters.
+    pub fn builder() -> BloomFilterBuilder {
+        BloomFilterBuilder::new()
+    }
+
     /// Creates a Bloom filter from explicit bit length and hash count.
     ///
     /// # Errors
     /// Returns [`SketchError::InvalidParameter`] when values are zero.
     pub fn with_size(bit_len: usize, num_hashes: u32) -> Result<Self, SketchError> {
-        if bit_len == 0 {
-            return Err(SketchError::InvalidParameter(
-                "bit_len must be greater than zero",
-            ));
-        }
-        if num_hashes == 0 {
-            return Err(SketchError::InvalidParameter(
-                "num_hashes must be greater than zero",
-            ));
-        }
-
-        let word_len = bit_len.div_ceil(64);
-        Ok(Self {
-            bit_len,
-            words: vec![0; word_len],
-            num_hashes,
-            inserted_items: 0,
-        })
+        Self::with_size_and_hasher(bit_len, num_hashes, DefaultBloomHasher::default())
     }
 
     /// Returns the recommended bit length for an expected number of distinct
@@ -118,7 +144,7 @@ impl BloomFilter {
 
         let n = expected_items as f64;
         let numerator = -n * false_positive_rate.ln();
-        let denominator = std::f64::consts::LN_2.powi(2);
+        let denominator = core::f64::consts::LN_2.powi(2);
         let bits = (numerator / denominator).ceil() as usize;
         Ok(bits.max(1))
     }
@@ -142,9 +168,62 @@ impl BloomFilter {
             ));
         }
 
-        let k = ((bit_len as f64 / expected_items as f64) * std::f64::consts::LN_2).round() as u32;
+        let k = ((bit_len as f64 / expected_items as f64) * core::f64::consts::LN_2).round() as u32;
         Ok(k.max(1))
     }
+}
+
+impl<S: BuildHasher> BloomFilter<S> {
+    /// Creates a Bloom filter like [`BloomFilter::new`] that hashes items with
+    /// `hasher`.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid input values.
+    pub fn with_hasher(
+        expected_items: usize,
+        false_positive_rate: f64,
+        hasher: S,
+    ) -> Result<Self, SketchError> {
+        let bit_len = BloomFilter::optimal_bit_len(expected_items, false_positive_rate)?;
+        let num_hashes = BloomFilter::optimal_num_hashes(bit_len, expected_items)?;
+        Self::with_size_and_hasher(bit_len, num_hashes, hasher)
+    }
+
+    /// Creates a Bloom filter from explicit bit length and hash count that
+    /// hashes items with `hasher`.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when values are zero.
+    pub fn with_size_and_hasher(
+        bit_len: usize,
+        num_hashes: u32,
+        hasher: S,
+    ) -> Result<Self, SketchError> {
+        if bit_len == 0 {
+            return Err(SketchError::InvalidParameter(
+                "bit_len must be greater than zero",
+            ));
+        }
+        if num_hashes == 0 {
+            return Err(SketchError::InvalidParameter(
+                "num_hashes must be greater than zero",
+            ));
+        }
+
+        let word_len = bit_len.div_ceil(64);
+        Ok(Self {
+            bit_len,
+            words: vec![0; word_len],
+            num_hashes,
+            inserted_items: 0,
+            hasher,
+        })
+    }
+
+    /// Returns the hasher used to derive probe positions.
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
 
     /// Returns the number of addressable bits.
     pub fn bit_len(&self) -> usize {
@@ -156,16 +235,38 @@ impl BloomFilter {
         self.num_hashes
     }
 
+    /// Returns the backing bitmap as 64-bit words.
+    ///
+    /// Bit `i` is bit `i % 64` of word `i / 64`. Bits at or beyond
+    /// [`Self::bit_len`] in the final word are always zero. Together with
+    /// [`Self::reset_to`] this lets callers keep precomputed states.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
     /// Returns the number of `insert` operations applied, including duplicate
     /// items, as a saturating counter.
     ///
     /// This is operational telemetry, not a distinct-item count or a measure
     /// of bitmap load. Merging sums the operation counters from both filters,
     /// even when their inserted items overlap.
+    ///
+    /// [`Self::reset_to`] is the exception: it cannot know how many inserts
+    /// produced the loaded bitmap, so it sets this counter to a distinct-item
+    /// estimate instead, and later operations count on from there.
     pub fn inserted_items(&self) -> u64 {
         self.inserted_items
     }
 
+    /// Returns the fraction of bits that are set, in `[0, 1]`.
+    ///
+    /// This is the bitmap load actually reached, unlike
+    /// [`Self::inserted_items`]. A ratio well above one half indicates that
+    /// the filter holds more distinct items than its optimal sizing assumed.
+    pub fn fill_ratio(&self) -> f64 {
+        self.set_bits() as f64 / self.bit_len as f64
+    }
+
     /// Returns `true` if no item has been inserted.
     pub fn is_empty(&self) -> bool {
         self.inserted_items == 0
@@ -174,15 +275,50 @@ impl BloomFilter {
     /// Inserts an item into the filter.
     pub fn insert<T: Hash>(&mut self, item: &T) {
         let (h1, h2) = self.hash_pair(item);
+        self.insert_pair(h1, h2);
+    }
 
-        let mut probe = h1;
-        for _ in 0..self.num_hashes {
-            let bit_index = (probe as usize) % self.bit_len;
-            self.set_bit(bit_index);
-            probe = probe.wrapping_add(h2);
+    /// Inserts an item unless it is possibly present already, returning `true`
+    /// when it was inserted.
+    ///
+    /// A `false` result means the item was probably seen before; with the
+    /// filter's false-positive rate it was never inserted at all. The item is
+    /// hashed once for both the probe and the insertion.
+    pub fn insert_if_absent<T: Hash>(&mut self, item: &T) -> bool {
+        let (h1, h2) = self.hash_pair(item);
+        if self.contains_pair(h1, h2) {
+            return false;
         }
+        self.insert_pair(h1, h2);
+        true
+    }
 
-        self.inserted_items = self.inserted_items.saturating_add(1);
+    /// Consumes the filter and yields only the items of `items` that
+    /// [`Self::insert_if_absent`] accepts, turning it into a streaming
+    /// deduplication operator.
+    ///
+    /// Repeated items are always suppressed. A first occurrence is also
+    /// suppressed when it collides with earlier items, which happens at about
+    /// the filter's false-positive rate for the number of distinct items seen
+    /// so far; size the filter for the expected number of distinct items.
+    pub fn dedup<I>(self, items: I) -> impl Iterator<Item = I::Item>
+    where
+        I: IntoIterator,
+        I::Item: Hash,
+    {
+        let mut filter = self;
+        items
+            .into_iter()
+            .filter(move |item| filter.insert_if_absent(item))
+    }
+
+    /// Inserts a caller-computed 64-bit hash, bypassing the filter's hasher.
+    ///
+    /// The hash should already be well mixed; it is expanded into the two
+    /// probe hashes with SplitMix64. Query it with [`Self::contains_hash`].
+    pub fn insert_hash(&mut self, hash: u64) {
+        let (h1, h2) = Self::raw_hash_pair(hash);
+        self.insert_pair(h1, h2);
     }
 
     /// Returns `true` if the item is possibly in the set.
@@ -190,16 +326,43 @@ impl BloomFilter {
     /// `false` means definitely not present.
     pub fn contains<T: Hash>(&self, item: &T) -> bool {
         let (h1, h2) = self.hash_pair(item);
+        self.contains_pair(h1, h2)
+    }
 
-        let mut probe = h1;
-        for _ in 0..self.num_hashes {
-            let bit_index = (probe as usize) % self.bit_len;
-            if !self.is_bit_set(bit_index) {
-                return false;
+    /// Returns `true` if a hash added with [`Self::insert_hash`] is possibly
+    /// in the set.
+    ///
+    /// `false` means definitely not present.
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        let (h1, h2) = Self::raw_hash_pair(hash);
+        self.contains_pair(h1, h2)
+    }
+
+    /// Answers [`Self::contains`] for every item, in input order.
+    ///
+    /// All items are hashed before any bit is probed, so the probe loop runs
+    /// over precomputed hash pairs.
+    pub fn contains_many<T: Hash>(&self, items: &[T]) -> Vec<bool> {
+        let pairs: Vec<(u64, u64)> = items.iter().map(|item| self.hash_pair(item)).collect();
+        pairs
+            .into_iter()
+            .map(|(h1, h2)| self.contains_pair(h1, h2))
+            .collect()
+    }
+
+    /// Answers [`Self::contains`] for every item as a packed bitmap.
+    ///
+    /// Bit `i % 64` of word `i / 64` is set when `items[i]` is possibly in the
+    /// set. The result has `ceil(items.len() / 64)` words, and bits past the
+    /// last item are zero.
+    pub fn contains_many_bits<T: Hash>(&self, items: &[T]) -> Vec<u64> {
+        let mut bits = vec![0_u64; items.len().div_ceil(64)];
+        for (index, present) in self.contains_many(items).into_iter().enumerate() {
+            if present {
+                bits[index / 64] |= 1 << (index % 64);
             }
-            probe = probe.wrapping_add(h2);
         }
-        true
+        bits
     }
 
     /// Clears all bits and resets the insert counter.
@@ -208,15 +371,63 @@ impl BloomFilter {
         self.inserted_items = 0;
     }
 
+    /// Overwrites the bitmap with `words`, reusing the existing allocation.
+    ///
+    /// `words` must have the layout returned by [`Self::words`] for a filter
+    /// with the same `bit_len`, and must come from a filter whose hasher and
+    /// `num_hashes` match this one for membership answers to be meaningful.
+    /// The buffer carries no operation count, so the raw insert count is lost.
+    /// [`Self::inserted_items`] is replaced by [`Self::estimated_cardinality`]
+    /// of the loaded bits, rounded up, which is zero exactly when the bitmap
+    /// is empty. Later inserts and merges add to that estimate, so the counter
+    /// stays an estimate until [`Self::clear`] resets it.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] without changing the filter
+    /// when the word count differs or a bit at or beyond `bit_len` is set.
+    pub fn reset_to(&mut self, words: &[u64]) -> Result<(), SketchError> {
+        if words.len() != self.words.len() {
+            return Err(SketchError::InvalidParameter(
+                "word count must match the filter's bit length",
+            ));
+        }
+        let tail_bits = self.bit_len % 64;
+        if tail_bits != 0 && words[words.len() - 1] >> tail_bits != 0 {
+            return Err(SketchError::InvalidParameter(
+                "bits beyond bit_len must be zero",
+            ));
+        }
+
+        self.words.copy_from_slice(words);
+        let set_bits = self.set_bits();
+        self.inserted_items = if set_bits == 0 {
+            0
+        } else {
+            self.cardinality_for_set_bits(set_bits).ceil().max(1.0) as u64
+        };
+        Ok(())
+    }
+
+    /// Returns `true` when [`Self::merge`] accepts `other`, which requires
+    /// equal bit length and hash count.
+    ///
+    /// Hashers cannot be compared, so filters built with differently keyed
+    /// hashers of the same type are reported as compatible.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.bit_len == other.bit_len && self.num_hashes == other.num_hashes
+    }
+
     /// Merges another filter into this one by bitwise OR.
     ///
+    /// Both filters must hash identically, for example by using the same
+    /// keyed hasher. Only the dimensions can be checked; merging filters with
+    /// differently keyed hashers produces a meaningless bitmap.
+    ///
     /// # Errors
     /// Returns [`SketchError::IncompatibleSketches`] when dimensions mismatch.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
-        if self.bit_len != other.bit_len || self.num_hashes != other.num_hashes {
-            return Err(SketchError::IncompatibleSketches(
-                "bit_len and num_hashes must match for merge",
-            ));
+        if !self.compatible(other) {
+            return Err(SketchError::IncompatibleSketches(INCOMPATIBLE_FILTERS));
         }
 
         for (left, right) in self.words.iter_mut().zip(other.words.iter()) {
@@ -226,11 +437,162 @@ impl BloomFilter {
         Ok(())
     }
 
+    /// Returns a new filter containing the bitwise OR of both filters.
+    ///
+    /// Neither input is modified. The result's insert counter is the
+    /// saturating sum of both counters, as with [`Self::merge`].
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when dimensions mismatch.
+    pub fn union(&self, other: &Self) -> Result<Self, SketchError>
+    where
+        S: Clone,
+    {
+        let mut union = self.clone();
+        union.merge(other)?;
+        Ok(union)
+    }
+
+    /// Estimates Jaccard similarity from the bit overlap of two filters.
+    ///
+    /// Each cardinality is estimated with the Swamidass-Baldi formula
+    /// `n = -(m / k) * ln(1 - X / m)`, where `X` is the number of set bits.
+    /// The union uses the bits of `a | b`, which is exactly the filter that
+    /// inserting both sets would produce. The intersection follows by
+    /// inclusion-exclusion and the ratio is clamped to `[0, 1]`; two empty
+    /// filters have Jaccard `1.0`.
+    ///
+    /// The estimate is biased and noisy. Collisions make every cardinality
+    /// estimate a random variable, and the subtraction amplifies that noise
+    /// when the intersection is small relative to the union. Accuracy also
+    /// degrades as the filters fill: a saturated bitmap is treated as having
+    /// one unset bit, which caps the cardinality estimate. Prefer
+    /// [`crate::minhash::MinHash`] when similarity is the primary workload.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when dimensions mismatch.
+    pub fn estimated_jaccard(&self, other: &Self) -> Result<f64, SketchError> {
+        if !self.compatible(other) {
+            return Err(SketchError::IncompatibleSketches(INCOMPATIBLE_FILTERS));
+        }
+
+        let union_bits = self
+            .words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(left, right)| (left | right).count_ones() as usize)
+            .sum();
+        let estimates = inclusion_exclusion_estimates(
+            self.cardinality_for_set_bits(self.set_bits()),
+            self.cardinality_for_set_bits(other.set_bits()),
+            self.cardinality_for_set_bits(union_bits),
+        );
+        Ok(estimates.jaccard)
+    }
+
+    /// Estimates the number of distinct inserted items from the fraction of
+    /// set bits.
+    ///
+    /// With `m` bits, `k` probes, and `X` set bits, this is the
+    /// Swamidass-Baldi estimate `-(m / k) * ln(1 - X / m)`. Repeated
+    /// insertions set no new bits, so unlike [`Self::inserted_items`] it
+    /// counts distinct items. A saturated bitmap is treated as having one
+    /// unset bit, so the result stays finite but is no longer informative.
+    pub fn estimated_cardinality(&self) -> f64 {
+        let set_bits = self.set_bits();
+        if set_bits == 0 {
+            return 0.0;
+        }
+        self.cardinality_for_set_bits(set_bits)
+    }
+
+    /// Estimates the current false-positive rate from the bitmap load.
+    ///
+    /// An absent item tests positive when all `k` of its probes land on set
+    /// bits, so this is `fill_ratio^k`. Unlike the rate requested at
+    /// construction, it reflects the items actually inserted, including
+    /// any overload beyond the sizing.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        self.fill_ratio().powf(self.num_hashes as f64)
+    }
+
+    /// Returns a soft membership score: `0.0` when the item is definitely
+    /// absent, otherwise `1 - estimated_false_positive_rate()`.
+    ///
+    /// The positive score is the probability that an absent item would have
+    /// tested negative. Every positive from one filter scores the same, so the
+    /// score separates positives only across filters of different load, for
+    /// example when ranking candidates found in several shards. It is not the
+    /// probability that a positive item is present. That posterior also
+    /// depends on the fraction of queried items that are present, and it
+    /// falls far below this score when most queries are for absent items.
+    pub fn membership_confidence<T: Hash>(&self, item: &T) -> f64 {
+        if self.contains(item) {
+            1.0 - self.estimated_false_positive_rate()
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the number of set bits in the backing bitmap.
+    fn set_bits(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Estimates the number of distinct inserted items from a set-bit count.
+    fn cardinality_for_set_bits(&self, set_bits: usize) -> f64 {
+        let bit_len = self.bit_len as f64;
+        // A saturated bitmap has an unbounded estimate; treat it as having one
+        // unset bit so the result remains finite.
+        let set_bits = set_bits.min(self.bit_len.saturating_sub(1)) as f64;
+        -(bit_len / self.num_hashes as f64) * (1.0 - set_bits / bit_len).ln()
+    }
+
+    /// Sets the `k` probe bits generated from one hash pair.
+    fn insert_pair(&mut self, h1: u64, h2: u64) {
+        let mut probe = h1;
+        for _ in 0..self.num_hashes {
+            let bit_index = (probe as usize) % self.bit_len;
+            self.set_bit(bit_index);
+            probe = probe.wrapping_add(h2);
+        }
+
+        self.inserted_items = self.inserted_items.saturating_add(1);
+    }
+
+    /// Returns `true` when all `k` probe bits of one hash pair are set.
+    fn contains_pair(&self, h1: u64, h2: u64) -> bool {
+        let mut probe = h1;
+        for _ in 0..self.num_hashes {
+            let bit_index = (probe as usize) % self.bit_len;
+            if !self.is_bit_set(bit_index) {
+                return false;
+            }
+            probe = probe.wrapping_add(h2);
+        }
+        true
+    }
+
     /// Returns two independent hashes for Kirsch-Mitzenmacher double hashing.
     fn hash_pair<T: Hash>(&self, item: &T) -> (u64, u64) {
-        let first = seeded_hash64(item, HASH_SEED_A);
-        let second = seeded_hash64(item, HASH_SEED_B) | 1;
-        (first, second)
+        let hash_with = |domain: u64| {
+            let mut hasher = self.hasher.build_hasher();
+            domain.hash(&mut hasher);
+            item.hash(&mut hasher);
+            hasher.finish()
+        };
+        (hash_with(HASH_SEED_A), hash_with(HASH_SEED_B) | 1)
+    }
+
+    /// Expands a caller-supplied hash into a double-hashing pair.
+    fn raw_hash_pair(hash: u64) -> (u64, u64) {
+        (
+            splitmix64(hash ^ HASH_SEED_A),
+            splitmix64(hash ^ HASH_SEED_B) | 1,
+        )
     }
 
     /// Sets one bit in the backing bitmap.
@@ -248,9 +610,189 @@ impl BloomFilter {
     }
 }
 
+/// Builder for [`BloomFilter`] configuration.
+///
+/// The bit length comes either from [`Self::bit_len`] or from
+/// [`Self::expected_items`] together with [`Self::false_positive_rate`], using
+/// [`BloomFilter::optimal_bit_len`]. The hash count comes either from
+/// [`Self::num_hashes`] or from the resolved bit length and expected items,
+/// using [`BloomFilter::optimal_num_hashes`]. Items are hashed with
+/// [`DefaultBloomHasher`] unless [`Self::hasher`] supplies another hasher.
+///
+/// # Example
+/// ```rust
+/// use sketches::bloom_filter::BloomFilter;
+///
+/// let filter = BloomFilter::builder()
+///     .expected_items(1_000)
+///     .false_positive_rate(0.01)
+///     .build()
+///     .unwrap();
+/// assert_eq!(filter.bit_len(), BloomFilter::new(1_000, 0.01).unwrap().bit_len());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BloomFilterBuilder<S = DefaultBloomHasher> {
+    expected_items: Option<usize>,
+    false_positive_rate: Option<f64>,
+    bit_len: Option<usize>,
+    num_hashes: Option<u32>,
+    hasher: S,
+}
+
+impl BloomFilterBuilder {
+    /// Creates a builder with no parameters set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: BuildHasher + Clone> BloomFilterBuilder<S> {
+    /// Sets the hasher the built filter uses to derive probe positions.
+    pub fn hasher<T: BuildHasher + Clone>(self, hasher: T) -> BloomFilterBuilder<T> {
+        BloomFilterBuilder {
+            expected_items: self.expected_items,
+            false_positive_rate: self.false_positive_rate,
+            bit_len: self.bit_len,
+            num_hashes: self.num_hashes,
+            hasher,
+        }
+    }
+
+    /// Sets the expected number of distinct items.
+    pub fn expected_items(mut self, expected_items: usize) -> Self {
+        self.expected_items = Some(expected_items);
+        self
+    }
+
+    /// Sets the target false-positive rate used to derive the bit length.
+    pub fn false_positive_rate(mut self, false_positive_rate: f64) -> Self {
+        self.false_positive_rate = Some(false_positive_rate);
+        self
+    }
+
+    /// Sets an explicit number of addressable bits.
+    pub fn bit_len(mut self, bit_len: usize) -> Self {
+        self.bit_len = Some(bit_len);
+        self
+    }
+
+    /// Sets an explicit number of hash probes per inserted key.
+    pub fn num_hashes(mut self, num_hashes: u32) -> Self {
+        self.num_hashes = Some(num_hashes);
+        self
+    }
+
+    /// Resolves derived parameters and builds the filter.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when the bit length or hash
+    /// count cannot be resolved, when an explicit bit length is combined with
+    /// a false-positive rate that would size it differently, or when any
+    /// supplied value is invalid.
+    pub fn build(&self) -> Result<BloomFilter<S>, SketchError> {
+        let derived_bit_len = match self.false_positive_rate {
+            Some(false_positive_rate) => {
+                let expected_items = self.expected_items.ok_or(SketchError::InvalidParameter(
+                    "false_positive_rate requires expected_items",
+                ))?;
+                Some(BloomFilter::optimal_bit_len(
+                    expected_items,
+                    false_positive_rate,
+                )?)
+            }
+            None => None,
+        };
+        let bit_len = match (self.bit_len, derived_bit_len) {
+            (Some(bit_len), Some(derived)) if bit_len != derived => {
+                return Err(SketchError::InvalidParameter(
+                    "bit_len conflicts with the size derived from false_positive_rate",
+                ));
+            }
+            (Some(bit_len), _) | (None, Some(bit_len)) => bit_len,
+            (None, None) => {
+                return Err(SketchError::InvalidParameter(
+                    "bit_len or false_positive_rate with expected_items is required",
+                ));
+            }
+        };
+
+        let num_hashes = match (self.num_hashes, self.expected_items) {
+            (Some(num_hashes), _) => num_hashes,
+            (None, Some(expected_items)) => {
+                BloomFilter::optimal_num_hashes(bit_len, expected_items)?
+            }
+            (None, None) => {
+                return Err(SketchError::InvalidParameter(
+                    "num_hashes or expected_items is required",
+                ));
+            }
+        };
+
+        BloomFilter::with_size_and_hasher(bit_len, num_hashes, self.hasher.clone())
+    }
+}
+
+impl<S: BuildHasher> ApproxCardinality for BloomFilter<S> {
+    fn estimated_cardinality(&self) -> f64 {
+        self.estimated_cardinality()
+    }
+}
+
+impl<S: BuildHasher> JacardIndex for BloomFilter<S> {
+    fn jaccard_index(&self, other: &Self) -> Result<f64, SketchError> {
+        self.estimated_jaccard(other)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::BloomFilter;
+    use core::hash::BuildHasher;
+
+    use siphasher::sip::SipHasher13;
+
+    use super::{BloomFilter, BloomFilterBuilder};
+
+    #[test]
+    fn insert_if_absent_reports_first_insertions() {
+        let mut filter = BloomFilter::new(100, 0.01).unwrap();
+        assert!(filter.insert_if_absent(&"alice"));
+        assert!(!filter.insert_if_absent(&"alice"));
+        assert!(filter.contains(&"alice"));
+        assert_eq!(filter.inserted_items(), 1);
+    }
+
+    #[test]
+    fn dedup_suppresses_repeats_and_few_first_occurrences() {
+        let stream = (0_u64..3).flat_map(|_| 0_u64..5_000);
+        let filter = BloomFilter::new(5_000, 0.01).unwrap();
+        let unique: Vec<u64> = filter.dedup(stream).collect();
+
+        let mut seen = std::collections::HashSet::new();
+        assert!(unique.iter().all(|value| seen.insert(*value)));
+        // The false-suppression rate stays below the configured rate.
+        assert!(unique.len() > 4_950, "kept {}", unique.len());
+    }
+
+    #[test]
+    fn bulk_contains_matches_single_queries() {
+        let mut filter = BloomFilter::new(1_000, 0.01).unwrap();
+        for value in (0_u64..1_000).step_by(3) {
+            filter.insert(&value);
+        }
+
+        let queries: Vec<u64> = (0..150).collect();
+        let expected: Vec<bool> = queries.iter().map(|value| filter.contains(value)).collect();
+        assert_eq!(filter.contains_many(&queries), expected);
+
+        let bits = filter.contains_many_bits(&queries);
+        assert_eq!(bits.len(), 3);
+        for (index, present) in expected.iter().enumerate() {
+            assert_eq!(bits[index / 64] >> (index % 64) & 1 == 1, *present);
+        }
+        assert_eq!(bits[2] >> (150 - 128), 0);
+        assert!(filter.contains_many::<u64>(&[]).is_empty());
+        assert!(filter.contains_many_bits::<u64>(&[]).is_empty());
+    }
 
     #[test]
     fn constructor_from_rate_creates_positive_shape() {
@@ -276,6 +818,97 @@ mod tests {
         assert!(BloomFilter::optimal_num_hashes(100, 0).is_err());
     }
 
+    #[test]
+    fn builder_matches_the_equivalent_constructors() {
+        let built = BloomFilter::builder()
+            .expected_items(1_000)
+            .false_positive_rate(0.01)
+            .build()
+            .unwrap();
+        let direct = BloomFilter::new(1_000, 0.01).unwrap();
+        assert_eq!(built.bit_len(), direct.bit_len());
+        assert_eq!(built.num_hashes(), direct.num_hashes());
+
+        let sized = BloomFilterBuilder::new()
+            .bit_len(4_096)
+            .num_hashes(5)
+            .build()
+            .unwrap();
+        assert_eq!(sized.bit_len(), 4_096);
+        assert_eq!(sized.num_hashes(), 5);
+
+        let derived_hashes = BloomFilter::builder()
+            .bit_len(4_096)
+            .expected_items(512)
+            .build()
+            .unwrap();
+        assert_eq!(
+            derived_hashes.num_hashes(),
+            BloomFilter::optimal_num_hashes(4_096, 512).unwrap()
+        );
+
+        let overridden_hashes = BloomFilter::builder()
+            .expected_items(1_000)
+            .false_positive_rate(0.01)
+            .num_hashes(3)
+            .build()
+            .unwrap();
+        assert_eq!(overridden_hashes.bit_len(), direct.bit_len());
+        assert_eq!(overridden_hashes.num_hashes(), 3);
+    }
+
+    #[test]
+    fn builder_rejects_missing_conflicting_or_invalid_parameters() {
+        assert!(BloomFilter::builder().build().is_err());
+        assert!(BloomFilter::builder().bit_len(64).build().is_err());
+        assert!(
+            BloomFilter::builder()
+                .false_positive_rate(0.01)
+                .num_hashes(3)
+                .build()
+                .is_err()
+        );
+        assert!(
+            BloomFilter::builder()
+                .expected_items(100)
+                .false_positive_rate(0.01)
+                .bit_len(1_024)
+                .build()
+                .is_err()
+        );
+        // A bit length that agrees with the derived size is not a conflict.
+        let agreeing_bit_len = BloomFilter::optimal_bit_len(100, 0.01).unwrap();
+        let agreeing = BloomFilter::builder()
+            .expected_items(100)
+            .false_positive_rate(0.01)
+            .bit_len(agreeing_bit_len)
+            .build()
+            .unwrap();
+        assert_eq!(agreeing.bit_len(), agreeing_bit_len);
+        assert!(
+            BloomFilter::builder()
+                .false_positive_rate(0.01)
+                .bit_len(agreeing_bit_len)
+                .num_hashes(3)
+                .build()
+                .is_err()
+        );
+        assert!(
+            BloomFilter::builder()
+                .bit_len(0)
+                .num_hashes(3)
+                .build()
+                .is_err()
+        );
+        assert!(
+            BloomFilter::builder()
+                .expected_items(100)
+                .false_positive_rate(1.0)
+                .build()
+                .is_err()
+        );
+    }
+
     #[test]
     fn inserted_elements_are_always_reported_present() {
         let mut filter = BloomFilter::new(5_000, 0.01).unwrap();
@@ -309,6 +942,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fill_ratio_rises_monotonically_within_unit_interval() {
+        let mut filter = BloomFilter::new(1_000, 0.01).unwrap();
+        assert_eq!(filter.fill_ratio(), 0.0);
+
+        let mut previous = 0.0;
+        for value in 0_u64..5_000 {
+            filter.insert(&value);
+            let ratio = filter.fill_ratio();
+            assert!(ratio >= previous && ratio <= 1.0, "ratio={ratio}");
+            previous = ratio;
+        }
+        assert!(previous > 0.9, "overloaded filter ratio={previous}");
+
+        let mut tiny = BloomFilter::with_size(3, 2).unwrap();
+        tiny.insert(&"a");
+        assert!(tiny.fill_ratio() > 0.0 && tiny.fill_ratio() <= 1.0);
+    }
+
+    #[test]
+    fn membership_confidence_scores_positives_by_the_current_load() {
+        let mut filter = BloomFilter::new(1_000, 0.01).unwrap();
+        assert_eq!(filter.estimated_false_positive_rate(), 0.0);
+        for value in 0_u64..1_000 {
+            filter.insert(&value);
+        }
+
+        let fp_rate = filter.estimated_false_positive_rate();
+        assert!((0.005..0.02).contains(&fp_rate), "fp_rate={fp_rate}");
+        assert_eq!(
+            fp_rate,
+            filter.fill_ratio().powf(filter.num_hashes() as f64)
+        );
+        for value in 0_u64..1_000 {
+            assert_eq!(filter.membership_confidence(&value), 1.0 - fp_rate);
+        }
+        let absent = (1_000_u64..100_000)
+            .find(|value| !filter.contains(value))
+            .unwrap();
+        assert_eq!(filter.membership_confidence(&absent), 0.0);
+
+        // Overloading the filter lowers the score of every positive.
+        for value in 1_000_u64..3_000 {
+            filter.insert(&value);
+        }
+        assert!(filter.membership_confidence(&0_u64) < 1.0 - fp_rate);
+    }
+
     #[test]
     fn clear_resets_filter_state() {
         let mut filter = BloomFilter::new(1_000, 0.01).unwrap();
@@ -338,6 +1019,56 @@ mod tests {
         assert_eq!(left.inserted_items(), 2);
     }
 
+    #[test]
+    fn union_returns_a_combined_filter_without_mutating_inputs() {
+        let mut left = BloomFilter::new(2_000, 0.01).unwrap();
+        let mut right = BloomFilter::new(2_000, 0.01).unwrap();
+        left.insert(&"left-only");
+        right.insert(&"right-only");
+        let left_words = left.words.clone();
+        let right_words = right.words.clone();
+
+        let union = left.union(&right).unwrap();
+        assert!(union.contains(&"left-only"));
+        assert!(union.contains(&"right-only"));
+        assert_eq!(union.inserted_items(), 2);
+
+        assert_eq!(left.words, left_words);
+        assert_eq!(right.words, right_words);
+        assert_eq!(left.inserted_items(), 1);
+        assert_eq!(right.inserted_items(), 1);
+
+        let incompatible = BloomFilter::with_size(512, 3).unwrap();
+        assert!(left.union(&incompatible).is_err());
+    }
+
+    #[test]
+    fn estimated_jaccard_tracks_known_overlap() {
+        let mut left = BloomFilter::new(2_000, 0.01).unwrap();
+        let mut right = BloomFilter::new(2_000, 0.01).unwrap();
+        for value in 0_u64..1_000 {
+            left.insert(&value);
+        }
+        for value in 500_u64..1_500 {
+            right.insert(&value);
+        }
+
+        let estimate = left.estimated_jaccard(&right).unwrap();
+        let exact = 500.0 / 1_500.0;
+        assert!(
+            (estimate - exact).abs() < 0.05,
+            "estimate={estimate} exact={exact}"
+        );
+        assert_eq!(left.estimated_jaccard(&left).unwrap(), 1.0);
+
+        let empty = BloomFilter::new(2_000, 0.01).unwrap();
+        assert_eq!(empty.estimated_jaccard(&empty).unwrap(), 1.0);
+        assert_eq!(left.estimated_jaccard(&empty).unwrap(), 0.0);
+
+        let incompatible = BloomFilter::with_size(512, 3).unwrap();
+        assert!(left.estimated_jaccard(&incompatible).is_err());
+    }
+
     #[test]
     fn merge_rejects_incompatible_filters() {
         let mut left = BloomFilter::with_size(256, 3).unwrap();
@@ -345,6 +1076,144 @@ mod tests {
         assert!(left.merge(&right).is_err());
     }
 
+    #[derive(Debug, Clone)]
+    struct KeyedSip(u64, u64);
+
+    impl BuildHasher for KeyedSip {
+        type Hasher = SipHasher13;
+
+        fn build_hasher(&self) -> SipHasher13 {
+            SipHasher13::new_with_keys(self.0, self.1)
+        }
+    }
+
+    #[test]
+    fn builder_combines_derived_sizing_with_a_custom_hasher() {
+        let fill = || {
+            let mut filter = BloomFilter::builder()
+                .expected_items(1_000)
+                .false_positive_rate(0.01)
+                .hasher(KeyedSip(7, 11))
+                .build()
+                .unwrap();
+            for value in 0_u64..100 {
+                filter.insert(&value);
+            }
+            filter
+        };
+
+        let first = fill();
+        assert_eq!(first.words(), fill().words());
+        assert_eq!(first.hasher().0, 7);
+
+        let mut direct = BloomFilter::with_hasher(1_000, 0.01, KeyedSip(7, 11)).unwrap();
+        for value in 0_u64..100 {
+            direct.insert(&value);
+        }
+        assert_eq!(first.bit_len(), direct.bit_len());
+        assert_eq!(first.words(), direct.words());
+    }
+
+    #[test]
+    fn custom_hashers_give_reproducible_bitmaps() {
+        let fill = |hasher: KeyedSip| {
+            let mut filter = BloomFilter::with_size_and_hasher(1_024, 5, hasher).unwrap();
+            for value in 0_u64..100 {
+                filter.insert(&value);
+            }
+            filter
+        };
+
+        let first = fill(KeyedSip(7, 11));
+        let second = fill(KeyedSip(7, 11));
+        assert_eq!(first.words, second.words);
+        assert!((0_u64..100).all(|value| first.contains(&value)));
+        assert_ne!(first.words, fill(KeyedSip(8, 11)).words);
+
+        // The default hasher is the same zero-keyed SipHash-1-3.
+        let mut default = BloomFilter::with_size(1_024, 5).unwrap();
+        for value in 0_u64..100 {
+            default.insert(&value);
+        }
+        assert_eq!(default.words, fill(KeyedSip(0, 0)).words);
+
+        let mut merged = first.clone();
+        merged.merge(&second).unwrap();
+        assert_eq!(merged.words, first.words);
+
+        let sized = BloomFilter::with_hasher(1_000, 0.01, KeyedSip(1, 2)).unwrap();
+        let reference = BloomFilter::new(1_000, 0.01).unwrap();
+        assert_eq!(sized.bit_len(), reference.bit_len());
+        assert_eq!(sized.num_hashes(), reference.num_hashes());
+    }
+
+    #[test]
+    fn std_random_state_is_a_valid_hasher() {
+        let state = std::hash::RandomState::new();
+        let mut left = BloomFilter::with_hasher(1_000, 0.01, state.clone()).unwrap();
+        let mut right = BloomFilter::with_hasher(1_000, 0.01, state).unwrap();
+        left.insert(&"alice");
+        right.insert(&"bob");
+        let union = left.union(&right).unwrap();
+        assert!(union.contains(&"alice") && union.contains(&"bob"));
+    }
+
+    #[test]
+    fn raw_hash_path_finds_inserted_hashes() {
+        let mut filter = BloomFilter::new(2_000, 0.01).unwrap();
+        for value in 0_u64..2_000 {
+            filter.insert_hash(crate::splitmix64(value));
+        }
+        assert_eq!(filter.inserted_items(), 2_000);
+        for value in 0_u64..2_000 {
+            assert!(filter.contains_hash(crate::splitmix64(value)));
+        }
+
+        let false_positives = (10_000_u64..14_000)
+            .filter(|&value| filter.contains_hash(crate::splitmix64(value)))
+            .count();
+        assert!(false_positives <= 120, "false_positives={false_positives}");
+
+        let mut empty = BloomFilter::new(100, 0.01).unwrap();
+        assert!(!empty.contains_hash(42));
+        empty.insert_hash(42);
+        assert!(empty.contains_hash(42));
+        assert!(!empty.contains_hash(43));
+    }
+
+    #[test]
+    fn reset_to_copies_a_precomputed_bitmap() {
+        let mut source = BloomFilter::new(1_000, 0.01).unwrap();
+        for value in 0_u64..500 {
+            source.insert(&value);
+        }
+        let snapshot = source.words().to_vec();
+
+        let mut ring_slot = BloomFilter::new(1_000, 0.01).unwrap();
+        ring_slot.insert(&"stale");
+        ring_slot.reset_to(&snapshot).unwrap();
+        assert_eq!(ring_slot.words(), source.words());
+        for value in 0_u64..2_000 {
+            assert_eq!(ring_slot.contains(&value), source.contains(&value));
+        }
+        assert!(!ring_slot.is_empty());
+        assert!(ring_slot.inserted_items().abs_diff(500) < 50);
+        assert_eq!(
+            ring_slot.inserted_items(),
+            ring_slot.estimated_cardinality().ceil() as u64
+        );
+
+        ring_slot.reset_to(&vec![0; snapshot.len()]).unwrap();
+        assert!(ring_slot.is_empty());
+        assert!(!ring_slot.contains(&1_u64));
+
+        let mut odd = BloomFilter::with_size(100, 3).unwrap();
+        assert!(odd.reset_to(&[0]).is_err());
+        assert!(odd.reset_to(&[0, 1 << 36]).is_err());
+        odd.reset_to(&[1, 1 << 35]).unwrap();
+        assert_eq!(odd.inserted_items(), 1);
+    }
+
     #[test]
     fn insert_counter_tracks_operations() {
         let mut filter = BloomFilter::new(100, 0.01).unwrap();
@@ -352,4 +1221,20 @@ mod tests {
         filter.insert(&"same");
         assert_eq!(filter.inserted_items(), 2);
     }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let filters = [
+            BloomFilter::with_size(1_024, 4).unwrap(),
+            BloomFilter::with_size(1_024, 5).unwrap(),
+            BloomFilter::with_size(2_048, 4).unwrap(),
+            BloomFilter::with_size(1_024, 4).unwrap(),
+        ];
+        for left in &filters {
+            for right in &filters {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
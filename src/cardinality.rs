@@ -0,0 +1,108 @@
+// MIT License
+//
+// Copyright (c) 2026 Raja Lehtihet & Wael El Oraiby
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+//! Approximate distinct-count trait shared by membership filters.
+//!
+//! [`crate::bloom_filter::BloomFilter`] and
+//! [`crate::cuckoo_filter::CuckooFilter`] are sized for membership queries,
+//! but their occupancy also yields a rough estimate of how many distinct items
+//! were inserted. The estimates come from different models, a set-bit count
+//! for Bloom filters and distinct stored fingerprints for cuckoo filters, and
+//! both degrade as the filter approaches saturation. Use a dedicated
+//! cardinality sketch such as [`crate::hyperloglog::HyperLogLog`] when the
+//! count itself matters.
+
+/// Common API for structures that can estimate their number of distinct
+/// inserted items.
+///
+/// # Example
+/// ```rust
+/// use sketches::bloom_filter::BloomFilter;
+/// use sketches::cardinality::ApproxCardinality;
+/// use sketches::cuckoo_filter::CuckooFilter;
+///
+/// fn report<F: ApproxCardinality>(filter: &F) -> f64 {
+///     filter.estimated_cardinality()
+/// }
+///
+/// let mut bloom = BloomFilter::new(2_000, 0.01).unwrap();
+/// let mut cuckoo = CuckooFilter::new(2_000, 0.01).unwrap();
+/// for value in 0_u64..1_000 {
+///     bloom.insert(&value);
+///     cuckoo.insert(&value);
+/// }
+///
+/// assert!((report(&bloom) - 1_000.0).abs() < 50.0);
+/// assert!((report(&cuckoo) - 1_000.0).abs() < 50.0);
+/// ```
+pub trait ApproxCardinality {
+    /// Returns the estimated number of distinct inserted items.
+    fn estimated_cardinality(&self) -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bloom_filter::BloomFilter, cardinality::ApproxCardinality, cuckoo_filter::CuckooFilter,
+    };
+
+    fn insert_distinct<F: ApproxCardinality>(
+        filter: &mut F,
+        insert: impl Fn(&mut F, u64),
+        items: u64,
+    ) -> f64 {
+        for value in 0..items {
+            insert(filter, value);
+            // Repeats must not change a distinct-count estimate.
+            insert(filter, value);
+        }
+        filter.estimated_cardinality()
+    }
+
+    // Exercises both filters through the shared trait rather than their
+    // inherent methods, guarding the trait delegation.
+    #[test]
+    fn trait_api_estimates_distinct_insertions_for_both_filters() {
+        let items = 5_000;
+        let mut bloom = BloomFilter::new(10_000, 0.01).unwrap();
+        let mut cuckoo = CuckooFilter::new(10_000, 0.01).unwrap();
+        assert_eq!(ApproxCardinality::estimated_cardinality(&bloom), 0.0);
+        assert_eq!(ApproxCardinality::estimated_cardinality(&cuckoo), 0.0);
+
+        let bloom_estimate =
+            insert_distinct(&mut bloom, |filter, value| filter.insert(&value), items);
+        let cuckoo_estimate = insert_distinct(
+            &mut cuckoo,
+            |filter, value| {
+                filter.insert(&value);
+            },
+            items,
+        );
+
+        for estimate in [bloom_estimate, cuckoo_estimate] {
+            assert!(
+                (estimate - items as f64).abs() < 0.03 * items as f64,
+                "estimate = {estimate}"
+            );
+        }
+    }
+}
@@ -42,6 +42,13 @@
 //! the Chernoff/KL majority bound to the median. A simultaneous guarantee for
 //! `q` predetermined queries requires constructing with `delta / q`.
 //!
+//! [`CountSketch::estimate_with_bound`] reports this guarantee for a concrete
+//! sketch. It recovers `epsilon = sqrt(8 / width)` and estimates `||f||_2`
+//! from the table itself: each row's sum of squared counters is an unbiased
+//! estimate of `||f||_2^2`, and the median over rows is used. The estimate
+//! includes the queried item's own mass, so the band is slightly conservative,
+//! and cancelled updates do not widen it.
+//!
 //! The integer row functions use Thorup's [strongly universal
 //! multiply-shift][multiply-shift] construction. The caller-owned seed is
 //! expanded deterministically into the row coefficients, so sketches are
@@ -63,21 +70,53 @@
 //! repeated across applications. Fixed seeds are useful for tests and
 //! reproducible pipelines; they are not secret keys.
 //!
+//! # Serialization
+//!
+//! With the `serde` feature enabled, a sketch serializes its dimensions,
+//! family seed, counters, net total count, and update magnitude. Row functions and fingerprint
+//! keys are derived from the seed and are rebuilt on deserialization, so a
+//! reloaded sketch uses exactly the original hash family and remains mergeable
+//! with it.
+//! Deserialization rejects invalid dimensions and a counter table whose length
+//! is not `width * depth`.
+//!
+//! [`CountSketch::to_bytes`] provides the same state without `serde`: the
+//! magic `b"SKCS"`, a version byte, then width, depth, seed, net total count,
+//! update magnitude, and the row-major counters, each as a little-endian
+//! 64-bit integer.
+//! [`CountSketch::from_bytes`] applies the same validation and also rejects
+//! truncated or trailing bytes.
+//!
 //! # Arithmetic
 //!
 //! Count Sketch is a linear sketch, so counters are never clamped. Every update
 //! and merge first checks all affected counters, then either commits exactly or
 //! returns [`SketchError::CounterOverflow`] without mutation. `i64::MIN` is
-//! excluded because its sign correction is not representable.
+//! excluded because its sign correction is not representable. The net total
+//! count and the update magnitude are auxiliary and saturate at their integer
+//! ranges instead of failing an update whose counters are representable.
+//!
+//! # Windows
+//!
+//! Counters are linear, so an expired window could in principle be subtracted,
+//! but only if its contents were kept. [`WindowedCountSketch`] keeps them as a
+//! second sketch: items go into the current window, and
+//! [`WindowedCountSketch::advance`] clears the previous window and makes it
+//! current. Estimates cover the current and previous windows together.
 //!
 //! [count-sketch-paper]: https://www.cs.yale.edu/homes/el327/datamining2011aFiles/FindingFrequentItemsInDataStreams.pdf
 //! [multiply-shift]: https://arxiv.org/abs/1504.06804
 
-use std::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use alloc::borrow::Cow;
+use alloc::{boxed::Box, vec::Vec};
+use core::hash::{Hash, Hasher};
 
 use siphasher::sip::SipHasher13;
 
-use crate::{SketchError, splitmix64};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::{SketchError, splitmix64, take_header, take_u64_le, take_usize_le};
 
 const WIDTH_NUMERATOR: f64 = 8.0;
 const DEPTH_DENOMINATOR: f64 = 0.826_678_573_184_467_9; // ln(16 / 7)
@@ -85,6 +124,8 @@ const SPLITMIX_INCREMENT: u64 = 0x9E37_79B9_7F4A_7C15;
 const FINGERPRINT_DOMAIN_A: u64 = 0x243F_6A88_85A3_08D3;
 const FINGERPRINT_DOMAIN_B: u64 = 0x1319_8A2E_0370_7344;
 const ROW_DOMAIN: u64 = 0xA409_3822_299F_31D0;
+const BYTES_MAGIC: [u8; 4] = *b"SKCS";
+const BYTES_VERSION: u8 = 1;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct RowHash {
@@ -118,6 +159,8 @@ pub struct CountSketch {
     rows: Box<[RowHash]>,
     family_seed: u64,
     fingerprint_keys: (u64, u64),
+    total_count: i64,
+    update_magnitude: u64,
 }
 
 impl CountSketch {
@@ -173,7 +216,7 @@ impl CountSketch {
                 "delta requires an unrepresentable depth",
             ))?;
         }
-        while (-(depth as f64) * DEPTH_DENOMINATOR / 2.0).exp() > delta {
+        while median_failure_bound(depth) > delta {
             depth = depth.checked_add(2).ok_or(SketchError::InvalidParameter(
                 "delta requires an unrepresentable depth",
             ))?;
@@ -240,9 +283,26 @@ impl CountSketch {
                 splitmix64(seed ^ FINGERPRINT_DOMAIN_A),
                 splitmix64(seed ^ FINGERPRINT_DOMAIN_B),
             ),
+            total_count: 0,
+            update_magnitude: 0,
         })
     }
 
+    /// Returns the net sum of all updates added to the sketch, saturated to
+    /// the `i64` range.
+    pub fn total_count(&self) -> i64 {
+        self.total_count
+    }
+
+    /// Returns the sum of the absolute weights of all updates, saturated to
+    /// the `u64` range.
+    ///
+    /// Unlike [`Self::total_count`], deletions add to this value instead of
+    /// cancelling earlier insertions.
+    pub fn update_magnitude(&self) -> u64 {
+        self.update_magnitude
+    }
+
     /// Returns the number of counters per row.
     pub fn width(&self) -> usize {
         self.width
@@ -253,6 +313,18 @@ impl CountSketch {
         self.rows.len()
     }
 
+    /// Returns the probability, under the guarantee in the
+    /// [module documentation](self), that one fixed query's estimate lies
+    /// within `epsilon * ||f without x||_2` of the true frequency.
+    ///
+    /// This is `1 - exp(-depth * ln(16 / 7) / 2)`, the complement of the
+    /// Chernoff bound on a majority of rows failing. It depends only on
+    /// [`Self::depth`] and is a lower bound; sketches built with
+    /// [`Self::new`] report at least `1 - delta`.
+    pub fn confidence(&self) -> f64 {
+        1.0 - median_failure_bound(self.depth())
+    }
+
     /// Returns the caller-provided hash-family seed.
     pub fn seed(&self) -> u64 {
         self.family_seed
@@ -264,7 +336,8 @@ impl CountSketch {
     /// # Errors
     ///
     /// Returns [`SketchError::CounterOverflow`] without changing the sketch if
-    /// the signed update or any resulting counter is not exactly representable.
+    /// the signed update or any resulting counter is not exactly
+    /// representable.
     pub fn add<T: Hash + ?Sized>(&mut self, item: &T, delta: i64) -> Result<(), SketchError> {
         let item_id = self.fingerprint(item);
         self.add_u64(item_id, delta)
@@ -279,7 +352,8 @@ impl CountSketch {
     /// # Errors
     ///
     /// Returns [`SketchError::CounterOverflow`] without changing the sketch if
-    /// the signed update or any resulting counter is not exactly representable.
+    /// the signed update or any resulting counter is not exactly
+    /// representable.
     pub fn add_u64(&mut self, item_id: u64, delta: i64) -> Result<(), SketchError> {
         if delta == 0 {
             return Ok(());
@@ -288,7 +362,6 @@ impl CountSketch {
         if delta == i64::MIN {
             return Err(SketchError::CounterOverflow);
         }
-
         // Rows occupy disjoint counter ranges. Check every destination before
         // mutating any of them so an error cannot leave a partial update.
         for row in 0..self.depth() {
@@ -306,6 +379,8 @@ impl CountSketch {
                 .checked_add(signed_delta)
                 .expect("preflight must prove that the counter update is representable");
         }
+        self.total_count = self.total_count.saturating_add(delta);
+        self.update_magnitude = self.update_magnitude.saturating_add(delta.unsigned_abs());
         Ok(())
     }
 
@@ -334,20 +409,183 @@ impl CountSketch {
 
     /// Returns the median estimate for a stable 64-bit item identifier.
     pub fn estimate_u64(&self, item_id: u64) -> i64 {
-        let mut estimates = Vec::with_capacity(self.depth());
-        for row in 0..self.depth() {
-            let (index, sign_is_positive) = self.location(row, item_id);
-            let counter = self.counters[index];
-            estimates.push(if sign_is_positive { counter } else { -counter });
-        }
-
+        let mut estimates = self.row_estimates(item_id);
         let middle = estimates.len() / 2;
         *estimates.select_nth_unstable(middle).1
     }
 
+    /// Returns a trimmed-mean frequency estimate for an item.
+    ///
+    /// This is an alternative to [`Self::estimate`]. The lowest and highest row
+    /// estimates are discarded and the remaining rows are averaged, rounding
+    /// to the nearest integer. A single heavy collision is trimmed away, as
+    /// with the median, and averaging the other rows lowers the variance of
+    /// the estimate when the collision noise comes from many small items.
+    /// Heavy items colliding in two or more rows can make it worse than the
+    /// median, and the median's `(epsilon, delta)` guarantee does not carry
+    /// over to this estimator. With depth one or three it equals
+    /// [`Self::estimate`].
+    pub fn estimate_robust<T: Hash + ?Sized>(&self, item: &T) -> i64 {
+        self.estimate_robust_u64(self.fingerprint(item))
+    }
+
+    /// Returns the trimmed-mean estimate for a stable 64-bit item identifier.
+    ///
+    /// See [`Self::estimate_robust`].
+    pub fn estimate_robust_u64(&self, item_id: u64) -> i64 {
+        let mut estimates = self.row_estimates(item_id);
+        if estimates.len() == 1 {
+            return estimates[0];
+        }
+
+        estimates.sort_unstable();
+        let kept = &estimates[1..estimates.len() - 1];
+
+        let sum: i128 = kept.iter().copied().map(i128::from).sum();
+        let count = kept.len() as i128;
+        // The mean of representable estimates lies between two of them.
+        (2 * sum + count).div_euclid(2 * count) as i64
+    }
+
+    /// Returns the estimated fraction of the net total count contributed by an
+    /// item.
+    ///
+    /// This is [`Self::estimate`] divided by [`Self::total_count`], or `0.0`
+    /// when the net total is zero. With deletions or collision noise the rate
+    /// can be negative or exceed one.
+    pub fn estimate_rate<T: Hash + ?Sized>(&self, item: &T) -> f64 {
+        if self.total_count == 0 {
+            return 0.0;
+        }
+        self.estimate(item) as f64 / self.total_count as f64
+    }
+
+    /// Returns `(estimate, half_width)` for an item.
+    ///
+    /// The half-width is `ceil(epsilon * ||f||_2)`, with `epsilon` recovered
+    /// from [`Self::width`] and `||f||_2` estimated from the counters as
+    /// described in the [module documentation](self). For a query fixed
+    /// independently of the seed, the true frequency lies in
+    /// `estimate - half_width..=estimate + half_width` except with
+    /// approximately the `delta` implied by [`Self::depth`].
+    ///
+    /// Unlike [`Self::estimate`], this scans the whole table and takes
+    /// `O(width * depth)` time.
+    pub fn estimate_with_bound<T: Hash + ?Sized>(&self, item: &T) -> (i64, i64) {
+        self.estimate_u64_with_bound(self.fingerprint(item))
+    }
+
+    /// Returns `(estimate, half_width)` for a stable 64-bit item identifier.
+    ///
+    /// See [`Self::estimate_with_bound`].
+    pub fn estimate_u64_with_bound(&self, item_id: u64) -> (i64, i64) {
+        let squared_norm = self.median_squared_row_norm();
+
+        let epsilon = (WIDTH_NUMERATOR / self.width as f64).sqrt();
+        // Float-to-integer casts saturate, so a huge band clamps to i64::MAX.
+        let half_width = (epsilon * squared_norm.sqrt()).ceil() as i64;
+        (self.estimate_u64(item_id), half_width)
+    }
+
+    /// Estimates how many other updates share the item's cell in each row.
+    ///
+    /// Returns [`Self::update_magnitude`] minus the magnitude of the item's
+    /// own estimate, divided by [`Self::width`] and rounded. With unit-weight
+    /// updates of distinct keys this is the expected number of other keys in
+    /// each of the item's cells. A repeated key or a weighted update counts
+    /// once per unit of weight, so the value bounds the number of distinct
+    /// colliding keys from above.
+    ///
+    /// [`Self::collision_variance`] measures the same collisions by their
+    /// squared mass instead.
+    pub fn collision_estimate<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        self.collision_estimate_u64(self.fingerprint(item))
+    }
+
+    /// Estimates the colliding updates for a stable 64-bit item identifier.
+    ///
+    /// See [`Self::collision_estimate`].
+    pub fn collision_estimate_u64(&self, item_id: u64) -> u64 {
+        let own = self.estimate_u64(item_id).unsigned_abs();
+        let others = self.update_magnitude.saturating_sub(own);
+        (others as f64 / self.width as f64).round() as u64
+    }
+
+    /// Estimates the variance of the collision noise in one row's counter
+    /// for an item.
+    ///
+    /// Other items hashed into the item's cell add their frequencies with
+    /// random signs, so the noise has variance equal to their squared mass
+    /// per cell. A row's sum of squared counters estimates `||f||_2^2`, so this
+    /// returns `(||f||_2^2 - estimate^2) / width`, with the squared norm taken
+    /// as the median over rows as in [`Self::estimate_with_bound`]. Its square
+    /// root is the typical error a single row contributes.
+    ///
+    /// The value counts colliding items only when every frequency is `±1`.
+    /// One colliding key of frequency `f` contributes `f^2 / width`, as much
+    /// as `f^2` unit keys.
+    ///
+    /// Like [`Self::estimate_with_bound`], this scans the whole table and
+    /// takes `O(width * depth)` time.
+    pub fn collision_variance<T: Hash + ?Sized>(&self, item: &T) -> f64 {
+        self.collision_variance_u64(self.fingerprint(item))
+    }
+
+    /// Estimates the per-row collision variance for a stable 64-bit item
+    /// identifier.
+    ///
+    /// See [`Self::collision_variance`].
+    pub fn collision_variance_u64(&self, item_id: u64) -> f64 {
+        let squared_norm = self.median_squared_row_norm();
+        let own = self.estimate_u64(item_id) as f64;
+        ((squared_norm - own * own) / self.width as f64).max(0.0)
+    }
+
+    /// Scales every counter and [`Self::total_count`] by `factor`, rounding
+    /// toward zero.
+    ///
+    /// Calling this on a fixed schedule implements exponential forgetting for
+    /// signed streams: after `n` calls, an update contributes about `factor^n`
+    /// of its original weight. Truncation treats positive and negative
+    /// counters symmetrically and guarantees that every counter eventually
+    /// reaches zero. Because each counter is rounded separately, an estimate
+    /// can drift by up to one per decay from the scaled exact value. Counters
+    /// beyond `2^53` in magnitude are scaled with `f64` precision.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SketchError::InvalidParameter`] unless `factor` is finite and
+    /// strictly between zero and one. An error leaves the sketch unchanged.
+    pub fn decay(&mut self, factor: f64) -> Result<(), SketchError> {
+        if !factor.is_finite() || factor <= 0.0 || factor >= 1.0 {
+            return Err(SketchError::InvalidParameter(
+                "decay factor must be finite and strictly between 0 and 1",
+            ));
+        }
+
+        // Float-to-integer casts truncate toward zero, and the magnitude only
+        // shrinks, so every result stays representable.
+        let scale = |count: i64| (count as f64 * factor) as i64;
+        for counter in &mut self.counters {
+            *counter = scale(*counter);
+        }
+        self.total_count = scale(self.total_count);
+        self.update_magnitude = (self.update_magnitude as f64 * factor) as u64;
+        Ok(())
+    }
+
     /// Clears all counters while retaining the hash family and allocated table.
     pub fn clear(&mut self) {
         self.counters.fill(0);
+        self.total_count = 0;
+        self.update_magnitude = 0;
+    }
+
+    /// Returns `true` when `other` has the dimensions and seed that
+    /// [`Self::merge`] requires. The merge itself can still fail on counter
+    /// overflow.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.check_mergeable(other).is_ok()
     }
 
     /// Adds another compatible sketch into this sketch.
@@ -360,18 +598,10 @@ impl CountSketch {
     ///
     /// Returns [`SketchError::IncompatibleSketches`] for dimension or seed
     /// mismatch. Returns [`SketchError::CounterOverflow`] without mutation if
-    /// any combined counter is not exactly representable.
+    /// any combined counter is not exactly representable. The net total
+    /// saturates.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
-        if self.width != other.width || self.depth() != other.depth() {
-            return Err(SketchError::IncompatibleSketches(
-                "width/depth must match for merge",
-            ));
-        }
-        if self.family_seed != other.family_seed {
-            return Err(SketchError::IncompatibleSketches(
-                "hash-family seeds must match for merge",
-            ));
-        }
+        self.check_mergeable(other)?;
 
         for (left, right) in self.counters.iter().zip(other.counters.iter()) {
             left.checked_add(*right)
@@ -383,6 +613,200 @@ impl CountSketch {
                 .checked_add(*right)
                 .expect("preflight must prove that the merged counter is representable");
         }
+        self.total_count = self.total_count.saturating_add(other.total_count);
+        self.update_magnitude = self.update_magnitude.saturating_add(other.update_magnitude);
+        Ok(())
+    }
+
+    /// Adds several compatible sketches into this sketch in one pass.
+    ///
+    /// Every input is validated before any counter changes, and each cell is
+    /// then summed across all inputs at once. Only the final sum of each cell
+    /// must be representable, so intermediate totals that would overflow a
+    /// pairwise [`Self::merge`] chain are accepted when later inputs cancel
+    /// them. An empty slice is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SketchError::IncompatibleSketches`] if any input differs in
+    /// dimensions or seed. Returns [`SketchError::CounterOverflow`] if any
+    /// combined counter is not exactly representable. Either error leaves this
+    /// sketch unchanged. The combined net total saturates.
+    pub fn merge_all(&mut self, others: &[&Self]) -> Result<(), SketchError> {
+        for other in others {
+            self.check_mergeable(other)?;
+        }
+
+        // i128 cannot overflow while summing fewer than 2^64 i64 counters.
+        let combined = |index: usize, own: i64| {
+            others.iter().fold(i128::from(own), |sum, other| {
+                sum + i128::from(other.counters[index])
+            })
+        };
+        let total_count = others
+            .iter()
+            .fold(i128::from(self.total_count), |sum, other| {
+                sum + i128::from(other.total_count)
+            });
+        let total_count = total_count.clamp(i64::MIN.into(), i64::MAX.into()) as i64;
+        let update_magnitude = others.iter().fold(self.update_magnitude, |sum, other| {
+            sum.saturating_add(other.update_magnitude)
+        });
+        for (index, &own) in self.counters.iter().enumerate() {
+            i64::try_from(combined(index, own))
+                .ok()
+                .filter(|&counter| counter != i64::MIN)
+                .ok_or(SketchError::CounterOverflow)?;
+        }
+        for index in 0..self.counters.len() {
+            self.counters[index] = combined(index, self.counters[index]) as i64;
+        }
+        self.total_count = total_count;
+        self.update_magnitude = update_magnitude;
+        Ok(())
+    }
+
+    /// Returns a sketch of the change from `other` to this sketch.
+    ///
+    /// Count Sketch is linear, so subtracting counters yields the sketch of the
+    /// stream difference: point queries on the result estimate each item's
+    /// frequency here minus its frequency in `other`. This suits comparing two
+    /// time windows to find heavy changes. Neither input is modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SketchError::IncompatibleSketches`] for dimension or seed
+    /// mismatch. Returns [`SketchError::CounterOverflow`] if any difference is
+    /// not exactly representable. The net total difference saturates.
+    pub fn delta(&self, other: &Self) -> Result<Self, SketchError> {
+        self.check_mergeable(other)?;
+
+        let mut delta = self.clone();
+        for (left, right) in delta.counters.iter_mut().zip(other.counters.iter()) {
+            *left = left
+                .checked_sub(*right)
+                .filter(|&counter| counter != i64::MIN)
+                .ok_or(SketchError::CounterOverflow)?;
+        }
+        delta.total_count = self.total_count.saturating_sub(other.total_count);
+        // The difference stream holds both inputs' updates, one side negated.
+        delta.update_magnitude = self.update_magnitude.saturating_add(other.update_magnitude);
+        Ok(delta)
+    }
+
+    /// Encodes the dimensions, seed, net total count, update magnitude, and
+    /// counters; see
+    /// [the module documentation](crate::count_sketch#serialization).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(45 + 8 * self.counters.len());
+        bytes.extend_from_slice(&BYTES_MAGIC);
+        bytes.push(BYTES_VERSION);
+        bytes.extend_from_slice(&(self.width as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.depth() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.family_seed.to_le_bytes());
+        bytes.extend_from_slice(&self.total_count.to_le_bytes());
+        bytes.extend_from_slice(&self.update_magnitude.to_le_bytes());
+        for counter in &self.counters {
+            bytes.extend_from_slice(&counter.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a sketch written by [`Self::to_bytes`].
+    ///
+    /// Row functions are re-derived from the stored seed, so the result
+    /// merges with the original.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when the magic or version
+    /// does not match, the dimensions are invalid for
+    /// [`Self::with_dimensions`], the payload does not hold exactly
+    /// `width * depth` counters, or a counter is `i64::MIN`.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, SketchError> {
+        take_header(&mut bytes, BYTES_MAGIC, BYTES_VERSION)?;
+        let width = take_usize_le(&mut bytes)?;
+        let depth = take_usize_le(&mut bytes)?;
+        let seed = take_u64_le(&mut bytes)?;
+        let total_count = take_u64_le(&mut bytes)? as i64;
+        let update_magnitude = take_u64_le(&mut bytes)?;
+        // Compare against the payload before allocating a table of the
+        // claimed size.
+        if width.checked_mul(depth).and_then(|len| len.checked_mul(8)) != Some(bytes.len()) {
+            return Err(SketchError::InvalidParameter(
+                "counter table length must equal width * depth",
+            ));
+        }
+        let counters = bytes
+            .chunks_exact(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes")))
+            .collect();
+        Self::from_parts(width, depth, seed, counters, total_count, update_magnitude)
+    }
+
+    /// Rebuilds a sketch from its encoded state, re-deriving row functions.
+    fn from_parts(
+        width: usize,
+        depth: usize,
+        seed: u64,
+        counters: Vec<i64>,
+        total_count: i64,
+        update_magnitude: u64,
+    ) -> Result<Self, SketchError> {
+        let mut sketch = Self::with_dimensions(width, depth, seed)?;
+        if counters.len() != sketch.counters.len() {
+            return Err(SketchError::InvalidParameter(
+                "counter table length must equal width * depth",
+            ));
+        }
+        if counters.contains(&i64::MIN) {
+            return Err(SketchError::InvalidParameter(
+                "counters must exclude i64::MIN",
+            ));
+        }
+        sketch.counters = counters;
+        sketch.total_count = total_count;
+        sketch.update_magnitude = update_magnitude;
+        Ok(sketch)
+    }
+
+    /// Returns the median over rows of the sum of squared counters, the
+    /// estimate of `||f||_2^2` described in the module documentation.
+    fn median_squared_row_norm(&self) -> f64 {
+        let mut row_norms: Vec<f64> = self
+            .counters
+            .chunks_exact(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|&counter| (counter as f64) * (counter as f64))
+                    .sum()
+            })
+            .collect();
+        let middle = row_norms.len() / 2;
+        *row_norms.select_nth_unstable_by(middle, f64::total_cmp).1
+    }
+
+    /// Returns each row's signed counter for an item, in row order.
+    fn row_estimates(&self, item_id: u64) -> Vec<i64> {
+        (0..self.depth())
+            .map(|row| {
+                let (index, sign_is_positive) = self.location(row, item_id);
+                let counter = self.counters[index];
+                if sign_is_positive { counter } else { -counter }
+            })
+            .collect()
+    }
+
+    fn check_mergeable(&self, other: &Self) -> Result<(), SketchError> {
+        if self.width != other.width || self.depth() != other.depth() {
+            return Err(SketchError::IncompatibleSketches(
+                "width/depth must match for merge",
+            ));
+        }
+        if self.family_seed != other.family_seed {
+            return Err(SketchError::IncompatibleSketches(
+                "hash-family seeds must match for merge",
+            ));
+        }
         Ok(())
     }
 
@@ -417,6 +841,188 @@ impl CountSketch {
     }
 }
 
+/// Serialized form of a [`CountSketch`]; row functions are re-derived.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "CountSketch")]
+struct CountSketchState<'a> {
+    width: usize,
+    depth: usize,
+    seed: u64,
+    counters: Cow<'a, [i64]>,
+    total_count: i64,
+    update_magnitude: u64,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CountSketch {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CountSketchState {
+            width: self.width,
+            depth: self.depth(),
+            seed: self.family_seed,
+            counters: Cow::Borrowed(&self.counters),
+            total_count: self.total_count,
+            update_magnitude: self.update_magnitude,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CountSketch {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = CountSketchState::deserialize(deserializer)?;
+        Self::from_parts(
+            state.width,
+            state.depth,
+            state.seed,
+            state.counters.into_owned(),
+            state.total_count,
+            state.update_magnitude,
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Signed frequency estimator over the two most recent windows.
+///
+/// The estimator alternates between two [`CountSketch`] instances with the
+/// same dimensions and seed. Updates go into the current window;
+/// [`Self::advance`] clears the previous window and makes it current, so
+/// estimates cover the last two periods.
+///
+/// # Example
+/// ```rust
+/// use sketches::count_sketch::WindowedCountSketch;
+///
+/// let mut window = WindowedCountSketch::with_dimensions(256, 5, 7).unwrap();
+/// window.add(&"cat", 3).unwrap();
+/// window.advance();
+/// window.add(&"cat", 2).unwrap();
+/// assert_eq!(window.estimate(&"cat"), 5);
+///
+/// window.advance();
+/// assert_eq!(window.estimate(&"cat"), 2);
+/// ```
+///
+/// # Representation and complexity
+///
+/// The estimator owns two tables of `width * depth` counters. Updates take
+/// `O(depth)` time, estimates take `O(depth)` time to sum and select the
+/// median of the combined rows, and advancing takes `O(width * depth)` time to
+/// clear the recycled table.
+#[derive(Debug, Clone)]
+pub struct WindowedCountSketch {
+    windows: [CountSketch; 2],
+    current: usize,
+}
+
+impl WindowedCountSketch {
+    /// Builds an estimator whose windows are sized like [`CountSketch::new`].
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] under the conditions of
+    /// [`CountSketch::new`].
+    pub fn new(epsilon: f64, delta: f64, seed: u64) -> Result<Self, SketchError> {
+        Ok(Self::from_sketch(CountSketch::new(epsilon, delta, seed)?))
+    }
+
+    /// Builds an estimator whose windows have explicit dimensions.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] under the conditions of
+    /// [`CountSketch::with_dimensions`].
+    pub fn with_dimensions(width: usize, depth: usize, seed: u64) -> Result<Self, SketchError> {
+        Ok(Self::from_sketch(CountSketch::with_dimensions(
+            width, depth, seed,
+        )?))
+    }
+
+    /// Returns the sketch of the window currently receiving updates.
+    pub fn current_window(&self) -> &CountSketch {
+        &self.windows[self.current]
+    }
+
+    /// Returns the sketch of the window before the current one.
+    pub fn previous_window(&self) -> &CountSketch {
+        &self.windows[1 - self.current]
+    }
+
+    /// Adds a signed delta for an item to the current window.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::CounterOverflow`] without mutation under the
+    /// conditions of [`CountSketch::add`].
+    pub fn add<T: Hash + ?Sized>(&mut self, item: &T, delta: i64) -> Result<(), SketchError> {
+        self.windows[self.current].add(item, delta)
+    }
+
+    /// Adds a signed delta for a stable 64-bit item identifier to the current
+    /// window.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::CounterOverflow`] without mutation under the
+    /// conditions of [`CountSketch::add_u64`].
+    pub fn add_u64(&mut self, item_id: u64, delta: i64) -> Result<(), SketchError> {
+        self.windows[self.current].add_u64(item_id, delta)
+    }
+
+    /// Returns the median estimate over both windows for an item.
+    pub fn estimate<T: Hash + ?Sized>(&self, item: &T) -> i64 {
+        self.estimate_u64(self.windows[0].fingerprint(item))
+    }
+
+    /// Returns the median estimate over both windows for a stable 64-bit item
+    /// identifier.
+    ///
+    /// Each row's counters are summed across the windows before the median is
+    /// taken, which is exactly the estimate of a merge of the two sketches.
+    /// Row sums saturate rather than fail.
+    pub fn estimate_u64(&self, item_id: u64) -> i64 {
+        let [first, second] = &self.windows;
+        let mut estimates = first.row_estimates(item_id);
+        for (estimate, other) in estimates.iter_mut().zip(second.row_estimates(item_id)) {
+            *estimate = estimate.saturating_add(other);
+        }
+        let middle = estimates.len() / 2;
+        *estimates.select_nth_unstable(middle).1
+    }
+
+    /// Returns the net total count over both windows, saturating on overflow.
+    pub fn total_count(&self) -> i64 {
+        let [first, second] = &self.windows;
+        first.total_count().saturating_add(second.total_count())
+    }
+
+    /// Clears the previous window and makes it current, dropping the updates
+    /// it held.
+    pub fn advance(&mut self) {
+        self.current = 1 - self.current;
+        self.windows[self.current].clear();
+    }
+
+    /// Clears both windows while retaining the hash family.
+    pub fn clear(&mut self) {
+        for window in &mut self.windows {
+            window.clear();
+        }
+    }
+
+    fn from_sketch(sketch: CountSketch) -> Self {
+        Self {
+            windows: [sketch.clone(), sketch],
+            current: 0,
+        }
+    }
+}
+
+/// Chernoff bound on the median of `depth` rows failing when each row fails
+/// with probability at most `1/8`.
+fn median_failure_bound(depth: usize) -> f64 {
+    (-(depth as f64) * DEPTH_DENOMINATOR / 2.0).exp()
+}
+
 fn low_bits_mask(bits: u32) -> u128 {
     match bits {
         0 => 0,
@@ -450,8 +1056,8 @@ mod tests {
     use std::cell::Cell;
     use std::hash::{Hash, Hasher};
 
-    use super::{CountSketch, DEPTH_DENOMINATOR};
-    use crate::SketchError;
+    use super::{CountSketch, DEPTH_DENOMINATOR, WindowedCountSketch};
+    use crate::{SketchError, splitmix64};
 
     const SEED: u64 = 0xA409_3822_299F_31D0;
 
@@ -466,6 +1072,25 @@ mod tests {
         assert!(failure_bound <= 0.01, "bound={failure_bound}");
     }
 
+    #[test]
+    fn confidence_grows_with_depth() {
+        let confidences: Vec<f64> = [1, 3, 5, 13, 31]
+            .into_iter()
+            .map(|depth| {
+                CountSketch::with_dimensions(64, depth, SEED)
+                    .unwrap()
+                    .confidence()
+            })
+            .collect();
+        assert!(confidences.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(confidences.iter().all(|&value| (0.0..1.0).contains(&value)));
+
+        for delta in [0.5, 0.1, 0.01, 0.0001] {
+            let sketch = CountSketch::new(0.1, delta, SEED).unwrap();
+            assert!(sketch.confidence() >= 1.0 - delta);
+        }
+    }
+
     #[test]
     fn constructors_reject_invalid_or_unallocatable_parameters() {
         assert!(CountSketch::new(0.0, 0.1, SEED).is_err());
@@ -505,6 +1130,98 @@ mod tests {
         assert!((4_500..=5_500).contains(&estimate), "estimate={estimate}");
     }
 
+    #[test]
+    fn bound_covers_hot_key_and_most_noise_keys() {
+        let mut sketch = CountSketch::new(0.1, 0.01, SEED).unwrap();
+        let noise = |value: u64| (value % 7) as i64 - 3;
+        for value in 0_u64..2_000 {
+            sketch.add(&value, noise(value)).unwrap();
+        }
+        sketch.add(&"hot", 500).unwrap();
+
+        let (estimate, half_width) = sketch.estimate_with_bound(&"hot");
+        assert_eq!(estimate, sketch.estimate(&"hot"));
+        assert!(
+            half_width > 0 && half_width < 500,
+            "half_width = {half_width}"
+        );
+        assert!((estimate - 500).abs() <= half_width);
+
+        let misses = (0_u64..2_000)
+            .filter(|&value| {
+                let (estimate, half_width) = sketch.estimate_with_bound(&value);
+                (estimate - noise(value)).abs() > half_width
+            })
+            .count();
+        assert!(misses <= 40, "misses = {misses}");
+
+        // Cancelled updates leave no residual uncertainty.
+        let mut cancelled = CountSketch::with_dimensions(64, 3, SEED).unwrap();
+        cancelled.add_u64(9, 1_000).unwrap();
+        cancelled.add_u64(9, -1_000).unwrap();
+        assert_eq!(cancelled.estimate_u64_with_bound(9), (0, 0));
+    }
+
+    #[test]
+    fn collision_estimate_grows_with_distinct_keys() {
+        let mut sketch = CountSketch::with_dimensions(1024, 5, SEED).unwrap();
+        assert_eq!(sketch.collision_estimate(&"hot"), 0);
+
+        // The item's own updates are not collisions.
+        sketch.add(&"hot", 1_000).unwrap();
+        assert_eq!(sketch.collision_estimate(&"hot"), 0);
+
+        let mut previous = 0;
+        for (start, end) in [(0_u64, 1_024), (1_024, 2_048), (2_048, 3_072)] {
+            for value in start..end {
+                sketch.add_u64(value, 1).unwrap();
+            }
+            let collisions = sketch.collision_estimate(&"hot");
+            assert!(collisions > previous, "collisions={collisions}");
+            assert_eq!(collisions, end / 1_024);
+            previous = collisions;
+        }
+
+        // Deletions are updates too, so they still count.
+        sketch.add_u64(0, -1_024).unwrap();
+        assert_eq!(sketch.update_magnitude(), 1_000 + 3_072 + 1_024);
+        assert_eq!(sketch.collision_estimate(&"hot"), 4);
+    }
+
+    #[test]
+    fn collision_variance_grows_with_colliding_mass() {
+        let mut sketch = CountSketch::with_dimensions(64, 5, SEED).unwrap();
+        sketch.add(&"hot", 1_000).unwrap();
+        assert_eq!(sketch.collision_variance(&"hot"), 0.0);
+
+        // One heavy key adds its squared frequency, not a count of one.
+        let mut heavy = sketch.clone();
+        heavy.add(&"other", 1_000).unwrap();
+        let variance = heavy.collision_variance(&"hot");
+        assert!(
+            (variance - 1_000_000.0 / 64.0).abs() < 1.0,
+            "variance={variance}"
+        );
+        assert_eq!(heavy.collision_variance(&"other"), variance);
+
+        let mut previous = 0.0;
+        for (start, end) in [(0_u64, 640), (640, 3_200), (3_200, 12_800)] {
+            for value in start..end {
+                sketch.add(&value, 1).unwrap();
+            }
+            let variance = sketch.collision_variance(&"hot");
+            assert!(variance > previous, "variance={variance}");
+            // Unit frequencies put `end / 64` other keys in each cell on
+            // average, and each contributes a squared mass of one.
+            let expected = end as f64 / 64.0;
+            assert!(
+                (variance - expected).abs() <= 0.4 * expected,
+                "variance={variance} expected={expected}"
+            );
+            previous = variance;
+        }
+    }
+
     #[test]
     fn overflow_is_reported_without_mutation() {
         let mut sketch = CountSketch::with_dimensions(16, 3, SEED).unwrap();
@@ -523,6 +1240,42 @@ mod tests {
         assert!(fresh.counters.iter().all(|&counter| counter == 0));
     }
 
+    #[test]
+    fn decay_shrinks_signed_estimates_geometrically() {
+        let mut sketch = CountSketch::with_dimensions(256, 5, SEED).unwrap();
+        sketch.add(&"rising", 1_000_000).unwrap();
+        sketch.add(&"falling", -1_000_000).unwrap();
+        sketch.add(&"background", 10).unwrap();
+
+        let mut expected = 1_000_000.0;
+        for _ in 0..10 {
+            sketch.decay(0.5).unwrap();
+            expected *= 0.5;
+            let rising = sketch.estimate(&"rising") as f64;
+            let falling = sketch.estimate(&"falling") as f64;
+            assert!((rising - expected).abs() <= 2.0, "{rising} vs {expected}");
+            assert!(
+                (falling + expected).abs() <= 2.0,
+                "{falling} vs {}",
+                -expected
+            );
+        }
+        assert_eq!(sketch.total_count(), 0);
+
+        for _ in 0..20 {
+            sketch.decay(0.5).unwrap();
+        }
+        assert_eq!(sketch.estimate(&"rising"), 0);
+        assert_eq!(sketch.estimate(&"falling"), 0);
+
+        for factor in [0.0, 1.0, -0.5, 1.5, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                sketch.decay(factor),
+                Err(SketchError::InvalidParameter(_))
+            ));
+        }
+    }
+
     #[test]
     fn merge_is_linear_and_requires_the_same_seed() {
         let mut left = CountSketch::with_dimensions(512, 5, SEED).unwrap();
@@ -558,6 +1311,67 @@ mod tests {
         assert_eq!(left.counters, counters_before);
     }
 
+    #[test]
+    fn merge_all_sums_every_shard_in_one_pass() {
+        let mut shards: Vec<_> = (0..8)
+            .map(|_| CountSketch::with_dimensions(1_024, 5, SEED).unwrap())
+            .collect();
+        let mut direct = CountSketch::with_dimensions(1_024, 5, SEED).unwrap();
+        for (shard, sketch) in shards.iter_mut().enumerate() {
+            let weight = shard as i64 + 1;
+            for key in 0_u64..50 {
+                sketch.add(&key, weight).unwrap();
+                direct.add(&key, weight).unwrap();
+            }
+            sketch.add(&"shared", 10 * weight).unwrap();
+            direct.add(&"shared", 10 * weight).unwrap();
+        }
+
+        let (first, rest) = shards.split_first_mut().unwrap();
+        let rest: Vec<&CountSketch> = rest.iter().collect();
+        first.merge_all(&rest).unwrap();
+        assert_eq!(first.counters, direct.counters);
+        assert_eq!(first.estimate(&"shared"), 360);
+
+        let before = first.counters.clone();
+        first.merge_all(&[]).unwrap();
+        assert_eq!(first.counters, before);
+    }
+
+    #[test]
+    fn merge_all_validates_every_input_before_mutation() {
+        let mut target = CountSketch::with_dimensions(16, 3, SEED).unwrap();
+        let mut compatible = CountSketch::with_dimensions(16, 3, SEED).unwrap();
+        target.add_u64(1, 5).unwrap();
+        compatible.add_u64(1, 7).unwrap();
+        let before = target.counters.clone();
+
+        let different_seed = CountSketch::with_dimensions(16, 3, SEED + 1).unwrap();
+        assert_eq!(
+            target.merge_all(&[&compatible, &different_seed]),
+            Err(SketchError::IncompatibleSketches(
+                "hash-family seeds must match for merge"
+            ))
+        );
+        let different_width = CountSketch::with_dimensions(32, 3, SEED).unwrap();
+        assert!(target.merge_all(&[&different_width]).is_err());
+        assert_eq!(target.counters, before);
+
+        let mut huge = CountSketch::with_dimensions(16, 3, SEED).unwrap();
+        huge.add_u64(1, i64::MAX).unwrap();
+        assert_eq!(
+            target.merge_all(&[&compatible, &huge]),
+            Err(SketchError::CounterOverflow)
+        );
+        assert_eq!(target.counters, before);
+
+        // Only the final sum must fit, unlike a pairwise merge chain.
+        let mut cancel = CountSketch::with_dimensions(16, 3, SEED).unwrap();
+        cancel.add_u64(1, -i64::MAX).unwrap();
+        target.merge_all(&[&huge, &cancel]).unwrap();
+        assert_eq!(target.counters, before);
+    }
+
     #[test]
     fn cancellation_restores_counters_without_consuming_an_update_budget() {
         let mut sketch = CountSketch::with_dimensions(128, 3, SEED).unwrap();
@@ -597,6 +1411,242 @@ mod tests {
         assert_eq!(calls.get(), 2);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_preserves_estimates_and_merge_compatibility() {
+        let mut original = CountSketch::with_dimensions(256, 5, SEED).unwrap();
+        for value in 0_u64..1_000 {
+            original.add(&value, (value % 7) as i64 - 3).unwrap();
+        }
+        original.add(&"hot", 500).unwrap();
+
+        let json = serde_json::to_string(&original).unwrap();
+        let mut restored: CountSketch = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.counters, original.counters);
+        assert_eq!(restored.rows, original.rows);
+        assert_eq!(restored.total_count(), original.total_count());
+        assert_eq!(restored.update_magnitude(), original.update_magnitude());
+        assert_eq!(restored.estimate(&"hot"), original.estimate(&"hot"));
+        for value in 0_u64..1_000 {
+            assert_eq!(restored.estimate(&value), original.estimate(&value));
+        }
+
+        restored.merge(&original).unwrap();
+        assert_eq!(restored.estimate(&"hot"), 2 * original.estimate(&"hot"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_inconsistent_state() {
+        let short_table = r#"{"width":4,"depth":3,"seed":1,"counters":[0,0,0],"total_count":0,"update_magnitude":0}"#;
+        assert!(serde_json::from_str::<CountSketch>(short_table).is_err());
+
+        let even_depth = r#"{"width":2,"depth":2,"seed":1,"counters":[0,0,0,0],"total_count":0,"update_magnitude":0}"#;
+        assert!(serde_json::from_str::<CountSketch>(even_depth).is_err());
+
+        let minimum_counter = format!(
+            r#"{{"width":1,"depth":1,"seed":1,"counters":[{}],"total_count":0,"update_magnitude":0}}"#,
+            i64::MIN
+        );
+        assert!(serde_json::from_str::<CountSketch>(&minimum_counter).is_err());
+    }
+
+    #[test]
+    fn byte_round_trip_preserves_estimates_and_merge_compatibility() {
+        let mut original = CountSketch::with_dimensions(256, 5, SEED).unwrap();
+        for value in 0_u64..1_000 {
+            original.add(&value, (value % 7) as i64 - 3).unwrap();
+        }
+        original.add(&"hot", 500).unwrap();
+
+        let bytes = original.to_bytes();
+        assert_eq!(bytes.len(), 45 + 8 * 256 * 5);
+        let mut restored = CountSketch::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.counters, original.counters);
+        assert_eq!(restored.rows, original.rows);
+        assert_eq!(restored.total_count(), original.total_count());
+        assert_eq!(restored.update_magnitude(), original.update_magnitude());
+        for value in 0_u64..1_000 {
+            assert_eq!(restored.estimate(&value), original.estimate(&value));
+        }
+
+        restored.merge(&original).unwrap();
+        assert_eq!(restored.estimate(&"hot"), 2 * original.estimate(&"hot"));
+    }
+
+    #[test]
+    fn from_bytes_rejects_inconsistent_payloads() {
+        let bytes = CountSketch::with_dimensions(4, 3, SEED).unwrap().to_bytes();
+        assert!(CountSketch::from_bytes(&bytes).is_ok());
+
+        let invalid = |edit: &dyn Fn(&mut Vec<u8>)| {
+            let mut payload = bytes.clone();
+            edit(&mut payload);
+            CountSketch::from_bytes(&payload).is_err()
+        };
+        assert!(invalid(&|payload| payload[0] = b'X'));
+        assert!(invalid(&|payload| payload[4] = 2));
+        assert!(invalid(&|payload| payload.truncate(20)));
+        assert!(invalid(&|payload| payload.truncate(payload.len() - 8)));
+        assert!(invalid(&|payload| payload.push(0)));
+        // Depth 2 is even.
+        assert!(invalid(&|payload| payload[13] = 2));
+        assert!(invalid(&|payload| {
+            payload[45..53].copy_from_slice(&i64::MIN.to_le_bytes())
+        }));
+    }
+
+    #[test]
+    fn windowed_estimates_drop_the_oldest_window_on_advance() {
+        let mut window = WindowedCountSketch::with_dimensions(256, 5, SEED).unwrap();
+        window.add(&"old", 40).unwrap();
+        window.add(&"both", 10).unwrap();
+        window.advance();
+        window.add(&"both", -3).unwrap();
+        window.add(&"new", 25).unwrap();
+
+        assert_eq!(window.estimate(&"old"), 40);
+        assert_eq!(window.estimate(&"both"), 7);
+        assert_eq!(window.estimate(&"new"), 25);
+        assert_eq!(window.total_count(), 72);
+
+        let mut merged = window.previous_window().clone();
+        merged.merge(window.current_window()).unwrap();
+        for item in ["old", "both", "new", "absent"] {
+            assert_eq!(window.estimate(&item), merged.estimate(&item));
+        }
+
+        window.advance();
+        assert_eq!(window.estimate(&"old"), 0);
+        assert_eq!(window.estimate(&"both"), -3);
+        assert_eq!(window.estimate(&"new"), 25);
+        assert_eq!(window.total_count(), 22);
+        assert_eq!(window.current_window().total_count(), 0);
+
+        window.clear();
+        assert_eq!(window.total_count(), 0);
+        assert_eq!(window.estimate(&"new"), 0);
+    }
+
+    #[test]
+    fn rates_normalize_by_the_net_total_count() {
+        let mut sketch = CountSketch::with_dimensions(256, 5, SEED).unwrap();
+        assert_eq!(sketch.estimate_rate(&"a"), 0.0);
+
+        sketch.add(&"a", 300).unwrap();
+        sketch.add(&"b", 100).unwrap();
+        assert_eq!(sketch.total_count(), 400);
+        let (a, b) = (sketch.estimate_rate(&"a"), sketch.estimate_rate(&"b"));
+        assert!((a - 0.75).abs() < 0.01, "a={a}");
+        assert!((a + b - 1.0).abs() < 0.01, "sum={}", a + b);
+
+        sketch.add(&"b", -100).unwrap();
+        sketch.add(&"a", -300).unwrap();
+        assert_eq!(sketch.total_count(), 0);
+        assert_eq!(sketch.estimate_rate(&"a"), 0.0);
+    }
+
+    #[test]
+    fn total_count_follows_merges_and_saturates() {
+        let mut left = CountSketch::with_dimensions(64, 3, SEED).unwrap();
+        let mut right = CountSketch::with_dimensions(64, 3, SEED).unwrap();
+        left.add_u64(1, 5).unwrap();
+        right.add_u64(2, -2).unwrap();
+        left.merge(&right).unwrap();
+        assert_eq!(left.total_count(), 3);
+        left.merge_all(&[&right, &right]).unwrap();
+        assert_eq!(left.total_count(), -1);
+
+        left.add_u64(3, -i64::MAX).unwrap();
+        assert_eq!(left.total_count(), i64::MIN);
+
+        // An update whose counters are representable is accepted even though
+        // the net total has no room left.
+        let shares_no_cell = |id: u64| {
+            (0..left.depth()).all(|row| left.location(row, id).0 != left.location(row, 3).0)
+        };
+        let other = (4_u64..).find(|&id| shares_no_cell(id)).unwrap();
+        left.add_u64(other, -5).unwrap();
+        assert_eq!(left.estimate_u64(other), -5);
+        assert_eq!(left.total_count(), i64::MIN);
+
+        let mut opposite = CountSketch::with_dimensions(64, 3, SEED).unwrap();
+        opposite.add_u64(other, 10).unwrap();
+        let delta = left.delta(&opposite).unwrap();
+        assert_eq!(delta.total_count(), i64::MIN);
+        assert_eq!(delta.estimate_u64(other), -15);
+
+        let before = left.counters.clone();
+        assert_eq!(left.add_u64(3, -2), Err(SketchError::CounterOverflow));
+        assert_eq!(left.counters, before);
+
+        left.clear();
+        assert_eq!(left.total_count(), 0);
+    }
+
+    #[test]
+    fn delta_isolates_the_spiking_key() {
+        let mut before = CountSketch::with_dimensions(1_024, 5, SEED).unwrap();
+        let mut after = CountSketch::with_dimensions(1_024, 5, SEED).unwrap();
+        for key in 0_u64..200 {
+            before.add(&key, 20).unwrap();
+            after.add(&key, 20).unwrap();
+        }
+        after.add(&7_u64, 5_000).unwrap();
+
+        let delta = after.delta(&before).unwrap();
+        assert_eq!(delta.total_count(), 5_000);
+        assert_eq!(delta.estimate(&7_u64), 5_000);
+        for key in (0_u64..200).filter(|&key| key != 7) {
+            assert!(delta.estimate(&key).abs() <= 10, "key={key}");
+        }
+        assert_eq!(before.delta(&after).unwrap().estimate(&7_u64), -5_000);
+
+        let other_seed = CountSketch::with_dimensions(1_024, 5, SEED + 1).unwrap();
+        assert!(matches!(
+            after.delta(&other_seed),
+            Err(SketchError::IncompatibleSketches(_))
+        ));
+
+        let mut low = CountSketch::with_dimensions(16, 1, SEED).unwrap();
+        let mut high = CountSketch::with_dimensions(16, 1, SEED).unwrap();
+        low.add_u64(1, -i64::MAX).unwrap();
+        high.add_u64(1, i64::MAX).unwrap();
+        assert!(matches!(
+            low.delta(&high),
+            Err(SketchError::CounterOverflow)
+        ));
+    }
+
+    #[test]
+    fn robust_estimate_has_lower_error_variance_than_the_median() {
+        let (mut median_squares, mut robust_squares) = (0_i128, 0_i128);
+        for trial in 0_u64..200 {
+            let mut sketch = CountSketch::with_dimensions(64, 5, SEED ^ splitmix64(trial)).unwrap();
+            for item in 0_u64..2_000 {
+                sketch.add_u64(item, 1 + (item % 5) as i64).unwrap();
+            }
+            for item in 0_u64..20 {
+                let truth = 1 + (item % 5) as i128;
+                median_squares += (i128::from(sketch.estimate_u64(item)) - truth).pow(2);
+                robust_squares += (i128::from(sketch.estimate_robust_u64(item)) - truth).pow(2);
+            }
+        }
+        assert!(
+            robust_squares < median_squares,
+            "robust={robust_squares} median={median_squares}"
+        );
+
+        let mut single_row = CountSketch::with_dimensions(8, 1, SEED).unwrap();
+        single_row.add(&"x", 9).unwrap();
+        single_row.add(&"y", -4).unwrap();
+        assert_eq!(single_row.estimate_robust(&"x"), single_row.estimate(&"x"));
+
+        let mut exact = CountSketch::with_dimensions(128, 5, SEED).unwrap();
+        exact.add(&"only", -17).unwrap();
+        assert_eq!(exact.estimate_robust(&"only"), -17);
+    }
+
     #[test]
     fn seed_selects_reproducible_hash_families() {
         let first = CountSketch::with_dimensions(128, 7, SEED).unwrap();
@@ -607,4 +1657,20 @@ mod tests {
         assert_eq!(first.rows, second.rows);
         assert_ne!(first.rows, different.rows);
     }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let sketches = [
+            CountSketch::with_dimensions(16, 3, SEED).unwrap(),
+            CountSketch::with_dimensions(16, 5, SEED).unwrap(),
+            CountSketch::with_dimensions(32, 3, SEED).unwrap(),
+            CountSketch::with_dimensions(16, 3, SEED + 1).unwrap(),
+        ];
+        for left in &sketches {
+            for right in &sketches {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
@@ -36,6 +36,13 @@
 //! Section 3.3 of the [paper]. Safe deletion of arbitrary keys requires exact
 //! membership information outside the filter.
 //!
+//! [`CuckooFilter::delete_strict`] narrows the hazard for callers that cannot
+//! guarantee the precondition: it refuses to delete when more than one copy of
+//! the item's fingerprint is stored in its candidate buckets, reporting
+//! [`DeleteOutcome::Ambiguous`] instead of guessing which key owns it. A
+//! non-member whose fingerprint matches exactly one stored entry is still
+//! indistinguishable from that entry's owner.
+//!
 //! # Difference from the original insertion algorithm
 //!
 //! [Algorithm 1 in the original Cuckoo Filter paper][paper] returns failure
@@ -61,6 +68,14 @@
 //! cuckoo hashing offers too few distinct bucket pairs. Both constructors
 //! therefore reject fingerprint widths outside `6..=16`.
 //!
+//! Buckets hold four entries by default. The paper also evaluates two- and
+//! eight-entry buckets, reaching about 84% and 98% occupancy respectively, at
+//! the cost of more fingerprint comparisons per lookup and a correspondingly
+//! higher false-positive rate for larger buckets.
+//! [`CuckooFilter::with_bucket_size`] and
+//! [`CuckooFilter::with_parameters_and_bucket_size`] accept bucket sizes 2, 4,
+//! and 8, and automatic sizing targets the matching occupancy.
+//!
 //! The paper and its reference implementation use 500 as `MaxNumKicks`, which
 //! is also the default used by [`CuckooFilter::new`]. Applications that prefer
 //! more relocation work in exchange for fewer early failures near capacity can
@@ -89,17 +104,23 @@
 //!
 //! [paper]: https://www.cs.cmu.edu/~dga/papers/cuckoo-conext2014.pdf
 
-use std::hash::Hash;
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
 
-use crate::{SketchError, seeded_hash64, splitmix64};
+use crate::cardinality::ApproxCardinality;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::{SketchError, SplitMix64, seeded_hash64};
 
-const BUCKET_SIZE: usize = 4;
+const DEFAULT_BUCKET_SIZE: usize = 4;
+const SUPPORTED_BUCKET_SIZES: [usize; 3] = [2, 4, 8];
 const DEFAULT_MAX_KICKS: usize = 500;
 const MAX_TARGET_LOAD_FACTOR: f64 = 0.96;
 const MIN_FINGERPRINT_BITS: u8 = 6;
 const MAX_FINGERPRINT_BITS: u8 = 16;
 const ITEM_HASH_SEED: u64 = 0x243F_6A88_85A3_08D3;
 const FINGERPRINT_MIX_MULTIPLIER: u64 = 0x5BD1_E995;
+const DEFAULT_SEED: u64 = 0xD6E8_FD93_5E7A_4A6D;
 
 /// Probability that two independently hashed fingerprints collide after the
 /// reserved zero value is remapped to one.
@@ -109,25 +130,39 @@ fn fingerprint_collision_probability(fingerprint_bits: u8) -> f64 {
 }
 
 /// Union bound for matching any entry across two completely full buckets.
-fn full_bucket_false_positive_rate_bound(fingerprint_bits: u8) -> f64 {
-    (2.0 * BUCKET_SIZE as f64 * fingerprint_collision_probability(fingerprint_bits)).min(1.0)
+fn full_bucket_false_positive_rate_bound(fingerprint_bits: u8, bucket_size: usize) -> f64 {
+    (2.0 * bucket_size as f64 * fingerprint_collision_probability(fingerprint_bits)).min(1.0)
+}
+
+/// Target occupancy for automatic sizing. Four-entry buckets use the reference
+/// implementation's 96% threshold; other sizes use the occupancy reported for
+/// them in Section 5.1 of the paper.
+fn max_target_load_factor(bucket_size: usize) -> f64 {
+    match bucket_size {
+        2 => 0.84,
+        8 => 0.98,
+        _ => MAX_TARGET_LOAD_FACTOR,
+    }
 }
 
 /// Chooses the smallest power-of-two bucket count whose target occupancy does
-/// not exceed the 96% threshold used by the reference implementation.
-fn bucket_count_for_expected_items(expected_items: usize) -> Result<usize, SketchError> {
+/// not exceed [`max_target_load_factor`] for `bucket_size`.
+fn bucket_count_for_expected_items(
+    expected_items: usize,
+    bucket_size: usize,
+) -> Result<usize, SketchError> {
     debug_assert!(expected_items > 0);
 
-    let minimum_buckets = expected_items.div_ceil(BUCKET_SIZE).max(2);
+    let minimum_buckets = expected_items.div_ceil(bucket_size).max(2);
     let mut buckets =
         minimum_buckets
             .checked_next_power_of_two()
             .ok_or(SketchError::InvalidParameter(
                 "expected_items requires too many buckets",
             ))?;
-    let target_load = expected_items as f64 / (buckets as f64 * BUCKET_SIZE as f64);
+    let target_load = expected_items as f64 / (buckets as f64 * bucket_size as f64);
 
-    if target_load > MAX_TARGET_LOAD_FACTOR {
+    if target_load > max_target_load_factor(bucket_size) {
         buckets = buckets.checked_mul(2).ok_or(SketchError::InvalidParameter(
             "expected_items requires too many buckets",
         ))?;
@@ -135,22 +170,30 @@ fn bucket_count_for_expected_items(expected_items: usize) -> Result<usize, Sketc
     Ok(buckets)
 }
 
-/// Byte-aligned storage for buckets of four packed fingerprints.
+/// Byte-aligned storage for buckets of packed fingerprints.
 ///
-/// A bucket uses `ceil(BUCKET_SIZE * fingerprint_bits / 8)` bytes. Keeping
+/// A bucket uses `ceil(bucket_size * fingerprint_bits / 8)` bytes. Keeping
 /// buckets byte-aligned makes each lookup touch one contiguous byte range while
-/// wasting at most four padding bits per bucket for odd fingerprint widths.
+/// wasting at most seven padding bits per bucket for odd fingerprint widths.
+/// Eight 16-bit slots fill exactly 128 bits, so a bucket always decodes into
+/// one `u128` word.
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PackedBuckets {
     bytes: Vec<u8>,
     bucket_count: usize,
+    bucket_size: usize,
     bytes_per_bucket: usize,
     fingerprint_bits: u8,
 }
 
 impl PackedBuckets {
-    fn new(bucket_count: usize, fingerprint_bits: u8) -> Result<Self, SketchError> {
-        let bits_per_bucket = BUCKET_SIZE * usize::from(fingerprint_bits);
+    fn new(
+        bucket_count: usize,
+        bucket_size: usize,
+        fingerprint_bits: u8,
+    ) -> Result<Self, SketchError> {
+        debug_assert!(bucket_size * usize::from(fingerprint_bits) <= 128);
+        let bits_per_bucket = bucket_size * usize::from(fingerprint_bits);
         let bytes_per_bucket = bits_per_bucket.div_ceil(8);
         let storage_len =
             bucket_count
@@ -159,9 +202,9 @@ impl PackedBuckets {
                     "packed bucket storage size overflows usize",
                 ))?;
         // A small zeroed suffix lets every bucket be decoded with one safe
-        // eight-byte load, including the final bucket and widths below 16.
+        // sixteen-byte load, including the final bucket and narrow buckets.
         let allocation_len = storage_len
-            .checked_add(std::mem::size_of::<u64>() - 1)
+            .checked_add(core::mem::size_of::<u128>() - 1)
             .ok_or(SketchError::InvalidParameter(
                 "packed bucket storage size overflows usize",
             ))?;
@@ -169,6 +212,7 @@ impl PackedBuckets {
         Ok(Self {
             bytes: vec![0; allocation_len],
             bucket_count,
+            bucket_size,
             bytes_per_bucket,
             fingerprint_bits,
         })
@@ -191,8 +235,29 @@ impl PackedBuckets {
         let word = self.read_bucket(bucket);
         let mask = self.fingerprint_mask();
 
-        (0..BUCKET_SIZE)
-            .any(|slot| ((word >> self.slot_shift(slot)) & mask) == u64::from(fingerprint))
+        (0..self.bucket_size)
+            .any(|slot| ((word >> self.slot_shift(slot)) & mask) == u128::from(fingerprint))
+    }
+
+    fn count(&self, bucket: usize, fingerprint: u16) -> usize {
+        let word = self.read_bucket(bucket);
+        let mask = self.fingerprint_mask();
+
+        (0..self.bucket_size)
+            .filter(|&slot| ((word >> self.slot_shift(slot)) & mask) == u128::from(fingerprint))
+            .count()
+    }
+
+    /// Counts occupied slots whose fingerprint also fills an earlier slot of
+    /// the same bucket.
+    fn repeated(&self, bucket: usize) -> usize {
+        (1..self.bucket_size)
+            .filter(|&slot| {
+                let fingerprint = self.read_slot(bucket, slot);
+                fingerprint != 0
+                    && (0..slot).any(|earlier| self.read_slot(bucket, earlier) == fingerprint)
+            })
+            .count()
     }
 
     #[cfg(test)]
@@ -200,17 +265,26 @@ impl PackedBuckets {
         self.contains(bucket, 0)
     }
 
+    fn occupied(&self, bucket: usize) -> usize {
+        let word = self.read_bucket(bucket);
+        let mask = self.fingerprint_mask();
+
+        (0..self.bucket_size)
+            .filter(|&slot| ((word >> self.slot_shift(slot)) & mask) != 0)
+            .count()
+    }
+
     fn insert(&mut self, bucket: usize, fingerprint: u16) -> bool {
         debug_assert_ne!(fingerprint, 0);
-        debug_assert!(u64::from(fingerprint) <= self.fingerprint_mask());
+        debug_assert!(u128::from(fingerprint) <= self.fingerprint_mask());
 
         let mut word = self.read_bucket(bucket);
         let mask = self.fingerprint_mask();
 
-        for slot in 0..BUCKET_SIZE {
+        for slot in 0..self.bucket_size {
             let shift = self.slot_shift(slot);
             if ((word >> shift) & mask) == 0 {
-                word |= u64::from(fingerprint) << shift;
+                word |= u128::from(fingerprint) << shift;
                 self.write_bucket(bucket, word);
                 return true;
             }
@@ -222,9 +296,9 @@ impl PackedBuckets {
         let mut word = self.read_bucket(bucket);
         let mask = self.fingerprint_mask();
 
-        for slot in 0..BUCKET_SIZE {
+        for slot in 0..self.bucket_size {
             let shift = self.slot_shift(slot);
-            if ((word >> shift) & mask) == u64::from(fingerprint) {
+            if ((word >> shift) & mask) == u128::from(fingerprint) {
                 word &= !(mask << shift);
                 self.write_bucket(bucket, word);
                 return true;
@@ -234,43 +308,42 @@ impl PackedBuckets {
     }
 
     fn swap_slot(&mut self, bucket: usize, slot: usize, fingerprint: &mut u16) {
-        debug_assert!(slot < BUCKET_SIZE);
-        debug_assert!(u64::from(*fingerprint) <= self.fingerprint_mask());
+        debug_assert!(slot < self.bucket_size);
+        debug_assert!(u128::from(*fingerprint) <= self.fingerprint_mask());
 
         let mut word = self.read_bucket(bucket);
         let mask = self.fingerprint_mask();
         let shift = self.slot_shift(slot);
         let previous = ((word >> shift) & mask) as u16;
 
-        word = (word & !(mask << shift)) | (u64::from(*fingerprint) << shift);
+        word = (word & !(mask << shift)) | (u128::from(*fingerprint) << shift);
         self.write_bucket(bucket, word);
         *fingerprint = previous;
     }
 
-    #[cfg(test)]
     fn read_slot(&self, bucket: usize, slot: usize) -> u16 {
-        debug_assert!(slot < BUCKET_SIZE);
+        debug_assert!(slot < self.bucket_size);
         ((self.read_bucket(bucket) >> self.slot_shift(slot)) & self.fingerprint_mask()) as u16
     }
 
-    fn fingerprint_mask(&self) -> u64 {
-        (1_u64 << self.fingerprint_bits) - 1
+    fn fingerprint_mask(&self) -> u128 {
+        (1_u128 << self.fingerprint_bits) - 1
     }
 
     fn slot_shift(&self, slot: usize) -> usize {
         slot * usize::from(self.fingerprint_bits)
     }
 
-    fn read_bucket(&self, bucket: usize) -> u64 {
+    fn read_bucket(&self, bucket: usize) -> u128 {
         debug_assert!(bucket < self.bucket_count);
         let start = bucket * self.bytes_per_bucket;
-        let bytes = self.bytes[start..start + std::mem::size_of::<u64>()]
+        let bytes = self.bytes[start..start + core::mem::size_of::<u128>()]
             .try_into()
             .expect("packed bucket storage always has read padding");
-        u64::from_le_bytes(bytes)
+        u128::from_le_bytes(bytes)
     }
 
-    fn write_bucket(&mut self, bucket: usize, word: u64) {
+    fn write_bucket(&mut self, bucket: usize, word: u128) {
         debug_assert!(bucket < self.bucket_count);
         let start = bucket * self.bytes_per_bucket;
         let destination = &mut self.bytes[start..start + self.bytes_per_bucket];
@@ -278,11 +351,24 @@ impl PackedBuckets {
     }
 }
 
+/// Result of [`CuckooFilter::delete_strict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteOutcome {
+    /// Exactly one matching fingerprint was stored, and it was removed.
+    Deleted,
+    /// Several matching fingerprints were stored, so none was removed.
+    Ambiguous,
+    /// No matching fingerprint was stored.
+    NotFound,
+}
+
 /// Approximate set-membership filter with support for deletion.
 ///
-/// Each four-entry bucket stores fingerprints in a byte-aligned packed field,
-/// using `ceil(4 * fingerprint_bits / 8)` bytes rather than four fixed-width
-/// integers.
+/// Each bucket stores its fingerprints in a byte-aligned packed field, using
+/// `ceil(bucket_size * fingerprint_bits / 8)` bytes rather than one
+/// fixed-width integer per slot. Buckets hold four entries unless another
+/// size is chosen with [`Self::with_bucket_size`] or
+/// [`Self::with_parameters_and_bucket_size`].
 ///
 /// # Example
 /// ```rust
@@ -299,7 +385,7 @@ pub struct CuckooFilter {
     buckets: PackedBuckets,
     max_kicks: usize,
     inserted_items: u64,
-    rng_state: u64,
+    rng: SplitMix64,
     /// Reusable flattened slot indexes for reversing a failed kick chain.
     relocation_log: Vec<usize>,
 }
@@ -326,6 +412,47 @@ impl CuckooFilter {
     /// requested false-positive rate would require fingerprints wider than 16
     /// bits.
     pub fn new(expected_items: usize, false_positive_rate: f64) -> Result<Self, SketchError> {
+        Self::with_bucket_size(expected_items, false_positive_rate, DEFAULT_BUCKET_SIZE)
+    }
+
+    /// Creates a filter like [`Self::new`] whose relocation walk is driven by
+    /// `seed`.
+    ///
+    /// Every other constructor uses one fixed seed, so all filters built from
+    /// the same parameters and inserts already end in identical states.
+    /// Choosing the seed lets tests pin a different kick sequence, or lets
+    /// independent filters avoid failing on the same unlucky inserts.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] under the same conditions as
+    /// [`Self::new`].
+    pub fn with_seed(
+        expected_items: usize,
+        false_positive_rate: f64,
+        seed: u64,
+    ) -> Result<Self, SketchError> {
+        let mut filter = Self::new(expected_items, false_positive_rate)?;
+        filter.rng = SplitMix64::new(seed);
+        Ok(filter)
+    }
+
+    /// Creates a filter like [`Self::new`] with `bucket_size` slots per bucket.
+    ///
+    /// `bucket_size` must be 2, 4, or 8. The fingerprint width accounts for
+    /// the `2 * bucket_size` comparisons a lookup makes, and the bucket count
+    /// targets the occupancy the paper reports for that size: 84% for two
+    /// slots, 96% for four, and 98% for eight.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid inputs or when the
+    /// requested false-positive rate would require fingerprints wider than 16
+    /// bits.
+    pub fn with_bucket_size(
+        expected_items: usize,
+        false_positive_rate: f64,
+        bucket_size: usize,
+    ) -> Result<Self, SketchError> {
+        validate_bucket_size(bucket_size)?;
         if expected_items == 0 {
             return Err(SketchError::InvalidParameter(
                 "expected_items must be greater than zero",
@@ -341,13 +468,20 @@ impl CuckooFilter {
         }
 
         let fingerprint_bits = (MIN_FINGERPRINT_BITS..=MAX_FINGERPRINT_BITS)
-            .find(|&bits| full_bucket_false_positive_rate_bound(bits) <= false_positive_rate)
+            .find(|&bits| {
+                full_bucket_false_positive_rate_bound(bits, bucket_size) <= false_positive_rate
+            })
             .ok_or(SketchError::InvalidParameter(
                 "false_positive_rate requires fingerprints wider than 16 bits",
             ))?;
-        let buckets = bucket_count_for_expected_items(expected_items)?;
+        let buckets = bucket_count_for_expected_items(expected_items, bucket_size)?;
 
-        Self::with_parameters(buckets, fingerprint_bits, DEFAULT_MAX_KICKS)
+        Self::with_parameters_and_bucket_size(
+            buckets,
+            bucket_size,
+            fingerprint_bits,
+            DEFAULT_MAX_KICKS,
+        )
     }
 
     /// Creates a filter from explicit parameters.
@@ -366,6 +500,29 @@ impl CuckooFilter {
         fingerprint_bits: u8,
         max_kicks: usize,
     ) -> Result<Self, SketchError> {
+        Self::with_parameters_and_bucket_size(
+            bucket_count,
+            DEFAULT_BUCKET_SIZE,
+            fingerprint_bits,
+            max_kicks,
+        )
+    }
+
+    /// Creates a filter from explicit parameters with `bucket_size` slots per
+    /// bucket.
+    ///
+    /// `bucket_size` must be 2, 4, or 8; the other parameters are validated as
+    /// in [`Self::with_parameters`].
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid values.
+    pub fn with_parameters_and_bucket_size(
+        bucket_count: usize,
+        bucket_size: usize,
+        fingerprint_bits: u8,
+        max_kicks: usize,
+    ) -> Result<Self, SketchError> {
+        validate_bucket_size(bucket_size)?;
         if bucket_count == 0 || !bucket_count.is_power_of_two() {
             return Err(SketchError::InvalidParameter(
                 "bucket_count must be a non-zero power of two",
@@ -383,10 +540,10 @@ impl CuckooFilter {
         }
 
         Ok(Self {
-            buckets: PackedBuckets::new(bucket_count, fingerprint_bits)?,
+            buckets: PackedBuckets::new(bucket_count, bucket_size, fingerprint_bits)?,
             max_kicks,
             inserted_items: 0,
-            rng_state: 0xD6E8_FD93_5E7A_4A6D,
+            rng: SplitMix64::new(DEFAULT_SEED),
             relocation_log: Vec::new(),
         })
     }
@@ -396,6 +553,11 @@ impl CuckooFilter {
         self.buckets.len()
     }
 
+    /// Returns the number of fingerprint slots per bucket.
+    pub fn bucket_size(&self) -> usize {
+        self.buckets.bucket_size
+    }
+
     /// Returns the fingerprint width in bits.
     pub fn fingerprint_bits(&self) -> u8 {
         self.buckets.fingerprint_bits
@@ -413,22 +575,89 @@ impl CuckooFilter {
 
     /// Returns current slot utilization in `[0, 1]`.
     pub fn load_factor(&self) -> f64 {
-        let capacity = (self.buckets.len() * BUCKET_SIZE) as f64;
+        let capacity = (self.buckets.len() * self.bucket_size()) as f64;
         if capacity == 0.0 {
             return 0.0;
         }
         self.inserted_items as f64 / capacity
     }
 
+    /// Estimates the number of distinct items stored in the filter.
+    ///
+    /// An item is identified only by its fingerprint and its unordered pair of
+    /// candidate buckets. Counting the distinct `(fingerprint, bucket pair)`
+    /// keys among occupied slots ignores repeated insertions of the same item,
+    /// but also merges distinct items that share a key, so the raw count `D`
+    /// is a lower bound on the distinct items present.
+    ///
+    /// The estimate corrects for those collisions by treating the filter as
+    /// `K = bucket_count * (2^fingerprint_bits - 1) / 2` equally likely keys
+    /// and inverting the expected occupancy, as in linear counting:
+    /// `ln(1 - D / K) / ln(1 - 1 / K)`. The model ignores the small skew from
+    /// remapping the zero fingerprint and from buckets that are their own
+    /// alternate, so it is approximate; the correction is negligible until the
+    /// number of items approaches `K`. This takes `O(s log s)` time for `s`
+    /// occupied slots.
+    pub fn estimated_cardinality(&self) -> f64 {
+        let mut keys: Vec<(u16, usize)> = self
+            .iter_fingerprints()
+            .map(|(bucket, _, fingerprint)| {
+                let alternate = self.alternate_index(bucket, fingerprint);
+                (fingerprint, bucket.min(alternate))
+            })
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let distinct = keys.len() as f64;
+
+        let key_space =
+            self.bucket_count() as f64 * ((1_u64 << self.fingerprint_bits()) - 1) as f64 / 2.0;
+        if key_space <= 1.0 || distinct >= key_space {
+            return distinct;
+        }
+        (-distinct / key_space).ln_1p() / (-1.0 / key_space).ln_1p()
+    }
+
     /// Returns a conservative false-positive-rate bound for two full buckets.
     ///
-    /// This is the union bound across all eight possible fingerprint
-    /// comparisons. It accounts for remapping the reserved zero fingerprint to
-    /// one and is therefore slightly higher than the paper's `8 / 2^f`
-    /// approximation. The value is not load-aware; partially filled filters
-    /// normally have a lower false-positive rate.
+    /// This is the union bound across all `2b` possible fingerprint
+    /// comparisons for bucket size `b`. It accounts for remapping the
+    /// reserved zero fingerprint to one and is therefore slightly higher than
+    /// the paper's `2b / 2^f` approximation. The value is not load-aware;
+    /// partially filled filters normally have a lower false-positive rate.
     pub fn expected_false_positive_rate(&self) -> f64 {
-        full_bucket_false_positive_rate_bound(self.fingerprint_bits())
+        full_bucket_false_positive_rate_bound(self.fingerprint_bits(), self.bucket_size())
+    }
+
+    /// Returns how many buckets hold each number of occupied slots.
+    ///
+    /// Entry `i` counts the buckets with exactly `i` occupied slots, so the
+    /// result has one entry more than [`Self::bucket_size`] and sums to
+    /// [`Self::bucket_count`]. A lightly loaded filter concentrates in the low
+    /// entries; mass in the last entry means full buckets, where insertions
+    /// start relocating. This is read-only and takes `O(bucket_count)` time.
+    pub fn occupancy_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![0; self.bucket_size() + 1];
+        for bucket in 0..self.buckets.len() {
+            histogram[self.buckets.occupied(bucket)] += 1;
+        }
+        histogram
+    }
+
+    /// Counts fingerprint collisions within buckets.
+    ///
+    /// Each occupied slot whose fingerprint already fills another slot of the
+    /// same bucket adds one, so a bucket holding the same fingerprint three
+    /// times contributes two. These shared slots are what make
+    /// [`Self::delete`] ambiguous, and a high count relative to
+    /// [`Self::inserted_items`] indicates that false positives will run above
+    /// what a nearly empty filter suggests. Repeated insertions of the same
+    /// item land in the same bucket and are counted too. This is read-only and
+    /// takes `O(bucket_count * bucket_size^2)` time.
+    pub fn observed_fingerprint_collisions(&self) -> usize {
+        (0..self.buckets.len())
+            .map(|bucket| self.buckets.repeated(bucket))
+            .sum()
     }
 
     /// Inserts one item into the filter.
@@ -442,46 +671,45 @@ impl CuckooFilter {
     /// `O(max_kicks)` reverse pass only when insertion fails. The bounded
     /// worst-case insertion time remains `O(max_kicks)`.
     pub fn insert<T: Hash>(&mut self, item: &T) -> bool {
-        let (mut fingerprint, index_a, index_b) = self.item_location(item);
-        let original_fingerprint = fingerprint;
+        let (fingerprint, index_a, index_b) = self.item_location(item);
 
         if self.insert_into_bucket(index_a, fingerprint)
             || self.insert_into_bucket(index_b, fingerprint)
+            || self.insert_with_relocation(fingerprint, index_a, index_b)
         {
             self.inserted_items = self.inserted_items.saturating_add(1);
             return true;
         }
+        false
+    }
 
-        self.relocation_log.clear();
-        if self.relocation_log.try_reserve(self.max_kicks).is_err() {
-            return false;
-        }
-
-        let rng_state_before = self.rng_state;
-        let mut bucket = if (self.next_u64() & 1) == 0 {
-            index_a
-        } else {
-            index_b
-        };
-
-        for _ in 0..self.max_kicks {
-            let slot = (self.next_u64() as usize) % BUCKET_SIZE;
-            self.relocation_log.push(bucket * BUCKET_SIZE + slot);
-            self.buckets.swap_slot(bucket, slot, &mut fingerprint);
-            bucket = self.alternate_index(bucket, fingerprint);
-
-            if self.insert_into_bucket(bucket, fingerprint) {
-                self.inserted_items = self.inserted_items.saturating_add(1);
-                self.relocation_log.clear();
-                return true;
+    /// Inserts every item in input order, or none of them.
+    ///
+    /// On the first failed [`Self::insert`], the items this call already
+    /// inserted are deleted again in reverse order and `Err(index)` reports
+    /// the position of the failing item. Because each deletion removes a
+    /// fingerprint this call stored, the filter afterwards holds exactly the
+    /// fingerprints it held before, and the relocation generator is restored
+    /// too. Earlier relocations may have moved pre-existing fingerprints to
+    /// their alternate buckets, so the slot layout can differ, but membership
+    /// answers and [`Self::inserted_items`] cannot.
+    ///
+    /// # Errors
+    /// Returns `Err(index)` with the index into `items` of the first item that
+    /// could not be inserted.
+    pub fn insert_batch_atomic<T: Hash>(&mut self, items: &[T]) -> Result<(), usize> {
+        let rng_before = self.rng;
+        for (index, item) in items.iter().enumerate() {
+            if !self.insert(item) {
+                for inserted in items[..index].iter().rev() {
+                    let removed = self.delete(inserted);
+                    debug_assert!(removed);
+                }
+                self.rng = rng_before;
+                return Err(index);
             }
         }
-
-        self.rollback_relocations(&mut fingerprint);
-        self.rng_state = rng_state_before;
-        self.relocation_log.clear();
-        debug_assert_eq!(fingerprint, original_fingerprint);
-        false
+        Ok(())
     }
 
     /// Returns `true` if the item is possibly in the set.
@@ -517,6 +745,108 @@ impl CuckooFilter {
         false
     }
 
+    /// Applies [`Self::delete`] to every item in input order and returns its
+    /// result for each.
+    ///
+    /// Each item must satisfy the known-present precondition of
+    /// [`Self::delete`]. Deletions run sequentially, so an item listed twice
+    /// removes two matching entries when both are stored and reports `false`
+    /// for the second otherwise.
+    pub fn delete_batch<T: Hash>(&mut self, items: &[T]) -> Vec<bool> {
+        items.iter().map(|item| self.delete(item)).collect()
+    }
+
+    /// Deletes an item only when its fingerprint is stored exactly once.
+    ///
+    /// Two distinct keys that share a fingerprint and bucket pair are stored
+    /// as identical entries, so [`Self::delete`] would remove one of them
+    /// without knowing whose it is. This method counts the matching entries
+    /// across both candidate buckets first and returns
+    /// [`DeleteOutcome::Ambiguous`], leaving the filter unchanged, when there
+    /// is more than one. An item inserted twice is also reported as
+    /// ambiguous. A single matching entry may still belong to a different key
+    /// when `item` was never inserted, so the known-present precondition of
+    /// [`Self::delete`] remains the only complete guarantee.
+    pub fn delete_strict<T: Hash>(&mut self, item: &T) -> DeleteOutcome {
+        let (fingerprint, index_a, index_b) = self.item_location(item);
+
+        let mut matches = self.buckets.count(index_a, fingerprint);
+        if index_b != index_a {
+            matches += self.buckets.count(index_b, fingerprint);
+        }
+        match matches {
+            0 => DeleteOutcome::NotFound,
+            1 => {
+                let removed = self.remove_from_bucket(index_a, fingerprint)
+                    || self.remove_from_bucket(index_b, fingerprint);
+                debug_assert!(removed);
+                self.inserted_items = self.inserted_items.saturating_sub(1);
+                DeleteOutcome::Deleted
+            }
+            _ => DeleteOutcome::Ambiguous,
+        }
+    }
+
+    /// Iterates over every occupied slot as `(bucket_index, slot, fingerprint)`.
+    ///
+    /// Slots are visited in bucket order, then slot order, and empty slots
+    /// (fingerprint zero) are skipped. This is read-only introspection: each
+    /// stored fingerprint is one successful insertion that has not been
+    /// deleted, so the iterator yields exactly [`Self::inserted_items`]
+    /// entries. It takes `O(bucket_count)` time to exhaust.
+    pub fn iter_fingerprints(&self) -> impl Iterator<Item = (usize, usize, u16)> + '_ {
+        (0..self.buckets.len()).flat_map(move |bucket| {
+            (0..self.bucket_size()).filter_map(move |slot| {
+                let fingerprint = self.buckets.read_slot(bucket, slot);
+                (fingerprint != 0).then_some((bucket, slot, fingerprint))
+            })
+        })
+    }
+
+    /// Rebuilds the bucket layout from the stored fingerprints.
+    ///
+    /// Every occupied slot is extracted, the buckets are cleared, and each
+    /// fingerprint is re-inserted into the less occupied of its two candidate
+    /// buckets, falling back to the usual random-walk relocation when both
+    /// are full. Both candidates are derivable from the fingerprint and its
+    /// current bucket, so membership and [`Self::inserted_items`] are
+    /// unchanged. Balancing occupancy after heavy insert/delete churn leaves
+    /// fewer full buckets, so later insertions need fewer relocations.
+    ///
+    /// Returns `false`, leaving the filter as it was, in the unlikely case
+    /// that some fingerprint cannot be placed within `max_kicks` relocations.
+    /// This takes `O(bucket_count)` time and temporarily holds a copy of the
+    /// bucket storage and every stored fingerprint.
+    pub fn compact(&mut self) -> bool {
+        let entries: Vec<(usize, u16)> = self
+            .iter_fingerprints()
+            .map(|(bucket, _, fingerprint)| (bucket, fingerprint))
+            .collect();
+        let original = self.buckets.clone();
+        let rng_before = self.rng;
+
+        self.buckets.clear();
+        for (bucket, fingerprint) in entries {
+            let alternate = self.alternate_index(bucket, fingerprint);
+            let (first, second) =
+                if self.buckets.occupied(alternate) < self.buckets.occupied(bucket) {
+                    (alternate, bucket)
+                } else {
+                    (bucket, alternate)
+                };
+
+            if !(self.insert_into_bucket(first, fingerprint)
+                || self.insert_into_bucket(second, fingerprint)
+                || self.insert_with_relocation(fingerprint, first, second))
+            {
+                self.buckets = original;
+                self.rng = rng_before;
+                return false;
+            }
+        }
+        true
+    }
+
     /// Clears all buckets and resets counters.
     pub fn clear(&mut self) {
         self.buckets.clear();
@@ -524,6 +854,47 @@ impl CuckooFilter {
         self.relocation_log.clear();
     }
 
+    /// Runs the paper's random-walk relocation for a fingerprint whose
+    /// candidate buckets are both full. A failed walk is reversed, so the
+    /// buckets are unchanged when this returns `false`.
+    fn insert_with_relocation(
+        &mut self,
+        mut fingerprint: u16,
+        index_a: usize,
+        index_b: usize,
+    ) -> bool {
+        let original_fingerprint = fingerprint;
+        self.relocation_log.clear();
+        if self.relocation_log.try_reserve(self.max_kicks).is_err() {
+            return false;
+        }
+
+        let rng_before = self.rng;
+        let mut bucket = if (self.rng.next_u64() & 1) == 0 {
+            index_a
+        } else {
+            index_b
+        };
+
+        for _ in 0..self.max_kicks {
+            let slot = (self.rng.next_u64() as usize) % self.bucket_size();
+            self.relocation_log.push(bucket * self.bucket_size() + slot);
+            self.buckets.swap_slot(bucket, slot, &mut fingerprint);
+            bucket = self.alternate_index(bucket, fingerprint);
+
+            if self.insert_into_bucket(bucket, fingerprint) {
+                self.relocation_log.clear();
+                return true;
+            }
+        }
+
+        self.rollback_relocations(&mut fingerprint);
+        self.rng = rng_before;
+        self.relocation_log.clear();
+        debug_assert_eq!(fingerprint, original_fingerprint);
+        false
+    }
+
     fn insert_into_bucket(&mut self, bucket_index: usize, fingerprint: u16) -> bool {
         self.buckets.insert(bucket_index, fingerprint)
     }
@@ -539,8 +910,8 @@ impl CuckooFilter {
     /// Reverses the paper-style swap chain after exhausting `max_kicks`.
     fn rollback_relocations(&mut self, fingerprint: &mut u16) {
         for &location in self.relocation_log.iter().rev() {
-            let bucket = location / BUCKET_SIZE;
-            let slot = location % BUCKET_SIZE;
+            let bucket = location / self.bucket_size();
+            let slot = location % self.bucket_size();
             self.buckets.swap_slot(bucket, slot, fingerprint);
         }
     }
@@ -573,10 +944,20 @@ impl CuckooFilter {
     fn primary_index_from_hash(&self, hash: u64) -> usize {
         ((hash >> self.fingerprint_bits()) as usize) & (self.buckets.len() - 1)
     }
+}
 
-    fn next_u64(&mut self) -> u64 {
-        self.rng_state = splitmix64(self.rng_state.wrapping_add(0x9E37_79B9_7F4A_7C15));
-        self.rng_state
+fn validate_bucket_size(bucket_size: usize) -> Result<(), SketchError> {
+    if !SUPPORTED_BUCKET_SIZES.contains(&bucket_size) {
+        return Err(SketchError::InvalidParameter(
+            "bucket_size must be 2, 4, or 8",
+        ));
+    }
+    Ok(())
+}
+
+impl ApproxCardinality for CuckooFilter {
+    fn estimated_cardinality(&self) -> f64 {
+        self.estimated_cardinality()
     }
 }
 
@@ -588,61 +969,66 @@ mod tests {
     };
 
     use super::{
-        BUCKET_SIZE, CuckooFilter, MAX_FINGERPRINT_BITS, MAX_TARGET_LOAD_FACTOR,
-        MIN_FINGERPRINT_BITS, PackedBuckets, bucket_count_for_expected_items,
-        fingerprint_collision_probability, full_bucket_false_positive_rate_bound,
+        CuckooFilter, DEFAULT_BUCKET_SIZE, DeleteOutcome, MAX_FINGERPRINT_BITS,
+        MAX_TARGET_LOAD_FACTOR, MIN_FINGERPRINT_BITS, PackedBuckets, SUPPORTED_BUCKET_SIZES,
+        bucket_count_for_expected_items, fingerprint_collision_probability,
+        full_bucket_false_positive_rate_bound,
     };
 
     #[test]
     fn packed_buckets_roundtrip_every_encodable_width() {
         let bucket_count = 3;
 
-        for fingerprint_bits in 1..=MAX_FINGERPRINT_BITS {
-            let mut buckets = PackedBuckets::new(bucket_count, fingerprint_bits).unwrap();
-            let expected_bytes =
-                bucket_count * (BUCKET_SIZE * usize::from(fingerprint_bits)).div_ceil(8);
-            let mask = (1_u64 << fingerprint_bits) - 1;
-            let mut expected = [[0_u16; BUCKET_SIZE]; 3];
-
-            assert_eq!(buckets.storage_len(), expected_bytes);
-
-            for (bucket, expected_bucket) in expected.iter_mut().enumerate() {
-                for (slot, expected_slot) in expected_bucket.iter_mut().enumerate() {
-                    let value = ((((bucket * BUCKET_SIZE + slot + 1) as u64) & mask).max(1)) as u16;
-                    let mut incoming = value;
-                    buckets.swap_slot(bucket, slot, &mut incoming);
-                    assert_eq!(incoming, 0);
-                    *expected_slot = value;
-                }
-            }
-
-            for (bucket, expected_bucket) in expected.iter().enumerate() {
-                assert!(!buckets.has_empty(bucket));
-                for (slot, &value) in expected_bucket.iter().enumerate() {
-                    assert_eq!(buckets.read_slot(bucket, slot), value);
-                    assert!(buckets.contains(bucket, value));
+        for bucket_size in SUPPORTED_BUCKET_SIZES {
+            for fingerprint_bits in 1..=MAX_FINGERPRINT_BITS {
+                let mut buckets =
+                    PackedBuckets::new(bucket_count, bucket_size, fingerprint_bits).unwrap();
+                let expected_bytes =
+                    bucket_count * (bucket_size * usize::from(fingerprint_bits)).div_ceil(8);
+                let mask = (1_u64 << fingerprint_bits) - 1;
+                let mut expected = vec![vec![0_u16; bucket_size]; bucket_count];
+
+                assert_eq!(buckets.storage_len(), expected_bytes);
+
+                for (bucket, expected_bucket) in expected.iter_mut().enumerate() {
+                    for (slot, expected_slot) in expected_bucket.iter_mut().enumerate() {
+                        let value =
+                            ((((bucket * bucket_size + slot + 1) as u64) & mask).max(1)) as u16;
+                        let mut incoming = value;
+                        buckets.swap_slot(bucket, slot, &mut incoming);
+                        assert_eq!(incoming, 0);
+                        *expected_slot = value;
+                    }
                 }
-            }
 
-            buckets.clear();
-            for bucket in 0..bucket_count {
-                assert!(buckets.has_empty(bucket));
-                for slot in 0..BUCKET_SIZE {
-                    assert_eq!(buckets.read_slot(bucket, slot), 0);
+                for (bucket, expected_bucket) in expected.iter().enumerate() {
+                    assert!(!buckets.has_empty(bucket));
+                    for (slot, &value) in expected_bucket.iter().enumerate() {
+                        assert_eq!(buckets.read_slot(bucket, slot), value);
+                        assert!(buckets.contains(bucket, value));
+                    }
                 }
-            }
 
-            for (bucket, expected_bucket) in expected.iter().enumerate() {
-                for &value in expected_bucket {
-                    assert!(buckets.insert(bucket, value));
+                buckets.clear();
+                for bucket in 0..bucket_count {
+                    assert!(buckets.has_empty(bucket));
+                    for slot in 0..bucket_size {
+                        assert_eq!(buckets.read_slot(bucket, slot), 0);
+                    }
                 }
-                assert!(!buckets.insert(bucket, 1));
 
-                for &value in expected_bucket {
-                    assert!(buckets.remove(bucket, value));
+                for (bucket, expected_bucket) in expected.iter().enumerate() {
+                    for &value in expected_bucket {
+                        assert!(buckets.insert(bucket, value));
+                    }
+                    assert!(!buckets.insert(bucket, 1));
+
+                    for &value in expected_bucket {
+                        assert!(buckets.remove(bucket, value));
+                    }
+                    assert!(!buckets.remove(bucket, 1));
+                    assert!(buckets.has_empty(bucket));
                 }
-                assert!(!buckets.remove(bucket, 1));
-                assert!(buckets.has_empty(bucket));
             }
         }
     }
@@ -659,6 +1045,88 @@ mod tests {
         assert!(CuckooFilter::with_parameters(8, 8, 0).is_err());
     }
 
+    #[test]
+    fn seed_selects_the_relocation_walk() {
+        let fill = |seed| {
+            let mut filter = CuckooFilter::with_seed(1_000, 0.01, seed).unwrap();
+            for value in 0_u64..1_000 {
+                assert!(filter.insert(&value));
+            }
+            filter
+        };
+
+        let first = fill(7);
+        let second = fill(7);
+        let reseeded = fill(8);
+        assert_eq!(first.buckets, second.buckets);
+        assert_eq!(first.rng, second.rng);
+        assert_ne!(first.buckets, reseeded.buckets);
+        for value in 0_u64..1_000 {
+            assert!(reseeded.contains(&value));
+        }
+        assert!(CuckooFilter::with_seed(0, 0.01, 7).is_err());
+    }
+
+    #[test]
+    fn bucket_size_is_validated_and_drives_sizing() {
+        for bucket_size in [0, 1, 3, 5, 16] {
+            assert!(CuckooFilter::with_bucket_size(1_000, 0.01, bucket_size).is_err());
+            assert!(CuckooFilter::with_parameters_and_bucket_size(8, bucket_size, 8, 100).is_err());
+        }
+        assert_eq!(CuckooFilter::new(1_000, 0.01).unwrap().bucket_size(), 4);
+
+        for bucket_size in SUPPORTED_BUCKET_SIZES {
+            let filter = CuckooFilter::with_bucket_size(100_000, 0.01, bucket_size).unwrap();
+            assert_eq!(filter.bucket_size(), bucket_size);
+            assert!(filter.expected_false_positive_rate() <= 0.01);
+            let load = 100_000.0 / (filter.bucket_count() * bucket_size) as f64;
+            assert!(load <= super::max_target_load_factor(bucket_size));
+
+            let mut filter = filter;
+            for value in 0_u64..1_000 {
+                assert!(filter.insert(&value));
+            }
+            assert!((0_u64..1_000).all(|value| filter.contains(&value)));
+            assert_eq!(filter.iter_fingerprints().count(), 1_000);
+            assert!((0_u64..1_000).all(|value| filter.delete(&value)));
+            assert!(filter.is_empty());
+        }
+        // Wider buckets compare more fingerprints, so they need more bits.
+        assert!(
+            CuckooFilter::with_bucket_size(1_000, 0.01, 8)
+                .unwrap()
+                .fingerprint_bits()
+                > CuckooFilter::with_bucket_size(1_000, 0.01, 2)
+                    .unwrap()
+                    .fingerprint_bits()
+        );
+    }
+
+    #[test]
+    fn larger_buckets_reach_higher_load_before_failing() {
+        let load_at_first_failure = |bucket_size: usize| {
+            let slots = 1 << 14;
+            let mut filter = CuckooFilter::with_parameters_and_bucket_size(
+                slots / bucket_size,
+                bucket_size,
+                12,
+                500,
+            )
+            .unwrap();
+            let mut value = 0_u64;
+            while filter.insert(&value) {
+                value += 1;
+            }
+            filter.load_factor()
+        };
+
+        let two = load_at_first_failure(2);
+        let four = load_at_first_failure(4);
+        let eight = load_at_first_failure(8);
+        assert!(two < four && four < eight, "loads = {two}, {four}, {eight}");
+        assert!(four > 0.9 && eight > 0.95, "loads = {four}, {eight}");
+    }
+
     #[test]
     fn automatic_sizing_uses_reference_load_threshold() {
         let expected_items = 1_000_000;
@@ -669,18 +1137,20 @@ mod tests {
         assert_eq!(filter.max_kicks, 500);
         assert_eq!(filter.buckets.storage_len(), 262_144 * 5);
         assert!(
-            expected_items as f64 / (filter.bucket_count() * BUCKET_SIZE) as f64
+            expected_items as f64 / (filter.bucket_count() * DEFAULT_BUCKET_SIZE) as f64
                 <= MAX_TARGET_LOAD_FACTOR
         );
 
-        let capacity = 262_144 * BUCKET_SIZE;
+        let capacity = 262_144 * DEFAULT_BUCKET_SIZE;
         let last_item_below_threshold = (capacity as f64 * MAX_TARGET_LOAD_FACTOR).floor() as usize;
         assert_eq!(
-            bucket_count_for_expected_items(last_item_below_threshold).unwrap(),
+            bucket_count_for_expected_items(last_item_below_threshold, DEFAULT_BUCKET_SIZE)
+                .unwrap(),
             262_144
         );
         assert_eq!(
-            bucket_count_for_expected_items(last_item_below_threshold + 1).unwrap(),
+            bucket_count_for_expected_items(last_item_below_threshold + 1, DEFAULT_BUCKET_SIZE)
+                .unwrap(),
             524_288
         );
     }
@@ -690,8 +1160,9 @@ mod tests {
         for expected_items in [
             1, 8, 9, 100, 1_000, 10_000, 100_000, 1_000_000, 1_006_632, 1_006_633,
         ] {
-            let buckets = bucket_count_for_expected_items(expected_items).unwrap();
-            let load = expected_items as f64 / (buckets as f64 * BUCKET_SIZE as f64);
+            let buckets =
+                bucket_count_for_expected_items(expected_items, DEFAULT_BUCKET_SIZE).unwrap();
+            let load = expected_items as f64 / (buckets as f64 * DEFAULT_BUCKET_SIZE as f64);
 
             assert!(buckets.is_power_of_two());
             assert!(buckets >= 2);
@@ -699,7 +1170,7 @@ mod tests {
 
             if buckets > 2 {
                 let previous_load =
-                    expected_items as f64 / ((buckets / 2) as f64 * BUCKET_SIZE as f64);
+                    expected_items as f64 / ((buckets / 2) as f64 * DEFAULT_BUCKET_SIZE as f64);
                 assert!(previous_load > MAX_TARGET_LOAD_FACTOR);
             }
         }
@@ -716,14 +1187,18 @@ mod tests {
             assert!(filter.expected_false_positive_rate() <= target_rate);
             assert!(
                 expected_bits == MIN_FINGERPRINT_BITS
-                    || full_bucket_false_positive_rate_bound(expected_bits - 1) > target_rate
+                    || full_bucket_false_positive_rate_bound(
+                        expected_bits - 1,
+                        DEFAULT_BUCKET_SIZE
+                    ) > target_rate
             );
         }
     }
 
     #[test]
     fn constructor_rejects_rate_below_sixteen_bit_bound() {
-        let minimum_supported_rate = full_bucket_false_positive_rate_bound(MAX_FINGERPRINT_BITS);
+        let minimum_supported_rate =
+            full_bucket_false_positive_rate_bound(MAX_FINGERPRINT_BITS, DEFAULT_BUCKET_SIZE);
 
         assert!(CuckooFilter::new(1_000, minimum_supported_rate).is_ok());
         assert!(CuckooFilter::new(1_000, minimum_supported_rate * 0.99).is_err());
@@ -732,11 +1207,11 @@ mod tests {
     #[test]
     fn expected_false_positive_rate_is_full_bucket_remapping_bound() {
         let filter = CuckooFilter::with_parameters(8, 8, 100).unwrap();
-        let paper_uniform_approximation = 2.0 * BUCKET_SIZE as f64 / 2_f64.powi(8);
+        let paper_uniform_approximation = 2.0 * DEFAULT_BUCKET_SIZE as f64 / 2_f64.powi(8);
 
         assert_eq!(
             filter.expected_false_positive_rate(),
-            full_bucket_false_positive_rate_bound(8)
+            full_bucket_false_positive_rate_bound(8, DEFAULT_BUCKET_SIZE)
         );
         assert!(filter.expected_false_positive_rate() > paper_uniform_approximation);
     }
@@ -750,6 +1225,58 @@ mod tests {
         assert!(!filter.contains(&"alice"));
     }
 
+    #[test]
+    fn atomic_batch_insert_rolls_back_on_failure() {
+        let stored = |filter: &CuckooFilter| {
+            let mut entries: Vec<(usize, u16)> = filter
+                .iter_fingerprints()
+                .map(|(bucket, _, fingerprint)| {
+                    let alternate = filter.alternate_index(bucket, fingerprint);
+                    (bucket.min(alternate), fingerprint)
+                })
+                .collect();
+            entries.sort_unstable();
+            entries
+        };
+
+        let mut filter = CuckooFilter::with_parameters(8, 12, 20).unwrap();
+        assert_eq!(filter.insert_batch_atomic(&[0_u64, 1, 2]), Ok(()));
+        let mut next = 3_u64;
+        while filter.load_factor() < 0.75 {
+            filter.insert(&next);
+            next += 1;
+        }
+
+        let before = stored(&filter);
+        let count_before = filter.inserted_items();
+        let rng_before = filter.rng;
+        let batch: Vec<u64> = (1_000..1_100).collect();
+        let failed_at = filter.insert_batch_atomic(&batch).unwrap_err();
+        assert!(failed_at > 0 && failed_at < batch.len());
+        assert_eq!(stored(&filter), before);
+        assert_eq!(filter.inserted_items(), count_before);
+        assert_eq!(filter.rng, rng_before);
+        assert_eq!(filter.insert_batch_atomic::<u64>(&[]), Ok(()));
+    }
+
+    #[test]
+    fn delete_batch_matches_sequential_deletes() {
+        let mut batched = CuckooFilter::new(1_000, 0.001).unwrap();
+        for value in 0_u64..100 {
+            assert!(batched.insert(&value));
+        }
+        let mut sequential = batched.clone();
+
+        let items = [5_u64, 1_000, 42, 5, 99, 2_000];
+        let flags = batched.delete_batch(&items);
+        let expected: Vec<bool> = items.iter().map(|item| sequential.delete(item)).collect();
+        assert_eq!(flags, expected);
+        assert_eq!(flags, [true, false, true, false, true, false]);
+        assert_eq!(batched.buckets, sequential.buckets);
+        assert_eq!(batched.inserted_items(), 97);
+        assert!(batched.delete_batch::<u64>(&[]).is_empty());
+    }
+
     #[test]
     fn public_operations_hash_each_item_once() {
         struct CountingItem {
@@ -795,6 +1322,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fingerprint_iterator_yields_each_occupied_slot() {
+        let mut filter = CuckooFilter::new(1_000, 0.01).unwrap();
+        assert_eq!(filter.iter_fingerprints().count(), 0);
+
+        for value in 0_u64..300 {
+            assert!(filter.insert(&value));
+        }
+        assert!(filter.delete(&7_u64));
+
+        let entries: Vec<_> = filter.iter_fingerprints().collect();
+        assert_eq!(entries.len() as u64, filter.inserted_items());
+        for &(bucket, slot, fingerprint) in &entries {
+            assert!(bucket < filter.bucket_count() && slot < DEFAULT_BUCKET_SIZE);
+            assert_ne!(fingerprint, 0);
+            assert_eq!(filter.buckets.read_slot(bucket, slot), fingerprint);
+        }
+        assert!(
+            entries
+                .windows(2)
+                .all(|pair| (pair[0].0, pair[0].1) < (pair[1].0, pair[1].1))
+        );
+
+        // Every member's fingerprint is stored in one of its candidate buckets.
+        for value in (0_u64..300).filter(|&value| value != 7) {
+            let (fingerprint, index_a, index_b) = filter.item_location(&value);
+            assert!(entries.iter().any(|&(bucket, _, stored)| {
+                stored == fingerprint && (bucket == index_a || bucket == index_b)
+            }));
+        }
+    }
+
+    #[test]
+    fn compact_rebalances_buckets_and_keeps_members() {
+        let mut filter = CuckooFilter::with_parameters(1_024, 12, 500).unwrap();
+        let mut members = Vec::new();
+        let mut next = 0_u64;
+        for round in 0..20 {
+            while filter.load_factor() < 0.9 {
+                assert!(filter.insert(&next));
+                members.push(next);
+                next += 1;
+            }
+            // Delete a deterministic third of the members each round.
+            let mut kept = Vec::with_capacity(members.len());
+            for (position, member) in members.into_iter().enumerate() {
+                if (position + round) % 3 == 0 {
+                    assert!(filter.delete(&member));
+                } else {
+                    kept.push(member);
+                }
+            }
+            members = kept;
+        }
+
+        let full_buckets = |filter: &CuckooFilter| {
+            (0..filter.bucket_count())
+                .filter(|&bucket| !filter.buckets.has_empty(bucket))
+                .count()
+        };
+        let stored_before = filter.inserted_items();
+        let full_before = full_buckets(&filter);
+
+        assert!(filter.compact());
+        let full_after = full_buckets(&filter);
+        assert!(
+            full_after < full_before,
+            "before={full_before} after={full_after}"
+        );
+        assert_eq!(filter.inserted_items(), stored_before);
+        assert_eq!(filter.iter_fingerprints().count() as u64, stored_before);
+        assert!(members.iter().all(|member| filter.contains(member)));
+    }
+
     #[test]
     fn load_factor_increases_with_inserts() {
         let mut filter = CuckooFilter::new(1_000, 0.01).unwrap();
@@ -830,7 +1431,7 @@ mod tests {
             let buckets_before = filter.buckets.clone();
             let count_before = filter.inserted_items();
             let load_before = filter.load_factor();
-            let rng_state_before = filter.rng_state;
+            let rng_before = filter.rng;
 
             if filter.insert(&value) {
                 accepted.push(value);
@@ -841,7 +1442,7 @@ mod tests {
             assert_eq!(filter.buckets, buckets_before);
             assert_eq!(filter.inserted_items(), count_before);
             assert_eq!(filter.load_factor(), load_before);
-            assert_eq!(filter.rng_state, rng_state_before);
+            assert_eq!(filter.rng, rng_before);
             assert!(filter.relocation_log.is_empty());
             for previous in &accepted {
                 assert!(
@@ -890,6 +1491,110 @@ mod tests {
         assert!(observed_relocation);
     }
 
+    #[test]
+    fn estimated_cardinality_counts_distinct_items() {
+        let mut filter = CuckooFilter::new(10_000, 0.001).unwrap();
+        assert_eq!(filter.estimated_cardinality(), 0.0);
+        for value in 0_u64..1_000 {
+            assert!(filter.insert(&value));
+        }
+        let estimate = filter.estimated_cardinality();
+        assert!((estimate - 1_000.0).abs() < 10.0, "estimate = {estimate}");
+
+        // Repeated insertions occupy extra slots but are not new items.
+        for value in 0_u64..500 {
+            assert!(filter.insert(&value));
+        }
+        assert_eq!(filter.inserted_items(), 1_500);
+        assert_eq!(filter.estimated_cardinality(), estimate);
+    }
+
+    #[test]
+    fn estimated_cardinality_corrects_fingerprint_collisions() {
+        // 64 buckets of 6-bit fingerprints leave about 2_000 distinct keys.
+        let mut filter = CuckooFilter::with_parameters(64, 6, 500).unwrap();
+        let items = 200_u64;
+        for value in 0..items {
+            assert!(filter.insert(&value));
+        }
+
+        let mut keys: Vec<_> = filter
+            .iter_fingerprints()
+            .map(|(bucket, _, fingerprint)| {
+                (
+                    fingerprint,
+                    bucket.min(filter.alternate_index(bucket, fingerprint)),
+                )
+            })
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let lower_bound = keys.len() as f64;
+        let estimate = filter.estimated_cardinality();
+
+        assert!(lower_bound < items as f64);
+        assert!(estimate > lower_bound);
+        assert!(
+            (estimate - items as f64).abs() < (lower_bound - items as f64).abs(),
+            "estimate = {estimate}, lower bound = {lower_bound}"
+        );
+    }
+
+    #[test]
+    fn occupancy_histogram_shifts_toward_full_buckets_with_load() {
+        let mut filter = CuckooFilter::with_parameters(256, 12, 500).unwrap();
+        assert_eq!(filter.occupancy_histogram(), vec![256, 0, 0, 0, 0]);
+
+        for value in 0_u64..128 {
+            assert!(filter.insert(&value));
+        }
+        let light = filter.occupancy_histogram();
+        assert_eq!(light.iter().sum::<usize>(), 256);
+        assert!(light[0] + light[1] > 200, "{light:?}");
+
+        for value in 128_u64..900 {
+            assert!(filter.insert(&value));
+        }
+        let heavy = filter.occupancy_histogram();
+        assert_eq!(heavy.iter().sum::<usize>(), 256);
+        assert_eq!(
+            heavy
+                .iter()
+                .enumerate()
+                .map(|(occupied, &buckets)| occupied * buckets)
+                .sum::<usize>(),
+            900
+        );
+        assert!(heavy[3] + heavy[4] > 200, "{heavy:?}");
+
+        let wide = CuckooFilter::with_parameters_and_bucket_size(16, 8, 12, 500).unwrap();
+        assert_eq!(wide.occupancy_histogram().len(), 9);
+    }
+
+    #[test]
+    fn fingerprint_collisions_grow_with_load() {
+        let mut filter = CuckooFilter::with_parameters_and_bucket_size(64, 8, 6, 500).unwrap();
+        assert_eq!(filter.observed_fingerprint_collisions(), 0);
+
+        let mut collisions = Vec::new();
+        for value in 0_u64..448 {
+            assert!(filter.insert(&value));
+            if (value + 1) % 112 == 0 {
+                collisions.push(filter.observed_fingerprint_collisions());
+            }
+        }
+        assert!(
+            collisions[0] < collisions[3] && collisions[1] < collisions[3],
+            "{collisions:?}"
+        );
+
+        let mut duplicate = CuckooFilter::with_parameters(64, 16, 500).unwrap();
+        assert!(duplicate.insert(&7_u64));
+        assert!(duplicate.insert(&7_u64));
+        assert!(duplicate.insert(&7_u64));
+        assert_eq!(duplicate.observed_fingerprint_collisions(), 2);
+    }
+
     #[test]
     fn empirical_false_positive_rate_meets_requested_bound() {
         let target_rate = 0.01;
@@ -915,7 +1620,8 @@ mod tests {
 
         let per_slot_match =
             filter.load_factor() * fingerprint_collision_probability(filter.fingerprint_bits());
-        let load_aware_prediction = 1.0 - (1.0 - per_slot_match).powi((2 * BUCKET_SIZE) as i32);
+        let load_aware_prediction =
+            1.0 - (1.0 - per_slot_match).powi((2 * DEFAULT_BUCKET_SIZE) as i32);
         let prediction_standard_error =
             (load_aware_prediction * (1.0 - load_aware_prediction) / trials as f64).sqrt();
         assert!(
@@ -948,4 +1654,27 @@ mod tests {
         assert!(filter.delete(&colliding_non_member));
         assert!(!filter.contains(&inserted));
     }
+
+    #[test]
+    fn strict_delete_refuses_shared_fingerprints() {
+        let mut filter = CuckooFilter::with_parameters(2, 6, 50).unwrap();
+        assert_eq!(filter.delete_strict(&0_u64), DeleteOutcome::NotFound);
+
+        let inserted = 0_u64;
+        assert!(filter.insert(&inserted));
+        let colliding = (1_u64..100_000)
+            .find(|candidate| filter.contains(candidate))
+            .expect("small fingerprints should yield a colliding fixture");
+        assert!(filter.insert(&colliding));
+
+        assert_eq!(filter.delete_strict(&inserted), DeleteOutcome::Ambiguous);
+        assert_eq!(filter.delete_strict(&colliding), DeleteOutcome::Ambiguous);
+        assert_eq!(filter.inserted_items(), 2);
+        assert!(filter.contains(&inserted));
+
+        assert!(filter.delete(&colliding));
+        assert_eq!(filter.delete_strict(&inserted), DeleteOutcome::Deleted);
+        assert!(filter.is_empty());
+        assert_eq!(filter.delete_strict(&inserted), DeleteOutcome::NotFound);
+    }
 }
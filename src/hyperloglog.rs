@@ -35,6 +35,33 @@
 //! standard error describes the estimator's expected statistical variation;
 //! it is not a deterministic bound on every estimate.
 //!
+//! # Exact small-range mode
+//!
+//! [`HyperLogLog::with_exact_threshold`] additionally keeps the distinct
+//! 64-bit item hashes until more than the configured threshold have been
+//! seen. While that set is active, cardinality, union, intersection and
+//! Jaccard results are exact with respect to those hashes. Once the threshold
+//! is exceeded the set is dropped and the sketch continues from its registers,
+//! which are maintained throughout, so promotion loses no information.
+//!
+//! # Sliding windows
+//!
+//! Registers cannot be decremented, so an expired time bucket cannot be
+//! subtracted from a merged sketch. [`SlidingHyperLogLog`] instead keeps one
+//! sketch per bucket in a ring; [`SlidingHyperLogLog::advance`] discards the
+//! oldest bucket and [`SlidingHyperLogLog::estimate`] merges the live ones.
+//!
+//! # Compact encoding
+//!
+//! [`HyperLogLog::to_bytes_compact`] writes the precision byte followed by the
+//! registers, with every run of zero registers replaced by a `0x00` marker
+//! and the run length as an unsigned LEB128 varint. Non-zero registers are
+//! written as themselves. A sparse sketch therefore costs a few bytes per
+//! occupied register instead of `2^p` bytes.
+//! [`HyperLogLog::from_bytes_compact`] rejects any payload that does not
+//! decode to exactly `2^p` valid registers. The encoding carries registers
+//! only, so an exact-mode hash set is not preserved.
+//!
 //! # Intersection and Jaccard limitations
 //!
 //! HyperLogLog natively represents unions through register-wise maxima. This
@@ -58,10 +85,13 @@
 //!
 //! [Ertl 2017]: https://arxiv.org/pdf/1702.01284
 
-use std::hash::Hash;
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
 
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
 use crate::jacard::{JacardIndex, inclusion_exclusion_estimates};
-use crate::{SketchError, seeded_hash64};
+use crate::{Set, SketchError, seeded_hash64};
 
 const MIN_PRECISION: u8 = 4;
 const MAX_PRECISION: u8 = 18;
@@ -70,6 +100,7 @@ const HASH_SEED: u64 = 0xD6E8_FD93_5E7A_4A6D;
 const HASH_BITS: usize = u64::BITS as usize;
 const MAX_REGISTER_COUNTS: usize = HASH_BITS + 2;
 const MAX_LIKELIHOOD_EPSILON: f64 = 1e-2;
+const MAX_INTERSECTION_ARITY: usize = 4;
 
 fn relative_standard_error(precision: u8) -> f64 {
     RELATIVE_STANDARD_ERROR_FACTOR / ((1_usize << precision) as f64).sqrt()
@@ -93,6 +124,11 @@ fn relative_standard_error(precision: u8) -> f64 {
 pub struct HyperLogLog {
     precision: u8,
     registers: Vec<u8>,
+    exact_threshold: usize,
+    exact: Option<Set<u64>>,
+    /// Estimate stored by [`HyperLogLog::estimate_cached`], cleared by every
+    /// mutation that can change it.
+    cached_estimate: Option<f64>,
 }
 
 impl HyperLogLog {
@@ -113,9 +149,44 @@ impl HyperLogLog {
         Ok(Self {
             precision,
             registers: vec![0; register_count],
+            exact_threshold: 0,
+            exact: None,
+            cached_estimate: None,
         })
     }
 
+    /// Creates a HyperLogLog with precision `p` that counts exactly until more
+    /// than `exact_threshold` distinct hashes have been observed.
+    ///
+    /// A threshold of zero disables the exact mode and is equivalent to
+    /// [`Self::new`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use sketches::hyperloglog::HyperLogLog;
+    ///
+    /// let mut hll = HyperLogLog::with_exact_threshold(12, 128).unwrap();
+    /// for i in 0..10_u64 {
+    ///     hll.add(&i);
+    /// }
+    /// assert!(hll.is_exact());
+    /// assert_eq!(hll.count(), 10);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when precision is out of range.
+    pub fn with_exact_threshold(
+        precision: u8,
+        exact_threshold: usize,
+    ) -> Result<Self, SketchError> {
+        let mut sketch = Self::new(precision)?;
+        sketch.exact_threshold = exact_threshold;
+        if exact_threshold > 0 {
+            sketch.exact = Some(Set::default());
+        }
+        Ok(sketch)
+    }
+
     /// Creates a HyperLogLog from a target nominal relative standard error.
     ///
     /// Selects the smallest supported precision whose nominal relative standard
@@ -146,6 +217,33 @@ impl HyperLogLog {
         Self::new(precision)
     }
 
+    /// Creates a HyperLogLog sized for an expected cardinality and an
+    /// acceptable absolute error at that cardinality.
+    ///
+    /// This is [`Self::with_error_rate`] with the target
+    /// `absolute_error / expected`, so the same caveats apply: the error is a
+    /// standard error, and the relative target must be reachable at precision
+    /// 18.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `expected` is zero, when
+    /// `absolute_error` is zero or not below `expected`, or when the implied
+    /// relative error is below the supported minimum.
+    pub fn for_cardinality(expected: u64, absolute_error: u64) -> Result<Self, SketchError> {
+        if expected == 0 {
+            return Err(SketchError::InvalidParameter(
+                "expected cardinality must be greater than zero",
+            ));
+        }
+        if absolute_error == 0 || absolute_error >= expected {
+            return Err(SketchError::InvalidParameter(
+                "absolute error must be greater than zero and less than the expected cardinality",
+            ));
+        }
+
+        Self::with_error_rate(absolute_error as f64 / expected as f64)
+    }
+
     /// Returns the configured precision.
     pub fn precision(&self) -> u8 {
         self.precision
@@ -156,6 +254,44 @@ impl HyperLogLog {
         self.registers.len()
     }
 
+    /// Returns the configured exact-mode threshold; zero when disabled.
+    pub fn exact_threshold(&self) -> usize {
+        self.exact_threshold
+    }
+
+    /// Returns `true` while estimates come from the exact hash set rather
+    /// than the registers.
+    pub fn is_exact(&self) -> bool {
+        self.exact.is_some()
+    }
+
+    /// Returns a read-only view of the register array.
+    ///
+    /// Registers are updated in exact mode as well, so they always describe
+    /// every observed item.
+    ///
+    /// Register `i` holds the largest observed rank for hashes whose leading
+    /// `precision` bits equal `i`.
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// Returns the size of the register storage in bytes.
+    ///
+    /// The dense representation stores one byte per register, so this is
+    /// `2^precision`, plus eight bytes per hash held while in exact mode. It
+    /// counts only that payload, not the fixed-size struct fields, set
+    /// bookkeeping or any unused allocation capacity, which makes it
+    /// comparable across precisions and against other cardinality sketches
+    /// when choosing a memory budget.
+    pub fn memory_bytes(&self) -> usize {
+        let exact_bytes = self
+            .exact
+            .as_ref()
+            .map_or(0, |exact| exact.len() * core::mem::size_of::<u64>());
+        core::mem::size_of_val(self.registers.as_slice()) + exact_bytes
+    }
+
     /// Returns the nominal relative standard error: `1.04 / sqrt(m)`.
     ///
     /// This is the expected statistical variation for the configured register
@@ -177,6 +313,15 @@ impl HyperLogLog {
 
         if rank > self.registers[index] {
             self.registers[index] = rank;
+            self.cached_estimate = None;
+        }
+
+        if let Some(exact) = &mut self.exact {
+            self.cached_estimate = None;
+            exact.insert(hash);
+            if exact.len() > self.exact_threshold {
+                self.exact = None;
+            }
         }
     }
 
@@ -187,8 +332,41 @@ impl HyperLogLog {
     /// estimator. (The paper's literal Algorithm 2 describes sketch merging,
     /// not cardinality estimation.)
     ///
+    /// In exact mode this is the number of distinct observed hashes instead.
+    ///
+    /// A value stored by [`Self::estimate_cached`] is returned without
+    /// rescanning the registers until the next mutation.
+    ///
     /// [Ertl 2017]: https://arxiv.org/pdf/1702.01284
     pub fn estimate(&self) -> f64 {
+        self.cached_estimate
+            .unwrap_or_else(|| self.compute_estimate())
+    }
+
+    /// Returns [`Self::estimate`], storing it until the next mutation.
+    ///
+    /// Computing an estimate scans all `2^precision` registers. When counts
+    /// are polled more often than the sketch changes, this variant pays that
+    /// cost once per change: [`Self::add`] (when it changes a register or the
+    /// exact set), [`Self::merge`], [`Self::apply_diff`], and [`Self::clear`]
+    /// discard the stored value. Later calls to [`Self::estimate`] and
+    /// [`Self::count`] through a shared reference reuse it as well.
+    pub fn estimate_cached(&mut self) -> f64 {
+        let estimate = self.estimate();
+        self.cached_estimate = Some(estimate);
+        estimate
+    }
+
+    /// Returns [`Self::estimate_cached`] rounded to `u64`.
+    pub fn count_cached(&mut self) -> u64 {
+        self.estimate_cached().round() as u64
+    }
+
+    fn compute_estimate(&self) -> f64 {
+        if let Some(exact) = &self.exact {
+            return exact.len() as f64;
+        }
+
         let mut counts = [0_usize; MAX_REGISTER_COUNTS];
         for &register in &self.registers {
             counts[register as usize] += 1;
@@ -203,9 +381,75 @@ impl HyperLogLog {
         self.estimate().round() as u64
     }
 
-    /// Resets all registers to zero.
+    /// Resets all registers to zero and re-enters exact mode if configured.
     pub fn clear(&mut self) {
         self.registers.fill(0);
+        self.exact = (self.exact_threshold > 0).then(Set::default);
+        self.cached_estimate = None;
+    }
+
+    /// Returns a copy folded down to `target_precision`.
+    ///
+    /// Each target register covers a contiguous group of `2^(p - target_p)`
+    /// source registers. The discarded index bits become the leading bits of
+    /// the longer hash suffix, so a nonempty register with a nonzero group
+    /// offset contributes the rank of that offset's first set bit, while the
+    /// group's first register contributes its rank plus the precision
+    /// difference. The result is identical to adding the same items directly
+    /// to a sketch of the target precision, which makes it merge-compatible
+    /// with such sketches. Passing the current precision returns a clone.
+    /// The exact-mode threshold and any exact hash set carry over unchanged,
+    /// since item hashes do not depend on precision.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `target_precision` is
+    /// outside `[4, 18]` or greater than the current precision.
+    pub fn downsample(&self, target_precision: u8) -> Result<Self, SketchError> {
+        if target_precision > self.precision {
+            return Err(SketchError::InvalidParameter(
+                "target precision must not exceed the current precision",
+            ));
+        }
+        let mut result = Self::new(target_precision)?;
+        result.exact_threshold = self.exact_threshold;
+        result.exact = self.exact.clone();
+
+        let precision_difference = u32::from(self.precision - target_precision);
+        let group_size = 1_usize << precision_difference;
+        for (index, register) in result.registers.iter_mut().enumerate() {
+            let group = &self.registers[index * group_size..(index + 1) * group_size];
+
+            // The first register's hashes had zeros in every discarded index
+            // bit, so their suffix ranks shift by the precision difference.
+            let mut rank = match group[0] {
+                0 => 0,
+                source_rank => source_rank + precision_difference as u8,
+            };
+            for (offset, &source_rank) in group.iter().enumerate().skip(1) {
+                if source_rank != 0 {
+                    let offset_rank =
+                        (offset as u64).leading_zeros() + precision_difference + 1 - u64::BITS;
+                    rank = rank.max(offset_rank as u8);
+                }
+            }
+            *register = rank;
+        }
+        Ok(result)
+    }
+
+    /// Returns `true` when [`Self::merge`] accepts `other` without any loss of
+    /// precision.
+    ///
+    /// This is [`Self::compatible`]; sketches that fail it can still be
+    /// combined with [`Self::merge_or_downsample`].
+    pub fn can_merge(&self, other: &Self) -> bool {
+        self.compatible(other)
+    }
+
+    /// Returns `true` when [`Self::merge`] accepts `other`, which requires
+    /// equal precision.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.precision == other.precision
     }
 
     /// Merges another HyperLogLog into this sketch.
@@ -215,12 +459,17 @@ impl HyperLogLog {
     /// calculated by the Algorithm 8 maximum-likelihood estimator used by
     /// [`Self::estimate`].
     ///
+    /// When both sketches are in exact mode their hash sets are unioned, and
+    /// the result leaves exact mode if the union exceeds this sketch's
+    /// threshold. If either sketch has already left exact mode, so does the
+    /// result.
+    ///
     /// [Ertl 2017]: https://arxiv.org/pdf/1702.01284
     ///
     /// # Errors
     /// Returns [`SketchError::IncompatibleSketches`] when precision differs.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
-        if self.precision != other.precision {
+        if !self.compatible(other) {
             return Err(SketchError::IncompatibleSketches(
                 "precision must match for merge",
             ));
@@ -229,9 +478,191 @@ impl HyperLogLog {
         for (left, right) in self.registers.iter_mut().zip(other.registers.iter()) {
             *left = (*left).max(*right);
         }
+        self.cached_estimate = None;
+
+        match (&mut self.exact, &other.exact) {
+            (Some(exact), Some(other_exact)) => {
+                exact.extend(other_exact.iter().copied());
+                if exact.len() > self.exact_threshold {
+                    self.exact = None;
+                }
+            }
+            _ => self.exact = None,
+        }
         Ok(())
     }
 
+    /// Merges `other` after folding whichever sketch is finer down to the
+    /// coarser precision with [`Self::downsample`].
+    ///
+    /// Unlike [`Self::merge`], this accepts any precision. When `other` is
+    /// coarser, this sketch is downsampled in place first, so its precision
+    /// can only decrease. Because downsampling is exact, the result equals a
+    /// sketch of the coarser precision that observed both streams, and the
+    /// union carries that precision's error.
+    ///
+    /// Returns the precision of the merged sketch, so callers can detect a
+    /// precision loss without comparing [`Self::precision`] before and after.
+    /// Use [`Self::can_merge`] first when any loss should be refused instead.
+    ///
+    /// # Errors
+    /// Propagates any error from [`Self::downsample`] or [`Self::merge`].
+    /// Neither fails for sketches built through the public API, because the
+    /// coarser precision is valid for both inputs.
+    pub fn merge_or_downsample(&mut self, other: &Self) -> Result<u8, SketchError> {
+        let target_precision = self.precision.min(other.precision);
+        let downsampled_other = if other.precision > target_precision {
+            Some(other.downsample(target_precision)?)
+        } else {
+            None
+        };
+        if self.precision > target_precision {
+            *self = self.downsample(target_precision)?;
+        }
+
+        self.merge(downsampled_other.as_ref().unwrap_or(other))?;
+        Ok(target_precision)
+    }
+
+    /// Returns the registers where this sketch differs from `base`, as
+    /// `(index, value)` pairs in index order.
+    ///
+    /// When this sketch descends from `base` by additions or merges, every
+    /// changed register has grown, so [`Self::apply_diff`] on a copy of `base`
+    /// reproduces this sketch's registers while shipping only the changes.
+    /// Exact-mode hash sets are not part of the diff.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when precision differs.
+    pub fn diff(&self, base: &Self) -> Result<Vec<(u32, u8)>, SketchError> {
+        if self.precision != base.precision {
+            return Err(SketchError::IncompatibleSketches(
+                "precision must match for diff",
+            ));
+        }
+
+        Ok(self
+            .registers
+            .iter()
+            .zip(base.registers.iter())
+            .enumerate()
+            .filter(|(_, (current, base))| current != base)
+            .map(|(index, (&current, _))| (index as u32, current))
+            .collect())
+    }
+
+    /// Applies a register diff produced by [`Self::diff`], taking the
+    /// register-wise maximum like [`Self::merge`].
+    ///
+    /// Applying any register change leaves exact mode, because the diff does
+    /// not carry the hashes behind it. The whole diff is validated before any
+    /// register changes.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when an index lies
+    /// outside this sketch's `2^precision` registers or a value exceeds the
+    /// largest rank possible at this precision, which indicates a diff taken
+    /// at a different precision.
+    pub fn apply_diff(&mut self, diff: &[(u32, u8)]) -> Result<(), SketchError> {
+        let max_rank = (HASH_BITS - self.precision as usize + 1) as u8;
+        if diff
+            .iter()
+            .any(|&(index, value)| index as usize >= self.registers.len() || value > max_rank)
+        {
+            return Err(SketchError::IncompatibleSketches(
+                "diff does not match this sketch's precision",
+            ));
+        }
+
+        for &(index, value) in diff {
+            let register = &mut self.registers[index as usize];
+            if value > *register {
+                *register = value;
+                self.exact = None;
+                self.cached_estimate = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the precision and registers with zero runs compressed; see
+    /// [the module documentation](crate::hyperloglog#compact-encoding).
+    ///
+    /// The exact-mode hash set and threshold are not encoded, so a decoded
+    /// sketch always estimates from its registers.
+    pub fn to_bytes_compact(&self) -> Vec<u8> {
+        let mut bytes = vec![self.precision];
+        let mut zero_run = 0_usize;
+        for &register in &self.registers {
+            if register == 0 {
+                zero_run += 1;
+                continue;
+            }
+            if zero_run > 0 {
+                push_zero_run(&mut bytes, zero_run);
+                zero_run = 0;
+            }
+            bytes.push(register);
+        }
+        if zero_run > 0 {
+            push_zero_run(&mut bytes, zero_run);
+        }
+        bytes
+    }
+
+    /// Decodes a sketch written by [`Self::to_bytes_compact`].
+    ///
+    /// The result has exact mode disabled, like [`Self::new`].
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when the payload is empty,
+    /// the precision is out of range, a register exceeds the largest rank
+    /// possible at that precision, a zero run is empty or truncated, or the
+    /// registers do not decode to exactly `2^precision` entries.
+    pub fn from_bytes_compact(bytes: &[u8]) -> Result<Self, SketchError> {
+        let (&precision, mut rest) = bytes
+            .split_first()
+            .ok_or(SketchError::InvalidParameter("compact payload is empty"))?;
+        let mut sketch = Self::new(precision)?;
+        let register_count = sketch.registers.len();
+        let max_rank = (HASH_BITS - precision as usize + 1) as u8;
+
+        let mut index = 0_usize;
+        while let Some((&byte, tail)) = rest.split_first() {
+            rest = tail;
+            if byte == 0 {
+                let run = read_zero_run(&mut rest)?;
+                // Registers start at zero, so a run only advances the index.
+                index = index
+                    .checked_add(run)
+                    .filter(|&end| end <= register_count)
+                    .ok_or(SketchError::InvalidParameter(
+                        "compact payload decodes to more than 2^precision registers",
+                    ))?;
+                continue;
+            }
+            if byte > max_rank {
+                return Err(SketchError::InvalidParameter(
+                    "compact register exceeds the largest rank at this precision",
+                ));
+            }
+            let register = sketch
+                .registers
+                .get_mut(index)
+                .ok_or(SketchError::InvalidParameter(
+                    "compact payload decodes to more than 2^precision registers",
+                ))?;
+            *register = byte;
+            index += 1;
+        }
+        if index != register_count {
+            return Err(SketchError::InvalidParameter(
+                "compact payload decodes to fewer than 2^precision registers",
+            ));
+        }
+        Ok(sketch)
+    }
+
     /// Returns the estimated union cardinality `|A ∪ B|`.
     ///
     /// This clones `self`, merges `other` into that clone using register-wise
@@ -312,6 +743,139 @@ impl HyperLogLog {
         Ok(inclusion_exclusion_estimates(a, b, union).intersection)
     }
 
+    /// Returns the estimated intersection cardinality of up to four sketches.
+    ///
+    /// This expands inclusion-exclusion over every non-empty subset `S` of
+    /// the inputs:
+    /// `|A_1 ∩ ... ∩ A_n| ≈ Σ (-1)^(|S| + 1) |∪ S|`.
+    /// Each of the `2^n - 1` union estimates is built by merging one sketch
+    /// into a previously built union, so this clones and estimates that many
+    /// sketches. The arity is capped at four to bound that cost. One sketch
+    /// returns its own estimate.
+    ///
+    /// The output is clamped to `[0, min |A_i|]`. The statistical limitations
+    /// of [`Self::intersection_estimate`] apply with more force here: the
+    /// alternating sum adds the errors of fifteen union estimates for four
+    /// inputs, each scaled to the size of the union rather than of the
+    /// intersection. Small intersections of large sets are not recoverable.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for an empty slice or more
+    /// than four sketches, and [`SketchError::IncompatibleSketches`] when
+    /// precisions differ.
+    pub fn intersection_estimate_many(sketches: &[&Self]) -> Result<f64, SketchError> {
+        let Some(first) = sketches.first() else {
+            return Err(SketchError::InvalidParameter(
+                "intersection_estimate_many requires at least one sketch",
+            ));
+        };
+        if sketches.len() > MAX_INTERSECTION_ARITY {
+            return Err(SketchError::InvalidParameter(
+                "intersection_estimate_many accepts at most four sketches",
+            ));
+        }
+        if sketches.iter().any(|sketch| !first.compatible(sketch)) {
+            return Err(SketchError::IncompatibleSketches(
+                "precision must match for merge",
+            ));
+        }
+
+        // `unions[mask]` is the union of the sketches whose bits are set in
+        // `mask`, built from the union without its lowest sketch.
+        let subsets = 1_usize << sketches.len();
+        let mut unions: Vec<Option<Self>> = Vec::with_capacity(subsets);
+        unions.push(None);
+        let mut intersection = 0.0;
+        for mask in 1..subsets {
+            let lowest = mask.trailing_zeros() as usize;
+            let mut union = match unions[mask & (mask - 1)].as_ref() {
+                Some(rest) => rest.clone(),
+                None => (*sketches[lowest]).clone(),
+            };
+            if mask & (mask - 1) != 0 {
+                union.merge(sketches[lowest])?;
+            }
+
+            let estimate = union.estimate();
+            if mask.count_ones() % 2 == 1 {
+                intersection += estimate;
+            } else {
+                intersection -= estimate;
+            }
+            unions.push(Some(union));
+        }
+
+        let smallest = sketches
+            .iter()
+            .map(|sketch| sketch.estimate())
+            .fold(f64::INFINITY, f64::min);
+        Ok(intersection.clamp(0.0, smallest))
+    }
+
+    /// Returns each sketch's estimated marginal contribution to the union of
+    /// all inputs.
+    ///
+    /// Entry `i` is the leave-one-out difference `|∪ all| - |∪ all but i|`,
+    /// the number of distinct items only sketch `i` observed. Items seen by two
+    /// or more inputs are attributed to none of them, so the contributions sum
+    /// to the union only when the inputs are disjoint. Each entry is clamped
+    /// at zero because the difference of two estimates can be slightly
+    /// negative. A single sketch contributes its own estimate, and an empty
+    /// slice returns an empty vector.
+    ///
+    /// Prefix and suffix unions are built once, so this clones and estimates
+    /// about `3n` sketches rather than `n^2`. Each difference carries the
+    /// error of two union-sized estimates, so a small contribution to a large
+    /// union is dominated by noise.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when precisions differ.
+    pub fn union_contributions(sketches: &[&Self]) -> Result<Vec<f64>, SketchError> {
+        let Some(first) = sketches.first() else {
+            return Ok(Vec::new());
+        };
+        if sketches.iter().any(|sketch| !first.compatible(sketch)) {
+            return Err(SketchError::IncompatibleSketches(
+                "precision must match for merge",
+            ));
+        }
+
+        // `prefixes[i]` is the union of `sketches[..i]` and `suffixes[i]` the
+        // union of `sketches[i..]`; `None` stands for the empty union.
+        let count = sketches.len();
+        let mut prefixes: Vec<Option<Self>> = Vec::with_capacity(count + 1);
+        prefixes.push(None);
+        for &sketch in sketches {
+            let mut union = sketch.clone();
+            if let Some(Some(previous)) = prefixes.last() {
+                union.merge(previous)?;
+            }
+            prefixes.push(Some(union));
+        }
+        let mut suffixes: Vec<Option<Self>> = Vec::with_capacity(count + 1);
+        suffixes.push(None);
+        for &sketch in sketches.iter().rev() {
+            let mut union = sketch.clone();
+            if let Some(Some(next)) = suffixes.last() {
+                union.merge(next)?;
+            }
+            suffixes.push(Some(union));
+        }
+        suffixes.reverse();
+
+        let total = prefixes[count].as_ref().map_or(0.0, Self::estimate);
+        let mut contributions = Vec::with_capacity(count);
+        for index in 0..count {
+            let others = match (&prefixes[index], &suffixes[index + 1]) {
+                (Some(before), Some(after)) => before.union_estimate(after)?,
+                (Some(union), None) | (None, Some(union)) => union.estimate(),
+                (None, None) => 0.0,
+            };
+            contributions.push((total - others).max(0.0));
+        }
+        Ok(contributions)
+    }
+
     /// Returns the estimated Jaccard index `|A ∩ B| / |A ∪ B|`.
     ///
     /// Jaccard index is:
@@ -469,15 +1033,173 @@ impl HyperLogLog {
     }
 }
 
+/// Appends a zero-run marker followed by `run` as an unsigned LEB128 varint.
+fn push_zero_run(bytes: &mut Vec<u8>, mut run: usize) {
+    bytes.push(0);
+    while run >= 0x80 {
+        bytes.push((run as u8 & 0x7F) | 0x80);
+        run >>= 7;
+    }
+    bytes.push(run as u8);
+}
+
+/// Reads the varint run length following a zero-run marker.
+fn read_zero_run(bytes: &mut &[u8]) -> Result<usize, SketchError> {
+    let mut run = 0_usize;
+    let mut shift = 0;
+    loop {
+        let (&byte, tail) = bytes.split_first().ok_or(SketchError::InvalidParameter(
+            "compact zero run is truncated",
+        ))?;
+        *bytes = tail;
+        let digit = usize::from(byte & 0x7F);
+        if shift >= usize::BITS || (digit << shift) >> shift != digit {
+            return Err(SketchError::InvalidParameter(
+                "compact zero run length overflows",
+            ));
+        }
+        run |= digit << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    if run == 0 {
+        return Err(SketchError::InvalidParameter("compact zero run is empty"));
+    }
+    Ok(run)
+}
+
 impl JacardIndex for HyperLogLog {
     fn jaccard_index(&self, other: &Self) -> Result<f64, SketchError> {
         HyperLogLog::jaccard_index(self, other)
     }
 }
 
+/// Distinct-count estimator over a sliding window of time buckets.
+///
+/// The window holds a ring of `buckets` HyperLogLog sketches of the same
+/// precision. Items go into the current bucket; [`Self::advance`] starts a new
+/// bucket and drops the oldest one, so estimates cover the most recent
+/// `buckets` periods.
+///
+/// # Example
+/// ```rust
+/// use sketches::hyperloglog::SlidingHyperLogLog;
+///
+/// let mut window = SlidingHyperLogLog::new(12, 2).unwrap();
+/// for user in 0..1_000_u64 {
+///     window.add(&user);
+/// }
+/// window.advance();
+/// for user in 1_000..1_500_u64 {
+///     window.add(&user);
+/// }
+/// assert!(window.count() > 1_400 && window.count() < 1_600);
+///
+/// window.advance();
+/// assert!(window.count() > 450 && window.count() < 550);
+/// ```
+///
+/// # Representation and complexity
+///
+/// A window of `w` buckets at precision `p` owns `w * 2^p` registers. Adding
+/// an item takes `O(1)` time, advancing takes `O(2^p)` time to reset the
+/// recycled bucket, and estimating takes `O(w * 2^p)` time to merge the live
+/// buckets.
+#[derive(Debug, Clone)]
+pub struct SlidingHyperLogLog {
+    buckets: Vec<HyperLogLog>,
+    current: usize,
+}
+
+impl SlidingHyperLogLog {
+    /// Creates a window of `buckets` sketches with precision `p`.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when precision is out of range
+    /// or `buckets == 0`.
+    pub fn new(precision: u8, buckets: usize) -> Result<Self, SketchError> {
+        if buckets == 0 {
+            return Err(SketchError::InvalidParameter(
+                "buckets must be greater than zero",
+            ));
+        }
+        let sketch = HyperLogLog::new(precision)?;
+        Ok(Self {
+            buckets: vec![sketch; buckets],
+            current: 0,
+        })
+    }
+
+    /// Returns the configured precision.
+    pub fn precision(&self) -> u8 {
+        self.buckets[0].precision()
+    }
+
+    /// Returns the number of buckets in the window.
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns the sketch of the bucket currently receiving items.
+    pub fn current_bucket(&self) -> &HyperLogLog {
+        &self.buckets[self.current]
+    }
+
+    /// Returns `true` when every live bucket is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.iter().all(HyperLogLog::is_empty)
+    }
+
+    /// Adds one item to the current bucket.
+    pub fn add<T: Hash>(&mut self, item: &T) {
+        self.buckets[self.current].add(item);
+    }
+
+    /// Rolls the window forward by one bucket.
+    ///
+    /// The oldest bucket is cleared and becomes the current bucket, so its
+    /// items no longer contribute to [`Self::estimate`].
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % self.buckets.len();
+        self.buckets[self.current].clear();
+    }
+
+    /// Returns the union of all live buckets as a single sketch.
+    pub fn union(&self) -> HyperLogLog {
+        let mut union = self.buckets[self.current].clone();
+        for bucket in &self.buckets {
+            union
+                .merge(bucket)
+                .expect("window buckets share one precision");
+        }
+        union
+    }
+
+    /// Returns the estimated number of distinct items across the window.
+    pub fn estimate(&self) -> f64 {
+        self.union().estimate()
+    }
+
+    /// Returns the window estimate rounded to `u64`.
+    pub fn count(&self) -> u64 {
+        self.estimate().round() as u64
+    }
+
+    /// Clears every bucket.
+    pub fn clear(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+        self.current = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::HyperLogLog;
+    use super::{HyperLogLog, SlidingHyperLogLog};
+    use crate::SketchError;
 
     fn assert_relative_eq(actual: f64, expected: f64, tolerance: f64) {
         let scale = expected.abs().max(1.0);
@@ -495,6 +1217,17 @@ mod tests {
         assert!(HyperLogLog::new(19).is_err());
     }
 
+    #[test]
+    fn memory_bytes_reports_dense_register_storage() {
+        for precision in [4, 10, 14, 18] {
+            let mut sketch = HyperLogLog::new(precision).unwrap();
+            assert_eq!(sketch.memory_bytes(), 1 << precision);
+            sketch.add(&"item");
+            assert_eq!(sketch.memory_bytes(), 1 << precision);
+            assert_eq!(sketch.downsample(4).unwrap().memory_bytes(), 1 << 4);
+        }
+    }
+
     #[test]
     fn error_rate_constructor_validates_input() {
         assert!(HyperLogLog::with_error_rate(0.0).is_err());
@@ -516,6 +1249,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cardinality_constructor_converts_absolute_error() {
+        assert!(HyperLogLog::for_cardinality(0, 1).is_err());
+        assert!(HyperLogLog::for_cardinality(1_000, 0).is_err());
+        assert!(HyperLogLog::for_cardinality(1_000, 1_000).is_err());
+        assert!(HyperLogLog::for_cardinality(1_000_000, 1).is_err());
+
+        for (expected, absolute_error) in [(1_000_u64, 100_u64), (1_000_000, 10_000), (50_000, 250)]
+        {
+            let hll = HyperLogLog::for_cardinality(expected, absolute_error).unwrap();
+            let target = absolute_error as f64 / expected as f64;
+            assert!(hll.expected_relative_error() * expected as f64 <= absolute_error as f64);
+            assert_eq!(
+                hll.precision(),
+                HyperLogLog::with_error_rate(target).unwrap().precision()
+            );
+        }
+    }
+
     #[test]
     fn error_rate_constructor_enforces_supported_boundary() {
         let minimum_supported = HyperLogLog::new(super::MAX_PRECISION)
@@ -670,6 +1422,170 @@ mod tests {
         );
     }
 
+    #[test]
+    fn downsample_matches_direct_ingestion_at_the_target_precision() {
+        let mut source = HyperLogLog::new(14).unwrap();
+        let mut direct = HyperLogLog::new(12).unwrap();
+        for value in 0_u64..50_000 {
+            source.add(&value);
+            direct.add(&value);
+        }
+
+        let downsampled = source.downsample(12).unwrap();
+        assert_eq!(downsampled.precision(), 12);
+        assert_eq!(downsampled.registers(), direct.registers());
+
+        let exact = 50_000.0;
+        let relative_error = (downsampled.estimate() - exact).abs() / exact;
+        assert!(
+            relative_error <= 4.0 * downsampled.expected_relative_error(),
+            "estimate={} rel_error={relative_error}",
+            downsampled.estimate()
+        );
+
+        // The downsampled sketch merges like one built at the target precision.
+        let mut other = HyperLogLog::new(12).unwrap();
+        other.add(&"extra");
+        let mut aligned = downsampled.clone();
+        aligned.merge(&other).unwrap();
+        direct.add(&"extra");
+        assert_eq!(aligned.registers(), direct.registers());
+
+        let to_minimum = source.downsample(4).unwrap();
+        let mut direct_minimum = HyperLogLog::new(4).unwrap();
+        for value in 0_u64..50_000 {
+            direct_minimum.add(&value);
+        }
+        assert_eq!(to_minimum.registers(), direct_minimum.registers());
+
+        assert_eq!(
+            source.downsample(14).unwrap().registers(),
+            source.registers()
+        );
+        assert!(source.downsample(15).is_err());
+        assert!(source.downsample(3).is_err());
+        assert!(
+            HyperLogLog::new(12)
+                .unwrap()
+                .downsample(4)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn diff_round_trips_register_changes() {
+        let mut base = HyperLogLog::new(10).unwrap();
+        for value in 0..2_000_u64 {
+            base.add(&value);
+        }
+        let mut current = base.clone();
+        for value in 2_000..2_100_u64 {
+            current.add(&value);
+        }
+
+        let diff = current.diff(&base).unwrap();
+        assert!(!diff.is_empty() && diff.len() < 100);
+        assert!(diff.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let mut replica = base.clone();
+        replica.apply_diff(&diff).unwrap();
+        assert_eq!(replica.registers(), current.registers());
+        assert!(current.diff(&current).unwrap().is_empty());
+
+        assert!(current.diff(&HyperLogLog::new(11).unwrap()).is_err());
+        let before = replica.clone();
+        assert!(replica.apply_diff(&[(0, 1), (1 << 10, 1)]).is_err());
+        assert!(replica.apply_diff(&[(0, 56)]).is_err());
+        assert_eq!(replica.registers(), before.registers());
+
+        let mut exact = HyperLogLog::with_exact_threshold(10, 64).unwrap();
+        exact.add(&1_u64);
+        exact.apply_diff(&[]).unwrap();
+        assert!(exact.is_exact());
+        exact.apply_diff(&diff).unwrap();
+        assert!(!exact.is_exact());
+    }
+
+    #[test]
+    fn cached_estimate_matches_fresh_estimate_until_mutated() {
+        let mut hll = HyperLogLog::new(12).unwrap();
+        assert_eq!(hll.estimate_cached(), 0.0);
+        for value in 0_u64..5_000 {
+            hll.add(&value);
+        }
+        let fresh = hll.compute_estimate();
+        assert_eq!(hll.estimate(), fresh);
+        assert_eq!(hll.estimate_cached(), fresh);
+        assert_eq!(hll.cached_estimate, Some(fresh));
+        assert_eq!(hll.count(), hll.count_cached());
+
+        hll.add(&0_u64);
+        assert_eq!(hll.cached_estimate, Some(fresh));
+        for value in 5_000_u64..6_000 {
+            hll.add(&value);
+        }
+        assert!(hll.estimate() > fresh);
+        assert_eq!(hll.estimate_cached(), hll.compute_estimate());
+
+        let mut other = HyperLogLog::new(12).unwrap();
+        for value in 10_000_u64..20_000 {
+            other.add(&value);
+        }
+        let before_merge = hll.estimate_cached();
+        hll.merge(&other).unwrap();
+        assert!(hll.estimate() > before_merge);
+        assert_eq!(hll.estimate_cached(), hll.compute_estimate());
+
+        let diff = hll.diff(&HyperLogLog::new(12).unwrap()).unwrap();
+        hll.clear();
+        assert_eq!(hll.estimate(), 0.0);
+        hll.estimate_cached();
+        hll.apply_diff(&diff).unwrap();
+        assert_eq!(hll.estimate(), hll.compute_estimate());
+
+        let mut exact = HyperLogLog::with_exact_threshold(12, 100).unwrap();
+        exact.add(&1_u64);
+        assert_eq!(exact.count_cached(), 1);
+        exact.add(&2_u64);
+        assert_eq!(exact.count(), 2);
+    }
+
+    #[test]
+    fn merge_or_downsample_unions_at_the_coarser_precision() {
+        let mut coarse = HyperLogLog::new(12).unwrap();
+        let mut fine = HyperLogLog::new(14).unwrap();
+        for value in 0_u64..30_000 {
+            coarse.add(&value);
+        }
+        for value in 20_000_u64..60_000 {
+            fine.add(&value);
+        }
+        let mut direct = HyperLogLog::new(12).unwrap();
+        for value in 0_u64..60_000 {
+            direct.add(&value);
+        }
+
+        let mut coarse_first = coarse.clone();
+        assert!(!coarse_first.can_merge(&fine));
+        assert_eq!(coarse_first.merge_or_downsample(&fine), Ok(12));
+        assert_eq!(coarse_first.precision(), 12);
+        assert_eq!(coarse_first.registers(), direct.registers());
+        let tolerance = 3.0 * coarse_first.expected_relative_error();
+        assert_relative_eq(coarse_first.estimate(), 60_000.0, tolerance);
+
+        let mut fine_first = fine.clone();
+        assert_eq!(fine_first.merge_or_downsample(&coarse), Ok(12));
+        assert_eq!(fine_first.precision(), 12);
+        assert_eq!(fine_first.estimate(), coarse_first.estimate());
+        assert_eq!(fine_first.registers(), direct.registers());
+
+        let mut same = coarse.clone();
+        assert!(same.can_merge(&coarse));
+        assert_eq!(same.merge_or_downsample(&coarse), Ok(12));
+        assert_eq!(same.registers(), coarse.registers());
+    }
+
     #[test]
     fn merge_rejects_mismatched_precision() {
         let mut left = HyperLogLog::new(10).unwrap();
@@ -697,6 +1613,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn many_way_intersection_recovers_a_known_triple_overlap() {
+        let mut a = HyperLogLog::new(14).unwrap();
+        let mut b = HyperLogLog::new(14).unwrap();
+        let mut c = HyperLogLog::new(14).unwrap();
+        // A = [0, 20_000), B = [10_000, 30_000), C = [15_000, 35_000), so
+        // A ∩ B ∩ C = [15_000, 20_000) holds 5_000 values.
+        for value in 0_u64..20_000 {
+            a.add(&value);
+        }
+        for value in 10_000_u64..30_000 {
+            b.add(&value);
+        }
+        for value in 15_000_u64..35_000 {
+            c.add(&value);
+        }
+
+        let triple = HyperLogLog::intersection_estimate_many(&[&a, &b, &c]).unwrap();
+        assert!((4_000.0..=6_000.0).contains(&triple), "triple={triple}");
+
+        let pair = HyperLogLog::intersection_estimate_many(&[&a, &b]).unwrap();
+        assert_eq!(pair, a.intersection_estimate(&b).unwrap());
+        assert_eq!(
+            HyperLogLog::intersection_estimate_many(&[&a]).unwrap(),
+            a.estimate()
+        );
+
+        assert!(HyperLogLog::intersection_estimate_many(&[]).is_err());
+        assert!(HyperLogLog::intersection_estimate_many(&[&a, &b, &c, &a, &b]).is_err());
+        let coarse = HyperLogLog::new(12).unwrap();
+        assert_eq!(
+            HyperLogLog::intersection_estimate_many(&[&a, &coarse]),
+            Err(SketchError::IncompatibleSketches(
+                "precision must match for merge"
+            ))
+        );
+    }
+
+    #[test]
+    fn union_contributions_recover_exclusive_counts() {
+        let mut a = HyperLogLog::new(14).unwrap();
+        let mut b = HyperLogLog::new(14).unwrap();
+        let mut c = HyperLogLog::new(14).unwrap();
+        // A = [0, 6_000), B = [4_000, 10_000), C = [8_000, 14_000): 4_000,
+        // 2_000, and 4_000 values are exclusive, and 4_000 are shared.
+        for value in 0_u64..6_000 {
+            a.add(&value);
+        }
+        for value in 4_000_u64..10_000 {
+            b.add(&value);
+        }
+        for value in 8_000_u64..14_000 {
+            c.add(&value);
+        }
+
+        let contributions = HyperLogLog::union_contributions(&[&a, &b, &c]).unwrap();
+        for (contribution, exclusive) in contributions.iter().zip([4_000.0, 2_000.0, 4_000.0]) {
+            assert!(
+                (contribution - exclusive).abs() < 500.0,
+                "contributions={contributions:?}"
+            );
+        }
+        // Only the shared values are left unattributed.
+        let mut union = a.clone();
+        union.merge(&b).unwrap();
+        union.merge(&c).unwrap();
+        let union = union.estimate();
+        let attributed: f64 = contributions.iter().sum();
+        assert!(
+            (union - attributed - 4_000.0).abs() < 600.0,
+            "union={union} attributed={attributed}"
+        );
+
+        // Disjoint inputs attribute the whole union.
+        let mut d = HyperLogLog::new(14).unwrap();
+        for value in 20_000_u64..25_000 {
+            d.add(&value);
+        }
+        let disjoint = HyperLogLog::union_contributions(&[&a, &d]).unwrap();
+        let union = a.union_estimate(&d).unwrap();
+        assert!(
+            (disjoint.iter().sum::<f64>() - union).abs() < 300.0,
+            "disjoint={disjoint:?} union={union}"
+        );
+
+        assert_eq!(
+            HyperLogLog::union_contributions(&[&a]).unwrap(),
+            vec![a.estimate()]
+        );
+        assert!(HyperLogLog::union_contributions(&[]).unwrap().is_empty());
+        let coarse = HyperLogLog::new(12).unwrap();
+        assert_eq!(
+            HyperLogLog::union_contributions(&[&a, &coarse]),
+            Err(SketchError::IncompatibleSketches(
+                "precision must match for merge"
+            ))
+        );
+    }
+
+    #[test]
+    fn compact_bytes_shrink_sparse_sketches_and_round_trip() {
+        let mut sparse = HyperLogLog::new(16).unwrap();
+        for value in 0_u64..20 {
+            sparse.add(&value);
+        }
+        let bytes = sparse.to_bytes_compact();
+        assert!(bytes.len() < 200, "len={}", bytes.len());
+        let decoded = HyperLogLog::from_bytes_compact(&bytes).unwrap();
+        assert_eq!(decoded.precision(), 16);
+        assert_eq!(decoded.registers(), sparse.registers());
+        assert_eq!(decoded.estimate(), sparse.estimate());
+
+        let mut dense = HyperLogLog::with_exact_threshold(8, 16).unwrap();
+        for value in 0_u64..10_000 {
+            dense.add(&value);
+        }
+        let decoded = HyperLogLog::from_bytes_compact(&dense.to_bytes_compact()).unwrap();
+        assert_eq!(decoded.registers(), dense.registers());
+        assert_eq!(decoded.exact_threshold(), 0);
+
+        let empty = HyperLogLog::new(4).unwrap();
+        assert_eq!(empty.to_bytes_compact(), vec![4, 0, 16]);
+        assert!(
+            HyperLogLog::from_bytes_compact(&empty.to_bytes_compact())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn compact_bytes_reject_malformed_payloads() {
+        for payload in [
+            &[][..],
+            &[3, 0, 8],
+            &[4, 0, 15],
+            &[4, 0, 17],
+            &[4, 0, 15, 62],
+            &[4, 0, 15, 1, 1],
+            &[4, 0, 0, 0, 16],
+            &[4, 0, 0x90],
+            &[
+                4, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+            ],
+        ] {
+            assert!(
+                HyperLogLog::from_bytes_compact(payload).is_err(),
+                "payload={payload:?}"
+            );
+        }
+        assert!(HyperLogLog::from_bytes_compact(&[4, 0, 15, 61]).is_ok());
+    }
+
     #[test]
     fn empty_sketches_have_jaccard_one() {
         let left = HyperLogLog::new(12).unwrap();
@@ -725,10 +1793,135 @@ mod tests {
         assert!(hll.is_empty());
     }
 
+    #[test]
+    fn exact_mode_counts_small_sets_exactly() {
+        let mut hll = HyperLogLog::with_exact_threshold(12, 128).unwrap();
+        for value in 0..10_u64 {
+            hll.add(&value);
+            hll.add(&value);
+        }
+        assert!(hll.is_exact());
+        assert_eq!(hll.count(), 10);
+        assert_eq!(hll.memory_bytes(), 4096 + 10 * 8);
+        assert!(!HyperLogLog::new(12).unwrap().is_exact());
+    }
+
+    #[test]
+    fn exact_mode_promotes_to_registers_past_threshold() {
+        let mut exact = HyperLogLog::with_exact_threshold(12, 128).unwrap();
+        let mut plain = HyperLogLog::new(12).unwrap();
+        for value in 0..129_u64 {
+            assert!(exact.is_exact());
+            exact.add(&value);
+            plain.add(&value);
+        }
+        assert!(!exact.is_exact());
+        assert_eq!(exact.registers(), plain.registers());
+        assert_eq!(exact.estimate(), plain.estimate());
+
+        exact.clear();
+        assert!(exact.is_exact());
+        assert_eq!(exact.count(), 0);
+    }
+
+    #[test]
+    fn exact_mode_merge_unions_sets_or_promotes() {
+        let mut left = HyperLogLog::with_exact_threshold(10, 100).unwrap();
+        let mut right = HyperLogLog::with_exact_threshold(10, 100).unwrap();
+        for value in 0..60_u64 {
+            left.add(&value);
+        }
+        for value in 30..90_u64 {
+            right.add(&value);
+        }
+        assert_eq!(left.union_estimate(&right).unwrap(), 90.0);
+        assert_eq!(left.intersection_estimate(&right).unwrap(), 30.0);
+        assert!((left.jaccard_index(&right).unwrap() - 30.0 / 90.0).abs() < 1e-12);
+
+        let mut promoted = left.clone();
+        let mut large = HyperLogLog::with_exact_threshold(10, 100).unwrap();
+        for value in 100..160_u64 {
+            large.add(&value);
+        }
+        promoted.merge(&large).unwrap();
+        assert!(!promoted.is_exact());
+
+        let mut plain = left.clone();
+        plain.merge(&HyperLogLog::new(10).unwrap()).unwrap();
+        assert!(!plain.is_exact());
+    }
+
+    #[test]
+    fn downsample_keeps_exact_hashes() {
+        let mut hll = HyperLogLog::with_exact_threshold(14, 64).unwrap();
+        for value in 0..40_u64 {
+            hll.add(&value);
+        }
+        let downsampled = hll.downsample(8).unwrap();
+        assert!(downsampled.is_exact());
+        assert_eq!(downsampled.exact_threshold(), 64);
+        assert_eq!(downsampled.count(), 40);
+    }
+
     #[test]
     fn expected_error_matches_register_count() {
         let hll = HyperLogLog::new(10).unwrap();
         let expected = 1.04 / (hll.register_count() as f64).sqrt();
         assert!((hll.expected_relative_error() - expected).abs() < 1e-12);
     }
+
+    #[test]
+    fn sliding_window_validates_parameters() {
+        assert!(SlidingHyperLogLog::new(12, 0).is_err());
+        assert!(SlidingHyperLogLog::new(3, 4).is_err());
+
+        let window = SlidingHyperLogLog::new(12, 4).unwrap();
+        assert_eq!(window.precision(), 12);
+        assert_eq!(window.bucket_count(), 4);
+        assert!(window.is_empty());
+        assert_eq!(window.estimate(), 0.0);
+    }
+
+    #[test]
+    fn sliding_window_drops_the_expired_bucket() {
+        let mut window = SlidingHyperLogLog::new(14, 4).unwrap();
+        for bucket in 0..4_u64 {
+            if bucket > 0 {
+                window.advance();
+            }
+            for value in bucket * 1_000..(bucket + 1) * 1_000 {
+                window.add(&value);
+            }
+        }
+        let full = window.estimate();
+        assert_relative_eq(full, 4_000.0, 0.05);
+
+        window.advance();
+        let mut expected = HyperLogLog::new(14).unwrap();
+        for value in 1_000..4_000_u64 {
+            expected.add(&value);
+        }
+        assert!(window.current_bucket().is_empty());
+        assert_eq!(window.union().registers(), expected.registers());
+        assert_eq!(window.estimate(), expected.estimate());
+        assert_relative_eq(full - window.estimate(), 1_000.0, 0.1);
+
+        window.clear();
+        assert!(window.is_empty());
+    }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let sketches = [
+            HyperLogLog::new(10).unwrap(),
+            HyperLogLog::new(12).unwrap(),
+            HyperLogLog::with_exact_threshold(12, 8).unwrap(),
+        ];
+        for left in &sketches {
+            for right in &sketches {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
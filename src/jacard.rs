@@ -24,14 +24,14 @@
 //!
 //! # Cardinality-sketch caveat
 //!
-//! [`crate::hyperloglog::HyperLogLog`] and
-//! [`crate::ultraloglog::UltraLogLog`] implement this trait using cardinality
-//! estimates and the inclusion-exclusion identity. That is useful when only
-//! cardinality-sketch state is available, but it has a materially weaker
-//! accuracy profile than MinHash similarity: inclusion-exclusion can be quite
-//! inaccurate when the true Jaccard index is small. Clamping the result to
-//! `[0, 1]` does not correct this statistical error. See the extensive warnings
-//! on each implementation and [Ertl 2017].
+//! [`crate::hyperloglog::HyperLogLog`], [`crate::ultraloglog::UltraLogLog`],
+//! and [`crate::bloom_filter::BloomFilter`] implement this trait using
+//! cardinality estimates and the inclusion-exclusion identity. That is useful
+//! when only cardinality-sketch state is available, but it has a materially
+//! weaker accuracy profile than MinHash similarity: inclusion-exclusion can be
+//! quite inaccurate when the true Jaccard index is small. Clamping the result
+//! to `[0, 1]` does not correct this statistical error. See the extensive
+//! warnings on each implementation and [Ertl 2017].
 //!
 //! [Ertl 2017]: https://arxiv.org/pdf/1702.01284
 
@@ -119,11 +119,55 @@ pub trait JacardIndex {
     fn jaccard_index(&self, other: &Self) -> Result<f64, SketchError>;
 }
 
+/// Estimates the same pair of sets with two sketch families side by side.
+///
+/// `a` and `b` hold the left and right sketches of each family, built from the
+/// same two underlying sets. The result is `(a_estimate, b_estimate)`. This is
+/// a validation harness, for example to check how far a cardinality sketch's
+/// inclusion-exclusion estimate drifts from MinHash before dropping one of
+/// them; it does not combine or convert sketch state.
+///
+/// # Example
+/// ```rust
+/// use sketches::hyperloglog::HyperLogLog;
+/// use sketches::jacard::jaccard_agreement;
+/// use sketches::minhash::MinHash;
+///
+/// let (mut left_hll, mut right_hll) = (HyperLogLog::new(12)?, HyperLogLog::new(12)?);
+/// let (mut left_mh, mut right_mh) = (MinHash::new(256)?, MinHash::new(256)?);
+/// for value in 0_u64..6_000 {
+///     left_hll.add(&value);
+///     left_mh.add(&value);
+/// }
+/// for value in 3_000_u64..9_000 {
+///     right_hll.add(&value);
+///     right_mh.add(&value);
+/// }
+///
+/// let (hll, minhash) =
+///     jaccard_agreement((&left_hll, &right_hll), (&left_mh, &right_mh))?;
+/// assert!((hll - minhash).abs() < 0.15);
+/// # Ok::<(), sketches::SketchError>(())
+/// ```
+///
+/// # Errors
+/// Returns the first error reported by either family's
+/// [`JacardIndex::jaccard_index`], typically
+/// [`SketchError::IncompatibleSketches`].
+pub fn jaccard_agreement<A: JacardIndex, B: JacardIndex>(
+    a: (&A, &A),
+    b: (&B, &B),
+) -> Result<(f64, f64), SketchError> {
+    Ok((a.0.jaccard_index(a.1)?, b.0.jaccard_index(b.1)?))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
+        SketchError,
+        bloom_filter::BloomFilter,
         hyperloglog::HyperLogLog,
-        jacard::{JacardIndex, inclusion_exclusion_estimates},
+        jacard::{JacardIndex, inclusion_exclusion_estimates, jaccard_agreement},
         minhash::MinHash,
         ultraloglog::UltraLogLog,
     };
@@ -195,4 +239,64 @@ mod tests {
         let similarity = JacardIndex::jaccard_index(&left, &right).unwrap();
         assert!(similarity > 0.20 && similarity < 0.60);
     }
+
+    // Compares each cardinality-based family against MinHash on identical
+    // inputs, across low, medium, and high overlap.
+    #[test]
+    fn jaccard_agreement_compares_families_on_the_same_sets() {
+        for (overlap, exact) in [
+            (1_000_u64, 1.0 / 15.0),
+            (4_000, 4.0 / 12.0),
+            (7_000, 7.0 / 9.0),
+        ] {
+            let right_range = (8_000 - overlap)..(16_000 - overlap);
+            let mut left_mh = MinHash::new(512).unwrap();
+            let mut right_mh = MinHash::new(512).unwrap();
+            let mut left_hll = HyperLogLog::new(14).unwrap();
+            let mut right_hll = HyperLogLog::new(14).unwrap();
+            let mut left_ull = UltraLogLog::new(14).unwrap();
+            let mut right_ull = UltraLogLog::new(14).unwrap();
+            let mut left_bloom = BloomFilter::new(32_000, 0.01).unwrap();
+            let mut right_bloom = left_bloom.clone();
+            for value in 0_u64..8_000 {
+                left_mh.add(&value);
+                left_hll.add(&value);
+                left_ull.add(&value);
+                left_bloom.insert(&value);
+            }
+            for value in right_range {
+                right_mh.add(&value);
+                right_hll.add(&value);
+                right_ull.add(&value);
+                right_bloom.insert(&value);
+            }
+
+            let (hll, minhash) =
+                jaccard_agreement((&left_hll, &right_hll), (&left_mh, &right_mh)).unwrap();
+            assert!(
+                (minhash - exact).abs() < 0.06,
+                "minhash={minhash} exact={exact}"
+            );
+            assert!((hll - minhash).abs() < 0.1, "hll={hll} minhash={minhash}");
+
+            let (ull, bloom) =
+                jaccard_agreement((&left_ull, &right_ull), (&left_bloom, &right_bloom)).unwrap();
+            assert!((ull - minhash).abs() < 0.1, "ull={ull} minhash={minhash}");
+            assert!(
+                (bloom - minhash).abs() < 0.1,
+                "bloom={bloom} minhash={minhash}"
+            );
+        }
+    }
+
+    #[test]
+    fn jaccard_agreement_propagates_incompatibility() {
+        let left = MinHash::new(64).unwrap();
+        let right = MinHash::new(128).unwrap();
+        let hll = HyperLogLog::new(10).unwrap();
+        assert!(matches!(
+            jaccard_agreement((&hll, &hll), (&left, &right)),
+            Err(SketchError::IncompatibleSketches(_))
+        ));
+    }
 }
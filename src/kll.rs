@@ -32,7 +32,9 @@
 //! Quantiles use the crate's empirical inverse-CDF convention: for retained
 //! weighted mass `N`, `q` selects zero-based rank
 //! `min(floor(q * N), N - 1)`. This is also the exact-sample convention used by
-//! [`crate::tdigest::TDigest`].
+//! [`crate::tdigest::TDigest`]. [`KllSketch::quantile_with`] can instead
+//! interpolate linearly between the two retained values straddling the
+//! fractional rank `q * (N - 1)`; see [`QuantileInterpolation`].
 //!
 //! It does not implement the paper's later sampler or GK-based refinements.
 //! Those refinements improve asymptotic space or failure-probability dependence
@@ -52,7 +54,12 @@
 //!
 //! [Original KLL paper]: https://arxiv.org/pdf/1603.05346
 
-use crate::{SketchError, splitmix64};
+use alloc::{vec, vec::Vec};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::quantile::QuantileSketch;
+use crate::{SketchError, SplitMix64, splitmix64};
 
 const CAPACITY_DECAY: f64 = 2.0 / 3.0;
 const ERROR_BOUND_CONSTANT: f64 = CAPACITY_DECAY * CAPACITY_DECAY * (2.0 * CAPACITY_DECAY - 1.0);
@@ -91,7 +98,59 @@ pub struct KllSketch {
     k: usize,
     levels: Vec<Vec<f64>>,
     count: u64,
-    rng_state: u64,
+    rng: SplitMix64,
+    nan_policy: NanPolicy,
+}
+
+/// Commonly exported percentiles computed by [`KllSketch::summary`].
+///
+/// Every field follows the same convention as [`KllSketch::quantile`], so each
+/// percentile carries the sketch's [`KllSketch::rank_error`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantileSummary {
+    /// Number of observations summarized by the sketch.
+    pub count: u64,
+    /// Median, `quantile(0.50)`.
+    pub p50: f64,
+    /// `quantile(0.75)`.
+    pub p75: f64,
+    /// `quantile(0.90)`.
+    pub p90: f64,
+    /// `quantile(0.95)`.
+    pub p95: f64,
+    /// `quantile(0.99)`.
+    pub p99: f64,
+    /// `quantile(0.999)`.
+    pub p999: f64,
+}
+
+/// How [`KllSketch::quantile_with`] turns a target rank into a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantileInterpolation {
+    /// Returns the retained value whose cumulative weight first exceeds rank
+    /// `min(floor(q * N), N - 1)`, as [`KllSketch::quantile`] does. Results
+    /// are always retained observations.
+    #[default]
+    Nearest,
+    /// Interpolates linearly between the values at the zero-based ranks
+    /// surrounding `q * (N - 1)`. Results vary continuously with `q`, so they
+    /// may lie strictly between two retained observations.
+    Linear,
+}
+
+/// Which non-finite inputs [`KllSketch::add`] keeps.
+///
+/// `NaN` has no rank, so it is dropped under every policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Ignores `NaN` and both infinities.
+    #[default]
+    Drop,
+    /// Keeps infinities as ordinary observations below or above every finite
+    /// value, so they count toward the tails. Like any retained value, an
+    /// infinity may be discarded by compaction, so a lone infinity in a long
+    /// stream is only reported while it is still retained.
+    TreatInfAsExtreme,
 }
 
 impl KllSketch {
@@ -134,10 +193,26 @@ impl KllSketch {
             k,
             levels: vec![Vec::new()],
             count: 0,
-            rng_state: splitmix64(seed),
+            rng: SplitMix64::new(splitmix64(seed)),
+            nan_policy: NanPolicy::Drop,
         })
     }
 
+    /// Creates a sketch like [`Self::new`] that handles non-finite inputs
+    /// according to `policy`.
+    ///
+    /// The policy only governs [`Self::add`]. It is not a merge compatibility
+    /// parameter, so merging a sketch that kept infinities brings them into
+    /// this sketch whatever its own policy.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `k < 2`.
+    pub fn with_nan_policy(k: usize, policy: NanPolicy) -> Result<Self, SketchError> {
+        let mut sketch = Self::new(k)?;
+        sketch.nan_policy = policy;
+        Ok(sketch)
+    }
+
     /// Creates a sketch for a target rank error with 99% single-query
     /// confidence.
     ///
@@ -225,31 +300,93 @@ impl KllSketch {
         Self::with_seed(k, seed)
     }
 
+    /// Creates a sketch with the largest `k` whose retained values fit in
+    /// `bytes`, using the default compaction seed.
+    ///
+    /// Level capacities shrink geometrically by `2/3` below the top level, so
+    /// the hierarchy retains at most about `3 * k` values, as reported by
+    /// [`Self::memory_bytes`]. This picks `k = bytes / (3 * 8)`, clamped to at
+    /// least 2. Each level can round its capacity up by one value and the
+    /// number of levels grows logarithmically with [`Self::count`], so very
+    /// long streams may exceed the budget by a few values per level. Budgets
+    /// below 48 bytes still produce the minimum `k = 2`.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] only if the sketch cannot be
+    /// constructed, which does not happen for any clamped `k`.
+    pub fn for_memory_budget(bytes: usize) -> Result<Self, SketchError> {
+        let values = bytes / core::mem::size_of::<f64>();
+        Self::new((values / 3).max(2))
+    }
+
     /// Returns the configured compaction parameter.
     pub fn k(&self) -> usize {
         self.k
     }
 
+    /// Returns the policy [`Self::add`] applies to non-finite values.
+    pub fn nan_policy(&self) -> NanPolicy {
+        self.nan_policy
+    }
+
     /// Returns the number of observed values.
     pub fn count(&self) -> u64 {
         self.count
     }
 
+    /// Returns the normalized rank error bound for one query at 99%
+    /// confidence.
+    ///
+    /// This is the single-quantile bound used by [`Self::with_error_rate`]:
+    /// the smallest `epsilon` for which
+    /// `2 * exp(-(4 / 27) * epsilon^2 * k^2) <= 0.01`. For the basic fully
+    /// mergeable construction it depends only on `k`; the hierarchy height
+    /// does not appear in the bound, so it holds for any stream length and
+    /// merge tree.
+    ///
+    /// To quote a value band around `quantile(q)`, query the neighboring
+    /// ranks: with `epsilon = rank_error()`, the true `q`-quantile lies in
+    /// `[quantile(max(q - epsilon, 0)), quantile(min(q + epsilon, 1))]` with
+    /// the stated single-query confidence.
+    pub fn rank_error(&self) -> f64 {
+        rank_error_bound(self.k, DEFAULT_FAILURE_PROBABILITY)
+    }
+
     /// Returns `true` when no values have been added.
     pub fn is_empty(&self) -> bool {
         self.count == 0
     }
 
+    /// Returns the number of values retained at each level, from the
+    /// weight-one level upward.
+    ///
+    /// A value at level `h` stands for `2^h` observations. The number of
+    /// levels grows logarithmically with [`Self::count`], while the total
+    /// stays bounded by roughly `3 * k` plus two values per level.
+    pub fn level_sizes(&self) -> Vec<usize> {
+        self.levels.iter().map(Vec::len).collect()
+    }
+
+    /// Returns the bytes occupied by the retained `f64` values.
+    ///
+    /// This counts retained values only; it excludes spare vector capacity
+    /// and the fixed size of the sketch itself.
+    pub fn memory_bytes(&self) -> usize {
+        self.levels.iter().map(Vec::len).sum::<usize>() * core::mem::size_of::<f64>()
+    }
+
     /// Adds one value to the sketch.
     ///
-    /// Non-finite values are ignored.
+    /// `NaN` is always ignored. Infinities are ignored under
+    /// [`NanPolicy::Drop`], the default, and kept under
+    /// [`NanPolicy::TreatInfAsExtreme`].
     ///
     /// # Panics
     /// Panics if the observation count is already `u64::MAX`. This limit is
     /// unreachable through practical single-value ingestion; fallible merges
     /// report [`SketchError::ObservationCountOverflow`] instead.
     pub fn add(&mut self, value: f64) {
-        if !value.is_finite() {
+        if !self.keeps(value) {
             return;
         }
 
@@ -263,6 +400,41 @@ impl KllSketch {
         self.compact_after_add();
     }
 
+    /// Adds `value` as `weight` observations.
+    ///
+    /// The weight is split into its binary digits, and the value is placed
+    /// once at every level `h` whose digit `2^h` is set, as if `2^h` copies
+    /// had already been compacted there. This takes `O(log weight)` space
+    /// before compaction instead of `O(weight)` repeated [`Self::add`] calls.
+    /// Values are filtered by [`Self::nan_policy`] like [`Self::add`], and a
+    /// zero weight is ignored.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::ObservationCountOverflow`] without changing the
+    /// sketch when the observation count would exceed `u64::MAX`.
+    pub fn add_weighted(&mut self, value: f64, weight: u64) -> Result<(), SketchError> {
+        if !self.keeps(value) || weight == 0 {
+            return Ok(());
+        }
+
+        let new_count = self
+            .count
+            .checked_add(weight)
+            .ok_or(SketchError::ObservationCountOverflow)?;
+        let height = (u64::BITS - weight.leading_zeros()) as usize;
+        if self.levels.len() < height {
+            self.levels.resize_with(height, Vec::new);
+        }
+        for level in 0..height {
+            if weight & (1 << level) != 0 {
+                self.levels[level].push(value);
+            }
+        }
+        self.count = new_count;
+        self.compact_all_levels();
+        Ok(())
+    }
+
     /// Returns the approximate quantile at `q` where `q` is in `[0, 1]`.
     ///
     /// The selected zero-based rank is `min(floor(q * N), N - 1)`, where `N`
@@ -286,6 +458,91 @@ impl KllSketch {
         ))
     }
 
+    /// Returns the approximate quantile at `q` together with its estimated
+    /// normalized rank.
+    ///
+    /// The value is the one [`Self::quantile`] returns. Its rank is the
+    /// retained weight strictly below that value divided by the observation
+    /// count, which is the zero-based rank of the value's first occurrence on
+    /// the same convention. For distinct values it lies within
+    /// [`Self::rank_error`] of `q` with the stated confidence; a run of equal
+    /// values reports the rank where the run starts.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid `q` or empty
+    /// sketches.
+    pub fn quantile_with_rank(&self, q: f64) -> Result<(f64, f64), SketchError> {
+        Self::validate_quantile(q)?;
+        self.validate_non_empty()?;
+
+        let weighted_values = self.sorted_weighted_values();
+        let total_weight = self.total_weight(&weighted_values);
+        let target_rank = Self::target_rank(q, total_weight);
+
+        let mut cumulative = 0_u128;
+        let mut below = 0_u128;
+        let mut previous = None;
+        for &(value, weight) in &weighted_values {
+            if previous != Some(value) {
+                below = cumulative;
+                previous = Some(value);
+            }
+            cumulative += weight as u128;
+            if cumulative > target_rank {
+                return Ok((value, below as f64 / total_weight as f64));
+            }
+        }
+        Err(SketchError::InvalidParameter(
+            "unable to compute quantile from current state",
+        ))
+    }
+
+    /// Returns the approximate quantile at `q` using the given interpolation
+    /// mode.
+    ///
+    /// [`QuantileInterpolation::Nearest`] matches [`Self::quantile`].
+    /// [`QuantileInterpolation::Linear`] treats each retained value of weight
+    /// `w` as `w` consecutive ranks, then interpolates between the values at
+    /// ranks `floor(q * (N - 1))` and the following rank. For example, the
+    /// linear median of `[0, 10]` is `5`. Both modes carry the sketch's
+    /// [`Self::rank_error`].
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid `q` or empty
+    /// sketches.
+    pub fn quantile_with(&self, q: f64, mode: QuantileInterpolation) -> Result<f64, SketchError> {
+        if mode == QuantileInterpolation::Nearest {
+            return self.quantile(q);
+        }
+        Self::validate_quantile(q)?;
+        self.validate_non_empty()?;
+
+        let weighted_values = self.sorted_weighted_values();
+        let total_weight = self.total_weight(&weighted_values);
+        let position = q * (total_weight - 1) as f64;
+        let lower_rank = (position.floor() as u128).min(total_weight - 1);
+        let upper_rank = (lower_rank + 1).min(total_weight - 1);
+        let fraction = position - lower_rank as f64;
+
+        let (Some(lower), Some(upper)) = (
+            Self::value_at_rank(&weighted_values, lower_rank),
+            Self::value_at_rank(&weighted_values, upper_rank),
+        ) else {
+            return Err(SketchError::InvalidParameter(
+                "unable to compute quantile from current state",
+            ));
+        };
+        // An infinite neighbour absorbs every point of the interval, and
+        // returning it directly avoids `inf - inf` and `-inf + fraction * inf`.
+        if lower == upper || fraction <= 0.0 || lower.is_infinite() {
+            return Ok(lower);
+        }
+        if upper.is_infinite() {
+            return Ok(upper);
+        }
+        Ok(lower + fraction * (upper - lower))
+    }
+
     /// Returns approximate quantiles for every query in `queries`.
     ///
     /// Results preserve the input query order, including duplicate and
@@ -337,6 +594,38 @@ impl KllSketch {
         }
     }
 
+    /// Returns the p50, p75, p90, p95, p99, and p99.9 percentiles together
+    /// with the observation count.
+    ///
+    /// All six percentiles are answered from one sorted scan of the retained
+    /// values, as with [`Self::quantiles`].
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for an empty sketch.
+    pub fn summary(&self) -> Result<QuantileSummary, SketchError> {
+        self.validate_non_empty()?;
+        let [p50, p75, p90, p95, p99, p999] = self
+            .quantiles(&[0.50, 0.75, 0.90, 0.95, 0.99, 0.999])?
+            .try_into()
+            .expect("one result per summary quantile");
+
+        Ok(QuantileSummary {
+            count: self.count,
+            p50,
+            p75,
+            p90,
+            p95,
+            p99,
+            p999,
+        })
+    }
+
+    /// Returns `true` when `other` has the `k` that [`Self::merge`] requires.
+    /// The merge itself can still fail on observation-count overflow.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.k == other.k
+    }
+
     /// Merges another sketch into this one.
     ///
     /// Levels of equal weight are concatenated, then all capacities are
@@ -355,7 +644,7 @@ impl KllSketch {
     /// count would exceed `u64::MAX`. Validation occurs before mutation, so an
     /// error leaves this sketch unchanged.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
-        if self.k != other.k {
+        if !self.compatible(other) {
             return Err(SketchError::IncompatibleSketches("k must match for merge"));
         }
 
@@ -375,6 +664,47 @@ impl KllSketch {
         Ok(())
     }
 
+    /// Merges many sketches into a new sketch.
+    ///
+    /// All inputs are concatenated level by level first, and the combined
+    /// hierarchy is compacted once at the end instead of after every pairwise
+    /// [`Self::merge`]. The result starts from a clone of the first sketch, so
+    /// its own RNG state drives the final compactions.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for an empty slice,
+    /// [`SketchError::IncompatibleSketches`] when any `k` differs, or
+    /// [`SketchError::ObservationCountOverflow`] when the combined observation
+    /// count would exceed `u64::MAX`.
+    pub fn merge_all(sketches: &[&Self]) -> Result<Self, SketchError> {
+        let (first, rest) = sketches.split_first().ok_or(SketchError::InvalidParameter(
+            "merge_all requires at least one sketch",
+        ))?;
+
+        let mut count = first.count;
+        let mut height = first.levels.len();
+        for sketch in rest {
+            if sketch.k != first.k {
+                return Err(SketchError::IncompatibleSketches("k must match for merge"));
+            }
+            count = count
+                .checked_add(sketch.count)
+                .ok_or(SketchError::ObservationCountOverflow)?;
+            height = height.max(sketch.levels.len());
+        }
+
+        let mut merged = (*first).clone();
+        merged.levels.resize_with(height, Vec::new);
+        for sketch in rest {
+            for (level, values) in sketch.levels.iter().enumerate() {
+                merged.levels[level].extend(values.iter().copied());
+            }
+        }
+        merged.count = count;
+        merged.compact_all_levels();
+        Ok(merged)
+    }
+
     /// Clears all retained state.
     pub fn clear(&mut self) {
         self.levels.clear();
@@ -382,6 +712,14 @@ impl KllSketch {
         self.count = 0;
     }
 
+    /// Returns whether [`Self::nan_policy`] admits `value`.
+    fn keeps(&self, value: f64) -> bool {
+        match self.nan_policy {
+            NanPolicy::Drop => value.is_finite(),
+            NanPolicy::TreatInfAsExtreme => !value.is_nan(),
+        }
+    }
+
     fn validate_quantile(q: f64) -> Result<(), SketchError> {
         if !q.is_finite() || !(0.0..=1.0).contains(&q) {
             return Err(SketchError::InvalidParameter(
@@ -504,7 +842,7 @@ impl KllSketch {
             self.levels.push(Vec::new());
         }
 
-        let mut values = std::mem::take(&mut self.levels[level]);
+        let mut values = core::mem::take(&mut self.levels[level]);
         values.sort_unstable_by(f64::total_cmp);
 
         // Keep one value at this level if the level length is odd.
@@ -514,7 +852,7 @@ impl KllSketch {
             None
         };
 
-        let offset = self.next_u64() as usize & 1;
+        let offset = self.rng.next_u64() as usize & 1;
         for index in (offset..values.len()).step_by(2) {
             self.levels[level + 1].push(values[index]);
         }
@@ -534,16 +872,23 @@ impl KllSketch {
         }
         self.levels[level] = values;
     }
+}
 
-    fn next_u64(&mut self) -> u64 {
-        self.rng_state = splitmix64(self.rng_state.wrapping_add(0x9E37_79B9_7F4A_7C15));
-        self.rng_state
+impl QuantileSketch for KllSketch {
+    fn add_count(&mut self, value: f64, count: u64) -> Result<(), SketchError> {
+        self.add_weighted(value, count)
+    }
+
+    fn quantile(&self, q: f64) -> Result<f64, SketchError> {
+        self.quantile(q)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DEFAULT_FAILURE_PROBABILITY, KllSketch, rank_error_bound};
+    use super::{
+        DEFAULT_FAILURE_PROBABILITY, KllSketch, NanPolicy, QuantileInterpolation, rank_error_bound,
+    };
     use crate::{SketchError, splitmix64};
 
     const REGRESSION_SEED: u64 = 0xD1B5_4A32_C192_ED03;
@@ -692,6 +1037,62 @@ mod tests {
         sketches.pop().unwrap()
     }
 
+    #[test]
+    fn level_sizes_grow_logarithmically_with_bounded_retention() {
+        let k = 100;
+        let mut sketch = KllSketch::with_seed(k, 11).unwrap();
+        assert_eq!(sketch.level_sizes(), vec![0]);
+        assert_eq!(sketch.memory_bytes(), 0);
+
+        let mut next_check = 1_000_u64;
+        for value in 0_u64..1_000_000 {
+            sketch.add(value as f64);
+            if sketch.count() == next_check {
+                let sizes = sketch.level_sizes();
+                let retained: usize = sizes.iter().sum();
+                let height = sizes.len();
+                let max_height = (sketch.count() as f64 / k as f64).log2().ceil() as usize + 2;
+
+                assert!(height <= max_height, "count={next_check} height={height}");
+                assert!(retained <= 3 * k + 2 * height, "retained={retained}");
+                assert_eq!(sketch.memory_bytes(), retained * 8);
+                assert_eq!(
+                    sizes
+                        .iter()
+                        .enumerate()
+                        .map(|(level, &size)| (size as u64) << level)
+                        .sum::<u64>(),
+                    sketch.count()
+                );
+                next_check *= 10;
+            }
+        }
+        assert!(sketch.level_sizes().len() >= 10);
+    }
+
+    #[test]
+    fn memory_budget_selects_the_largest_fitting_k() {
+        assert_eq!(KllSketch::for_memory_budget(0).unwrap().k(), 2);
+        assert_eq!(KllSketch::for_memory_budget(47).unwrap().k(), 2);
+
+        let mut previous_k = 0;
+        for bytes in [4_096, 16_384, 65_536, 262_144] {
+            let mut sketch = KllSketch::for_memory_budget(bytes).unwrap();
+            assert!(sketch.k() > previous_k);
+            previous_k = sketch.k();
+
+            for value in 0_u64..100_000 {
+                sketch.add(value as f64);
+            }
+            let slack = 8 * 2 * sketch.level_sizes().len();
+            assert!(
+                sketch.memory_bytes() <= bytes + slack,
+                "bytes={bytes} used={}",
+                sketch.memory_bytes()
+            );
+        }
+    }
+
     #[test]
     fn constructor_validates_k() {
         assert!(KllSketch::new(1).is_err());
@@ -708,7 +1109,7 @@ mod tests {
             default_second.add(value as f64);
         }
         assert_eq!(default_first.levels, default_second.levels);
-        assert_eq!(default_first.rng_state, default_second.rng_state);
+        assert_eq!(default_first.rng, default_second.rng);
 
         let mut seeded_first = KllSketch::with_seed(50, 7).unwrap();
         let mut seeded_second = KllSketch::with_seed(50, 7).unwrap();
@@ -717,10 +1118,10 @@ mod tests {
             seeded_second.add(value as f64);
         }
         assert_eq!(seeded_first.levels, seeded_second.levels);
-        assert_eq!(seeded_first.rng_state, seeded_second.rng_state);
+        assert_eq!(seeded_first.rng, seeded_second.rng);
 
         let differently_seeded = KllSketch::with_seed(50, 8).unwrap();
-        assert_ne!(seeded_first.rng_state, differently_seeded.rng_state);
+        assert_ne!(seeded_first.rng, differently_seeded.rng);
     }
 
     #[test]
@@ -761,7 +1162,7 @@ mod tests {
         assert_eq!(default.k(), expected_k);
         assert_eq!(seeded.k(), expected_k);
         assert_eq!(fully_configured.k(), expected_k);
-        assert_ne!(seeded.rng_state, fully_configured.rng_state);
+        assert_ne!(seeded.rng, fully_configured.rng);
 
         assert!(KllSketch::with_error_rate(0.0).is_err());
         assert!(KllSketch::with_error_rate(f64::NAN).is_err());
@@ -769,6 +1170,160 @@ mod tests {
         assert!(KllSketch::with_error_rate_and_failure_probability(0.01, f64::NAN).is_err());
     }
 
+    #[test]
+    fn reported_rank_error_decreases_with_k_and_matches_error_rate_sizing() {
+        let errors: Vec<_> = [2_usize, 8, 50, 200, 1_000]
+            .iter()
+            .map(|&k| KllSketch::new(k).unwrap().rank_error())
+            .collect();
+        assert!(
+            errors.windows(2).all(|pair| pair[0] > pair[1]),
+            "{errors:?}"
+        );
+
+        for &rank_error in &[0.01, 0.05, 0.1] {
+            let sketch = KllSketch::with_error_rate(rank_error).unwrap();
+            assert!(sketch.rank_error() <= rank_error);
+        }
+
+        let mut sketch = KllSketch::with_seed(64, 3).unwrap();
+        let empty_error = sketch.rank_error();
+        for value in 0_u64..10_000 {
+            sketch.add(value as f64);
+        }
+        assert_eq!(sketch.rank_error(), empty_error);
+    }
+
+    #[test]
+    fn summary_reports_increasing_named_percentiles() {
+        let mut sketch = KllSketch::with_seed(200, 11).unwrap();
+        assert!(sketch.summary().is_err());
+
+        let values: Vec<f64> = (0_u64..100_000).map(|value| value as f64).collect();
+        for &value in &values {
+            sketch.add(value);
+        }
+
+        let summary = sketch.summary().unwrap();
+        assert_eq!(summary.count, 100_000);
+        let named = [
+            (0.50, summary.p50),
+            (0.75, summary.p75),
+            (0.90, summary.p90),
+            (0.95, summary.p95),
+            (0.99, summary.p99),
+            (0.999, summary.p999),
+        ];
+        assert!(
+            named.windows(2).all(|pair| pair[0].1 <= pair[1].1),
+            "{summary:?}"
+        );
+        for (q, estimate) in named {
+            assert!(normalized_rank_error(&values, estimate, q) <= sketch.rank_error());
+        }
+        assert_eq!(
+            sketch.quantiles(&[0.50, 0.999]).unwrap(),
+            vec![summary.p50, summary.p999]
+        );
+    }
+
+    #[test]
+    fn linear_interpolation_lands_between_adjacent_observations() {
+        let mut sketch = KllSketch::new(200).unwrap();
+        sketch.add(0.0);
+        sketch.add(10.0);
+
+        assert_eq!(
+            sketch
+                .quantile_with(0.5, QuantileInterpolation::Nearest)
+                .unwrap(),
+            10.0
+        );
+        assert_eq!(
+            sketch
+                .quantile_with(0.5, QuantileInterpolation::Nearest)
+                .unwrap(),
+            sketch.quantile(0.5).unwrap()
+        );
+        assert_eq!(
+            sketch
+                .quantile_with(0.5, QuantileInterpolation::Linear)
+                .unwrap(),
+            5.0
+        );
+
+        for value in 1..=8 {
+            sketch.add(f64::from(value) * 10.0);
+        }
+        let linear = sketch
+            .quantile_with(0.3, QuantileInterpolation::Linear)
+            .unwrap();
+        assert!(linear > 10.0 && linear < 20.0, "linear = {linear}");
+        let nearest = sketch
+            .quantile_with(0.3, QuantileInterpolation::Nearest)
+            .unwrap();
+        assert!(nearest == 10.0 || nearest == 20.0, "nearest = {nearest}");
+
+        assert_eq!(
+            sketch
+                .quantile_with(0.0, QuantileInterpolation::Linear)
+                .unwrap(),
+            0.0
+        );
+        assert_eq!(
+            sketch
+                .quantile_with(1.0, QuantileInterpolation::Linear)
+                .unwrap(),
+            80.0
+        );
+        assert!(
+            KllSketch::new(8)
+                .unwrap()
+                .quantile_with(0.5, QuantileInterpolation::Linear)
+                .is_err()
+        );
+        assert!(
+            sketch
+                .quantile_with(1.5, QuantileInterpolation::Linear)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn quantile_with_rank_reports_a_rank_near_q() {
+        let mut values: Vec<f64> = (0..100_000).map(f64::from).collect();
+        deterministic_shuffle(&mut values, 0x243F_6A88_85A3_08D3);
+        let mut sketch = KllSketch::new(200).unwrap();
+        for &value in &values {
+            sketch.add(value);
+        }
+
+        for q in [0.0, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 1.0] {
+            let (value, rank) = sketch.quantile_with_rank(q).unwrap();
+            assert_eq!(value, sketch.quantile(q).unwrap());
+            assert!(
+                (rank - q).abs() <= sketch.rank_error(),
+                "q={q} rank={rank} limit={}",
+                sketch.rank_error()
+            );
+        }
+
+        let mut exact = KllSketch::new(200).unwrap();
+        for value in [5.0, 1.0, 3.0, 3.0] {
+            exact.add(value);
+        }
+        assert_eq!(exact.quantile_with_rank(0.0).unwrap(), (1.0, 0.0));
+        assert_eq!(exact.quantile_with_rank(0.5).unwrap(), (3.0, 0.25));
+        assert_eq!(exact.quantile_with_rank(1.0).unwrap(), (5.0, 0.75));
+        assert!(
+            KllSketch::new(200)
+                .unwrap()
+                .quantile_with_rank(0.5)
+                .is_err()
+        );
+        assert!(exact.quantile_with_rank(1.5).is_err());
+    }
+
     #[test]
     fn quantile_rejects_empty_sketch() {
         let kll = KllSketch::new(64).unwrap();
@@ -808,10 +1363,7 @@ mod tests {
                     affected_levels.count, full_scan.count,
                     "k={k} index={index}"
                 );
-                assert_eq!(
-                    affected_levels.rng_state, full_scan.rng_state,
-                    "k={k} index={index}"
-                );
+                assert_eq!(affected_levels.rng, full_scan.rng, "k={k} index={index}");
                 assert_eq!(
                     affected_levels.levels, full_scan.levels,
                     "k={k} index={index}"
@@ -1116,6 +1668,133 @@ mod tests {
         assert!(sketch.quantiles(&[1.1]).is_err());
     }
 
+    #[test]
+    fn weighted_adds_place_binary_digits_on_their_levels() {
+        let mut sketch = KllSketch::new(200).unwrap();
+        sketch.add_weighted(1.0, 5).unwrap();
+        sketch.add_weighted(2.0, 0).unwrap();
+        sketch.add_weighted(f64::NAN, 3).unwrap();
+        assert_eq!(sketch.count(), 5);
+        assert_eq!(sketch.level_sizes(), vec![1, 0, 1]);
+
+        sketch.add_weighted(3.0, 1 << 40).unwrap();
+        sketch.add_weighted(4.0, 1 << 40).unwrap();
+        assert_eq!(sketch.count(), 5 + (1 << 41));
+        assert_eq!(sketch.quantile(0.25).unwrap(), 3.0);
+        assert_eq!(sketch.quantile(0.75).unwrap(), 4.0);
+        assert_eq!(
+            sketch.add_weighted(5.0, u64::MAX),
+            Err(SketchError::ObservationCountOverflow)
+        );
+        assert_eq!(sketch.count(), 5 + (1 << 41));
+    }
+
+    #[test]
+    fn nan_policy_selects_which_non_finite_values_are_kept() {
+        let mut dropping = KllSketch::new(200).unwrap();
+        let mut keeping = KllSketch::with_nan_policy(200, NanPolicy::TreatInfAsExtreme).unwrap();
+        assert_eq!(dropping.nan_policy(), NanPolicy::Drop);
+        assert_eq!(keeping.nan_policy(), NanPolicy::TreatInfAsExtreme);
+        assert!(KllSketch::with_nan_policy(1, NanPolicy::TreatInfAsExtreme).is_err());
+
+        // Stay below k so no compaction runs and the extremes are retained.
+        for value in 0..100 {
+            dropping.add(f64::from(value));
+            keeping.add(f64::from(value));
+        }
+        for value in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            dropping.add(value);
+            keeping.add(value);
+        }
+
+        assert_eq!(dropping.count(), 100);
+        assert_eq!(dropping.quantile(0.0).unwrap(), 0.0);
+        assert_eq!(dropping.quantile(1.0).unwrap(), 99.0);
+
+        assert_eq!(keeping.count(), 102);
+        assert_eq!(keeping.quantile(0.0).unwrap(), f64::NEG_INFINITY);
+        assert_eq!(keeping.quantile(1.0).unwrap(), f64::INFINITY);
+        let median = keeping.quantile(0.5).unwrap();
+        assert!((40.0..=60.0).contains(&median), "median={median}");
+    }
+
+    #[test]
+    fn linear_interpolation_returns_infinite_neighbours() {
+        let mut sketch = KllSketch::with_nan_policy(200, NanPolicy::TreatInfAsExtreme).unwrap();
+        sketch.add(f64::NEG_INFINITY);
+        for value in 0..10 {
+            sketch.add(f64::from(value));
+        }
+        sketch.add(f64::INFINITY);
+
+        let linear = |q| {
+            sketch
+                .quantile_with(q, QuantileInterpolation::Linear)
+                .unwrap()
+        };
+        assert_eq!(linear(0.0), f64::NEG_INFINITY);
+        assert_eq!(linear(0.05), f64::NEG_INFINITY);
+        assert_eq!(linear(0.5), 4.5);
+        assert_eq!(linear(0.95), f64::INFINITY);
+        assert_eq!(linear(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn merge_all_combines_many_shards_with_one_compaction_pass() {
+        let shards: Vec<_> = (0_u64..50)
+            .map(|shard| {
+                let mut sketch = KllSketch::with_seed(200, 3_000 + shard).unwrap();
+                for value in shard * 1_000..(shard + 1) * 1_000 {
+                    sketch.add(value as f64);
+                }
+                sketch
+            })
+            .collect();
+        let references: Vec<_> = shards.iter().collect();
+
+        let merged = KllSketch::merge_all(&references).unwrap();
+        assert_eq!(merged.count(), 50_000);
+        assert_eq!(retained_weight(&merged), 50_000);
+        for level in 0..merged.levels.len() {
+            assert!(merged.levels[level].len() <= merged.level_capacity(level));
+        }
+
+        let values: Vec<_> = (0_u64..50_000).map(|value| value as f64).collect();
+        for q in [0.5, 0.1, 0.9] {
+            let estimate = merged.quantile(q).unwrap();
+            assert!(
+                normalized_rank_error(&values, estimate, q) <= merged.rank_error(),
+                "q={q} estimate={estimate}"
+            );
+        }
+
+        let single = KllSketch::merge_all(&[&shards[0]]).unwrap();
+        assert_eq!(single.levels, shards[0].levels);
+    }
+
+    #[test]
+    fn merge_all_validates_inputs() {
+        assert_eq!(
+            KllSketch::merge_all(&[]).unwrap_err(),
+            SketchError::InvalidParameter("merge_all requires at least one sketch")
+        );
+
+        let left = KllSketch::with_seed(100, 7).unwrap();
+        let right = KllSketch::with_seed(101, 8).unwrap();
+        assert!(matches!(
+            KllSketch::merge_all(&[&left, &right]),
+            Err(SketchError::IncompatibleSketches(_))
+        ));
+
+        let mut large = KllSketch::with_seed(2, 7).unwrap();
+        large.add(1.0);
+        large.count = u64::MAX;
+        assert_eq!(
+            KllSketch::merge_all(&[&large, &large]).unwrap_err(),
+            SketchError::ObservationCountOverflow
+        );
+    }
+
     #[test]
     fn merge_rejects_different_k() {
         let mut left = KllSketch::with_seed(100, 7).unwrap();
@@ -1140,7 +1819,7 @@ mod tests {
         assert_eq!(error, SketchError::ObservationCountOverflow);
         assert_eq!(sketch.count, before.count);
         assert_eq!(sketch.levels, before.levels);
-        assert_eq!(sketch.rng_state, before.rng_state);
+        assert_eq!(sketch.rng, before.rng);
         assert!(sketch.levels.len() <= u64::BITS as usize);
     }
 
@@ -1153,4 +1832,19 @@ mod tests {
         assert!(kll.is_empty());
         assert!(kll.quantile(0.5).is_err());
     }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let sketches = [
+            KllSketch::new(200).unwrap(),
+            KllSketch::new(100).unwrap(),
+            KllSketch::new(200).unwrap(),
+        ];
+        for left in &sketches {
+            for right in &sketches {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
@@ -27,26 +27,52 @@
 //!   estimation.
 //! - [`minmax_sketch::MinMaxSketch`] for approximate ordered-value lookup.
 //! - [`hyperloglog::HyperLogLog`] for approximate cardinality estimation.
+//! - [`hyperloglog::SlidingHyperLogLog`] for distinct counts over a sliding
+//!   window of time buckets.
 //! - [`ultraloglog::UltraLogLog`] for more space-efficient approximate
 //!   cardinality estimation.
 //! - [`jacard`] for approximate set overlap/Jaccard helpers on cardinality and
 //!   similarity sketches.
 //! - [`bloom_filter::BloomFilter`] for approximate set membership checks.
+//! - [`cardinality`] for a distinct-count estimate shared by the membership
+//!   filters.
 //! - [`count_sketch::CountSketch`] for signed approximate frequency estimation.
+//! - [`count_sketch::WindowedCountSketch`] for signed frequencies over the two
+//!   most recent windows.
 //! - [`space_saving::SpaceSaving`] for approximate heavy hitters in
 //!   unit-weight streams.
 //! - [`kll::KllSketch`] for approximate quantiles.
 //! - [`tdigest::TDigest`] for tail-friendly quantiles.
+//! - [`quantile`] for a quantile API and histogram ingestion shared by both
+//!   quantile sketches.
 //! - [`cuckoo_filter::CuckooFilter`] for membership with deletions.
 //! - [`minhash::MinHash`] for approximate Jaccard estimation.
+//! - [`simhash::SimHash`] for approximate cosine similarity.
 //! - [`lsh_minhash::MinHashLshIndex`] for approximate nearest-neighbor lookup.
 //! - [`reservoir_sampling::ReservoirSampling`] for uniform stream sampling.
+//!
+//! # `no_std` support
+//!
+//! The default `std` feature can be disabled to build the crate as `#![no_std]`
+//! with `alloc`. Floating-point functions then come from `libm`, and the hash
+//! tables inside [`space_saving::SpaceSaving`] and
+//! [`lsh_minhash::MinHashLshIndex`] are replaced by ordered B-tree maps, and
+//! LSH ID hashing is unkeyed rather than randomized per index. Space-Saving
+//! items implement both [`Ord`] and [`Hash`] in every build (see
+//! [`space_saving::TrackedItem`]), so toggling the feature never changes which
+//! item types compile.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 use core::fmt;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use core::hash::{Hash, Hasher};
+
+use siphasher::sip::SipHasher13;
 
 pub mod bloom_filter;
+pub mod cardinality;
 pub mod count_sketch;
 pub mod cuckoo_filter;
 pub mod hyperloglog;
@@ -56,11 +82,16 @@ pub mod lsh_minhash;
 pub mod mincount_sketch;
 pub mod minhash;
 pub mod minmax_sketch;
+pub mod quantile;
 pub mod reservoir_sampling;
+pub mod simhash;
 pub mod space_saving;
 pub mod tdigest;
 pub mod ultraloglog;
 
+#[cfg(not(any(feature = "std", test)))]
+mod float_math;
+
 /// Errors returned by sketch construction, update, query, and merge operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SketchError {
@@ -71,8 +102,8 @@ pub enum SketchError {
     /// Returned when combining sketches would exceed the supported observation
     /// count.
     ObservationCountOverflow,
-    /// Returned when a Count Sketch update would exceed its exact signed
-    /// counter range.
+    /// Returned when a counter update or merge would exceed the exact range of
+    /// the sketch's counters.
     CounterOverflow,
 }
 
@@ -85,17 +116,36 @@ impl fmt::Display for SketchError {
                 write!(f, "KLL observation count exceeds u64::MAX")
             }
             Self::CounterOverflow => {
-                write!(f, "Count Sketch counter update exceeds the exact i64 range")
+                write!(f, "counter update exceeds the exact counter range")
             }
         }
     }
 }
 
-impl std::error::Error for SketchError {}
+impl core::error::Error for SketchError {}
+
+/// Map used by internal indexes that never depend on iteration order.
+///
+/// Without `std` there is no `HashMap`, so an ordered map provides the same
+/// lookup API.
+#[cfg(feature = "std")]
+pub(crate) type Map<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+pub(crate) type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+/// Set counterpart of [`Map`].
+#[cfg(feature = "std")]
+pub(crate) type Set<T> = std::collections::HashSet<T>;
+#[cfg(not(feature = "std"))]
+pub(crate) type Set<T> = alloc::collections::BTreeSet<T>;
 
 /// Computes a deterministic 64-bit hash using an item and a fixed seed.
+///
+/// Zero-keyed SipHash-1-3 matches the algorithm behind
+/// `std::collections::hash_map::DefaultHasher` today, but unlike that type it
+/// is specified to stay fixed and is available without `std`.
 pub(crate) fn seeded_hash64<T: Hash + ?Sized>(item: &T, seed: u64) -> u64 {
-    let mut hasher = DefaultHasher::new();
+    let mut hasher = SipHasher13::new_with_keys(0, 0);
     seed.hash(&mut hasher);
     item.hash(&mut hasher);
     hasher.finish()
@@ -109,6 +159,74 @@ pub(crate) fn splitmix64(mut x: u64) -> u64 {
     x ^ (x >> 31)
 }
 
+/// Seedable SplitMix64 generator driving the randomized choices of
+/// [`kll::KllSketch`], [`cuckoo_filter::CuckooFilter`], and
+/// [`reservoir_sampling::ReservoirSampling`].
+///
+/// Each step feeds the previous output back through [`splitmix64`], so the
+/// whole generator state is the last value returned and can be saved or
+/// restored as a single `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Creates a generator whose state is exactly `seed`.
+    pub(crate) const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Advances the generator and returns the next value.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = splitmix64(self.state.wrapping_add(0x9E37_79B9_7F4A_7C15));
+        self.state
+    }
+
+    /// Returns the current state, which [`Self::new`] accepts to resume.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) const fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Checks and strips the four-byte magic and version byte that open every
+/// fixed binary encoding.
+pub(crate) fn take_header(
+    bytes: &mut &[u8],
+    magic: [u8; 4],
+    version: u8,
+) -> Result<(), SketchError> {
+    let (header, rest) = bytes
+        .split_first_chunk::<5>()
+        .ok_or(SketchError::InvalidParameter("payload is truncated"))?;
+    if header[..4] != magic {
+        return Err(SketchError::InvalidParameter(
+            "payload magic does not match",
+        ));
+    }
+    if header[4] != version {
+        return Err(SketchError::InvalidParameter("unsupported payload version"));
+    }
+    *bytes = rest;
+    Ok(())
+}
+
+/// Strips a little-endian `u64` from the front of `bytes`.
+pub(crate) fn take_u64_le(bytes: &mut &[u8]) -> Result<u64, SketchError> {
+    let (word, rest) = bytes
+        .split_first_chunk::<8>()
+        .ok_or(SketchError::InvalidParameter("payload is truncated"))?;
+    *bytes = rest;
+    Ok(u64::from_le_bytes(*word))
+}
+
+/// Strips a little-endian `u64` dimension and converts it to `usize`.
+pub(crate) fn take_usize_le(bytes: &mut &[u8]) -> Result<usize, SketchError> {
+    usize::try_from(take_u64_le(bytes)?)
+        .map_err(|_| SketchError::InvalidParameter("payload dimension does not fit in usize"))
+}
+
 #[cfg(test)]
 mod quantile_contract_tests {
     use crate::kll::KllSketch;
@@ -44,19 +44,38 @@
 //! the MinHash banding analysis is presented in [Mining of Massive
 //! Datasets][mmds].
 //!
+//! An optional capacity set with [`MinHashLshIndex::set_capacity`] bounds the
+//! index by evicting the least-recently-inserted id whenever an insertion
+//! would exceed it.
+//!
+//! Band keys are hashed with the same [`BuildHasher`] type as the indexed
+//! [`MinHash`] signatures, so an index built with [`MinHashLshIndex::with_hasher`]
+//! only accepts sketches that use that hasher type. Differently keyed hashers of
+//! the same type cannot be detected.
+//!
 //! [gionis]: https://www.vldb.org/conf/1999/P49.pdf
 //! [mmds]: https://infolab.stanford.edu/~ullman/mmds/book.pdf
 
-use std::alloc::Layout;
-use std::cmp::{Ordering, Reverse};
-use std::collections::{BinaryHeap, HashMap, HashSet, hash_map::RandomState};
-use std::hash::{BuildHasher, Hash};
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::{boxed::Box, vec::Vec};
+use core::alloc::Layout;
+use core::cmp::{Ordering, Reverse};
+use core::hash::{BuildHasher, Hash};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::minhash::{DefaultMinHashHasher, MinHash, hash_component};
+use crate::{Map, Set, SketchError, splitmix64};
 
-use crate::minhash::MinHash;
-use crate::{SketchError, seeded_hash64, splitmix64};
+/// Builds the per-index ID lookup hash. Without `std` there is no randomness
+/// source, so IDs are hashed with zero-keyed SipHash instead.
+#[cfg(feature = "std")]
+type IdHashBuilder = std::hash::RandomState;
+#[cfg(not(feature = "std"))]
+type IdHashBuilder = core::hash::BuildHasherDefault<siphasher::sip::SipHasher13>;
 
 /// Stable internal reference to one arena record.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct EntryHandle(usize);
 
 /// Candidate score retained by the bounded top-k heap.
@@ -101,7 +120,7 @@ struct StoredSignature {
 }
 
 impl StoredSignature {
-    fn from_minhash(signature: &MinHash) -> Self {
+    fn from_minhash<S: BuildHasher>(signature: &MinHash<S>) -> Self {
         Self {
             values: signature.signature().into(),
             observed_any: !signature.is_empty(),
@@ -117,6 +136,8 @@ struct Entry<Id> {
     id_hash: u64,
     next_same_hash: Option<EntryHandle>,
     signature: StoredSignature,
+    /// Position in `insertion_order`, refreshed whenever the id is inserted.
+    inserted_at: u64,
 }
 
 /// Locality-Sensitive Hashing index built on MinHash signatures.
@@ -157,13 +178,17 @@ struct Entry<Id> {
 /// once regardless of `b`. Excluding the cost of hashing a user ID, insertion
 /// and removal take `O(m + b)` expected time; candidate lookup takes
 /// `O(m + postings visited)` expected time before output IDs are cloned.
+/// Without the `std` feature, band tables are B-tree maps and each table
+/// lookup adds a logarithmic factor.
+/// Insertion order is kept in a B-tree for capacity eviction, which adds
+/// `O(log n)` to each insertion and removal.
 ///
 /// For `c` unique candidates and a requested result count `q`,
 /// [`Self::query_top_k`] spends `O(cm)` time scoring retained signatures,
 /// `O(c log q)` maintaining its bounded heap, and `O(q log q)` ordering the
 /// result. Only the final `min(c, q)` IDs are cloned.
 #[derive(Debug, Clone)]
-pub struct MinHashLshIndex<Id>
+pub struct MinHashLshIndex<Id, S = DefaultMinHashHasher>
 where
     Id: Eq + Hash + Clone,
 {
@@ -172,12 +197,16 @@ where
     rows_per_band: usize,
     band_seeds: Vec<u64>,
     hash_family_seed: Option<u64>,
-    tables: Vec<HashMap<u64, HashSet<EntryHandle>>>,
+    tables: Vec<Map<u64, Set<EntryHandle>>>,
     entries: Vec<Option<Entry<Id>>>,
     free_entries: Vec<EntryHandle>,
-    id_hash_builder: RandomState,
-    id_heads: HashMap<u64, EntryHandle>,
+    id_hash_builder: IdHashBuilder,
+    id_heads: Map<u64, EntryHandle>,
     entry_count: usize,
+    capacity: Option<usize>,
+    insertion_order: BTreeMap<u64, EntryHandle>,
+    next_insertion: u64,
+    hasher: S,
 }
 
 impl<Id> MinHashLshIndex<Id>
@@ -194,6 +223,22 @@ where
     /// unrepresentable signature storage, or index configuration storage that
     /// cannot be reserved.
     pub fn new(num_hashes: usize, bands: usize) -> Result<Self, SketchError> {
+        Self::with_hasher(num_hashes, bands, DefaultMinHashHasher::default())
+    }
+}
+
+impl<Id, S> MinHashLshIndex<Id, S>
+where
+    Id: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    /// Creates an LSH index like [`MinHashLshIndex::new`] for signatures built
+    /// with [`MinHash::with_hasher`]; band keys are hashed with `hasher`.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] under the same conditions as
+    /// [`MinHashLshIndex::new`].
+    pub fn with_hasher(num_hashes: usize, bands: usize, hasher: S) -> Result<Self, SketchError> {
         if num_hashes == 0 {
             return Err(SketchError::InvalidParameter(
                 "num_hashes must be greater than zero",
@@ -225,13 +270,13 @@ where
 
         // Reserve both configuration vectors explicitly so capacity overflow
         // and allocator failure become constructor errors rather than panics.
-        // `resize_with` calls `HashMap::new` once per band; empty maps do not
+        // `resize_with` calls `Map::new` once per band; empty maps do not
         // allocate bucket arrays until their first posting is inserted.
         let mut tables = Vec::new();
         tables
             .try_reserve_exact(bands)
             .map_err(|_| SketchError::InvalidParameter("bands are too large to allocate"))?;
-        tables.resize_with(bands, HashMap::new);
+        tables.resize_with(bands, Map::new);
 
         let mut band_seeds = Vec::new();
         band_seeds
@@ -250,12 +295,21 @@ where
             tables,
             entries: Vec::new(),
             free_entries: Vec::new(),
-            id_hash_builder: RandomState::new(),
-            id_heads: HashMap::new(),
+            id_hash_builder: IdHashBuilder::default(),
+            id_heads: Map::new(),
             entry_count: 0,
+            capacity: None,
+            insertion_order: BTreeMap::new(),
+            next_insertion: 0,
+            hasher,
         })
     }
 
+    /// Returns the hasher used for band keys.
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
     /// Returns the MinHash signature width configured for this index.
     pub fn num_hashes(&self) -> usize {
         self.num_hashes
@@ -341,6 +395,33 @@ where
         self.entry_count == 0
     }
 
+    /// Returns the maximum number of indexed items, or `None` when unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Bounds the index to at most `max_items` ids.
+    ///
+    /// Once the bound is reached, each insertion of a new id evicts the id
+    /// whose latest insertion is oldest, removing it from every band table
+    /// and dropping its retained signature. Replacing an existing id counts
+    /// as a fresh insertion. If the index already holds more than `max_items`
+    /// ids, the oldest are evicted immediately.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `max_items == 0`.
+    pub fn set_capacity(&mut self, max_items: usize) -> Result<(), SketchError> {
+        if max_items == 0 {
+            return Err(SketchError::InvalidParameter(
+                "capacity must be greater than zero",
+            ));
+        }
+
+        self.capacity = Some(max_items);
+        self.evict_to_capacity();
+        Ok(())
+    }
+
     /// Returns `true` when an id is currently indexed.
     pub fn contains_id(&self, id: &Id) -> bool {
         self.find_handle(id).is_some()
@@ -354,24 +435,31 @@ where
     ///
     /// The borrowed MinHash signature is copied once into compact index-owned
     /// storage so the index remains independent of subsequent caller changes.
+    /// When a capacity is set and a new id would exceed it, the
+    /// least-recently-inserted id is evicted.
     ///
     /// # Errors
     /// Returns [`SketchError::IncompatibleSketches`] when `signature` does not
     /// match the index dimensions or the hash family established by previously
     /// inserted signatures.
-    pub fn insert(&mut self, id: Id, signature: &MinHash) -> Result<(), SketchError> {
+    pub fn insert(&mut self, id: Id, signature: &MinHash<S>) -> Result<(), SketchError> {
         self.ensure_compatible(signature)?;
         if self.hash_family_seed.is_none() {
             self.hash_family_seed = Some(signature.hash_family_seed());
         }
 
         let id_hash = self.hash_id(&id);
+        let inserted_at = self.next_insertion;
+        self.next_insertion += 1;
         if let Some(handle) = self.find_handle_with_hash(&id, id_hash) {
             self.remove_handle_from_bands(handle);
-            self.entries[handle.0]
+            let entry = self.entries[handle.0]
                 .as_mut()
-                .expect("live handle must reference an entry")
-                .signature = StoredSignature::from_minhash(signature);
+                .expect("live handle must reference an entry");
+            entry.signature = StoredSignature::from_minhash(signature);
+            let previous = core::mem::replace(&mut entry.inserted_at, inserted_at);
+            self.insertion_order.remove(&previous);
+            self.insertion_order.insert(inserted_at, handle);
             self.add_handle_to_bands(handle);
             return Ok(());
         }
@@ -381,11 +469,14 @@ where
             id_hash,
             next_same_hash: self.id_heads.get(&id_hash).copied(),
             signature: StoredSignature::from_minhash(signature),
+            inserted_at,
         };
         let handle = self.allocate_entry(entry);
         self.id_heads.insert(id_hash, handle);
         self.add_handle_to_bands(handle);
+        self.insertion_order.insert(inserted_at, handle);
         self.entry_count += 1;
+        self.evict_to_capacity();
         Ok(())
     }
 
@@ -398,11 +489,7 @@ where
             return false;
         };
 
-        self.remove_handle_from_bands(handle);
-        self.unlink_id_handle(handle);
-        self.entries[handle.0] = None;
-        self.free_entries.push(handle);
-        self.entry_count -= 1;
+        self.remove_handle(handle);
         true
     }
 
@@ -420,7 +507,7 @@ where
     /// # Errors
     /// Returns [`SketchError::IncompatibleSketches`] when the query dimensions
     /// or hash family mismatch this index.
-    pub fn query_candidates(&self, query: &MinHash) -> Result<Vec<Id>, SketchError> {
+    pub fn query_candidates(&self, query: &MinHash<S>) -> Result<Vec<Id>, SketchError> {
         let handles = self.candidate_handles(query)?;
         Ok(handles
             .into_iter()
@@ -443,7 +530,7 @@ where
     /// # Errors
     /// Returns [`SketchError::IncompatibleSketches`] when the query dimensions
     /// or hash family mismatch this index.
-    pub fn query_top_k(&self, query: &MinHash, k: usize) -> Result<Vec<(Id, f64)>, SketchError> {
+    pub fn query_top_k(&self, query: &MinHash<S>, k: usize) -> Result<Vec<(Id, f64)>, SketchError> {
         if k == 0 {
             self.ensure_compatible(query)?;
             return Ok(Vec::new());
@@ -513,12 +600,14 @@ where
         self.free_entries.clear();
         self.id_heads.clear();
         self.entry_count = 0;
+        self.insertion_order.clear();
+        self.next_insertion = 0;
         for table in &mut self.tables {
             table.clear();
         }
     }
 
-    fn ensure_compatible(&self, signature: &MinHash) -> Result<(), SketchError> {
+    fn ensure_compatible(&self, signature: &MinHash<S>) -> Result<(), SketchError> {
         if signature.num_hashes() != self.num_hashes {
             return Err(SketchError::IncompatibleSketches(
                 "signature num_hashes must match index num_hashes",
@@ -535,10 +624,10 @@ where
         Ok(())
     }
 
-    fn candidate_handles(&self, query: &MinHash) -> Result<HashSet<EntryHandle>, SketchError> {
+    fn candidate_handles(&self, query: &MinHash<S>) -> Result<Set<EntryHandle>, SketchError> {
         self.ensure_compatible(query)?;
 
-        let mut candidates = HashSet::new();
+        let mut candidates = Set::new();
         for band in 0..self.bands {
             let band_hash = self.band_hash(query.signature(), band);
             if let Some(bucket) = self.tables[band].get(&band_hash) {
@@ -558,6 +647,30 @@ where
         }
     }
 
+    fn remove_handle(&mut self, handle: EntryHandle) {
+        self.remove_handle_from_bands(handle);
+        self.unlink_id_handle(handle);
+        let entry = self.entries[handle.0]
+            .take()
+            .expect("live handle must reference an entry");
+        self.insertion_order.remove(&entry.inserted_at);
+        self.free_entries.push(handle);
+        self.entry_count -= 1;
+    }
+
+    fn evict_to_capacity(&mut self) {
+        let Some(capacity) = self.capacity else {
+            return;
+        };
+        while self.entry_count > capacity {
+            let (_, handle) = self
+                .insertion_order
+                .pop_first()
+                .expect("indexed entries have insertion positions");
+            self.remove_handle(handle);
+        }
+    }
+
     fn remove_handle_from_bands(&mut self, handle: EntryHandle) {
         for band in 0..self.bands {
             let band_hash = self.band_hash_for_handle(handle, band);
@@ -651,14 +764,14 @@ where
     fn band_hash(&self, signature: &[u64], band: usize) -> u64 {
         let start = band * self.rows_per_band;
         let end = start + self.rows_per_band;
-        seeded_hash64(&signature[start..end], self.band_seeds[band])
+        hash_component(&self.hasher, &signature[start..end], self.band_seeds[band])
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::cell::Cell;
-    use std::hash::{Hash, Hasher};
+    use std::hash::{BuildHasherDefault, DefaultHasher, Hash, Hasher};
     use std::rc::Rc;
 
     use super::MinHashLshIndex;
@@ -919,6 +1032,56 @@ mod tests {
         assert!(index.query_candidates(&second).unwrap().contains(&2));
     }
 
+    #[test]
+    fn capacity_evicts_the_least_recently_inserted_ids() {
+        let signatures: Vec<_> = (0..6_u64)
+            .map(|id| signature_for_range(id * 10_000, id * 10_000 + 1_000, 64))
+            .collect();
+        let mut index = MinHashLshIndex::new(64, 16).unwrap();
+        assert_eq!(index.capacity(), None);
+        assert!(index.set_capacity(0).is_err());
+        index.set_capacity(3).unwrap();
+        assert_eq!(index.capacity(), Some(3));
+
+        for id in 0..5_u64 {
+            index.insert(id, &signatures[id as usize]).unwrap();
+            assert!(index.len() <= 3);
+        }
+        assert_eq!(index.len(), 3);
+        for id in 0..2_u64 {
+            assert!(!index.contains_id(&id));
+            assert!(
+                index
+                    .query_candidates(&signatures[id as usize])
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+        for id in 2..5_u64 {
+            assert!(index.contains_id(&id));
+            assert_eq!(
+                index.query_candidates(&signatures[id as usize]).unwrap(),
+                vec![id]
+            );
+        }
+
+        // Re-inserting id 2 makes id 3 the oldest entry.
+        index.insert(2, &signatures[2]).unwrap();
+        index.insert(5, &signatures[5]).unwrap();
+        assert_eq!(index.len(), 3);
+        assert!(!index.contains_id(&3));
+        assert!(index.contains_id(&2));
+
+        index.set_capacity(1).unwrap();
+        assert_eq!(index.len(), 1);
+        assert!(index.contains_id(&5));
+        assert_eq!(index.entries.iter().flatten().count(), 1);
+        assert_eq!(
+            index.tables.iter().map(|table| table.len()).sum::<usize>(),
+            16
+        );
+    }
+
     #[test]
     fn insert_replaces_existing_id_signature() {
         let mut index = MinHashLshIndex::<u64>::new(128, 32).unwrap();
@@ -1046,4 +1209,25 @@ mod tests {
         assert!(index.hash_family_seed.is_none());
         assert!(index.query_candidates(&signature).unwrap().is_empty());
     }
+
+    #[test]
+    fn index_with_custom_hasher_finds_matching_signatures() {
+        let hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let mut index = MinHashLshIndex::<u64, _>::with_hasher(64, 16, hasher.clone()).unwrap();
+        let mut near = MinHash::with_hasher(64, hasher.clone()).unwrap();
+        let mut far = MinHash::with_hasher(64, hasher.clone()).unwrap();
+        let mut query = MinHash::with_hasher(64, hasher).unwrap();
+        for value in 0_u64..1_000 {
+            near.add(&value);
+            query.add(&(value + 20));
+            far.add(&(value + 50_000));
+        }
+
+        index.insert(1, &near).unwrap();
+        index.insert(2, &far).unwrap();
+        assert_eq!(index.query_candidates(&query).unwrap(), vec![1]);
+        let top = index.query_top_k(&query, 1).unwrap();
+        assert_eq!(top[0].0, 1);
+        assert!(top[0].1 > 0.9);
+    }
 }
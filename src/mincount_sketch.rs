@@ -65,14 +65,18 @@
 //!
 //! Counts saturate at [`u64::MAX`] rather than wrapping. Once either an item
 //! count or total stream weight exceeds that range, the mathematical error
-//! guarantee no longer applies.
+//! guarantee no longer applies. [`MinCountSketch::merge_checked`] reports such
+//! a merge as [`SketchError::CounterOverflow`] instead of saturating.
 //!
 //! [Count-Min paper]: https://dimacs.rutgers.edu/~graham/pubs/papers/cm-full.pdf
 
-use std::hash::{Hash, Hasher};
+use alloc::{boxed::Box, vec::Vec};
+use core::hash::{Hash, Hasher};
 
 use siphasher::sip::SipHasher13;
 
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
 use crate::{SketchError, splitmix64};
 
 const SPLITMIX_INCREMENT: u64 = 0x9E37_79B9_7F4A_7C15;
@@ -135,7 +139,7 @@ impl MinCountSketch {
         }
 
         // The Count-Min proof needs at least e/epsilon counters per row.
-        let minimum_width = (std::f64::consts::E / epsilon).ceil();
+        let minimum_width = (core::f64::consts::E / epsilon).ceil();
         if !minimum_width.is_finite() || minimum_width > usize::MAX as f64 {
             return Err(SketchError::InvalidParameter(
                 "epsilon requires an unrepresentable width",
@@ -239,6 +243,24 @@ impl MinCountSketch {
         self.rows.len()
     }
 
+    /// Returns the additive error factor `e / width` implied by the width.
+    ///
+    /// Every estimate exceeds the true count by at most
+    /// `epsilon() * total_count()` with probability at least `1 - delta()`.
+    /// Because [`Self::new`] rounds the width up to a power of two, this is
+    /// at most the requested `epsilon` and may be up to half of it.
+    pub fn epsilon(&self) -> f64 {
+        core::f64::consts::E / self.width as f64
+    }
+
+    /// Returns the failure probability `exp(-depth)` implied by the depth.
+    ///
+    /// For sketches built with [`Self::new`] this is at most the requested
+    /// `delta` and more than `delta / e`.
+    pub fn delta(&self) -> f64 {
+        (-(self.depth() as f64)).exp()
+    }
+
     /// Returns the caller-provided hash-family seed.
     pub fn seed(&self) -> u64 {
         self.family_seed
@@ -320,12 +342,144 @@ impl MinCountSketch {
         minimum
     }
 
+    /// Returns the estimated fraction of [`Self::total_count`] contributed by
+    /// an item, or `0.0` for an empty sketch.
+    ///
+    /// Like [`Self::estimate`], the rate is an upper bound up to collision
+    /// noise, so rates of distinct items may sum to more than one.
+    pub fn estimate_rate<T: Hash + ?Sized>(&self, item: &T) -> f64 {
+        if self.total_count == 0 {
+            return 0.0;
+        }
+        self.estimate(item) as f64 / self.total_count as f64
+    }
+
+    /// Returns the candidate with the largest [`Self::estimate`], or `None`
+    /// for an empty slice.
+    ///
+    /// Ties go to the earliest candidate. Estimates are upper bounds, so a
+    /// rare candidate inflated by collisions can outrank a frequent one whose
+    /// true count is within the sketch's error of it.
+    pub fn argmax<'a, T: Hash>(&self, candidates: &'a [T]) -> Option<&'a T> {
+        // `max_by_key` keeps the last maximum, so scanning in reverse keeps
+        // the first.
+        candidates
+            .iter()
+            .rev()
+            .max_by_key(|candidate| self.estimate(*candidate))
+    }
+
+    /// Removes `known_count` occurrences of an item after hashing it once.
+    ///
+    /// This is a best-effort correction, not an exact deletion. Every counter
+    /// selected by the item is lowered by `known_count`, saturating at zero,
+    /// and [`Self::total_count`] is lowered by the same amount. Count-Min cells
+    /// are shared, so an item that collided with this one in a row can lose
+    /// weight it never contributed: estimates for other items may fall below
+    /// their true frequency, and the one-sided upper-bound guarantee no longer
+    /// holds for them. Pass only a count that was actually added for this item.
+    pub fn reset_key<T: Hash + ?Sized>(&mut self, item: &T, known_count: u64) {
+        self.reset_key_u64(self.fingerprint(item), known_count);
+    }
+
+    /// Removes `known_count` occurrences of a stable 64-bit item ID.
+    ///
+    /// This bypasses generic fingerprinting and has the same best-effort
+    /// semantics as [`Self::reset_key`].
+    pub fn reset_key_u64(&mut self, item_id: u64, known_count: u64) {
+        if known_count == 0 {
+            return;
+        }
+
+        for row in 0..self.depth() {
+            let index = self.location(row, item_id);
+            self.counters[index] = self.counters[index].saturating_sub(known_count);
+        }
+        self.total_count = self.total_count.saturating_sub(known_count);
+    }
+
+    /// Scales every counter and [`Self::total_count`] by `factor`, rounding
+    /// down.
+    ///
+    /// Calling this on a fixed schedule implements exponential forgetting:
+    /// after `n` calls, an update contributes about `factor^n` of its original
+    /// weight. Rounding down guarantees that every count eventually reaches
+    /// zero; rounding to nearest would pin a count of one forever whenever
+    /// `factor >= 0.5`. Rounding is monotone, so each estimate remains an upper
+    /// bound on the item's decayed frequency rounded the same way. Counters
+    /// above `2^53` are scaled with `f64` precision.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SketchError::InvalidParameter`] unless `factor` is finite and
+    /// strictly between zero and one. An error leaves the sketch unchanged.
+    pub fn decay(&mut self, factor: f64) -> Result<(), SketchError> {
+        if !factor.is_finite() || factor <= 0.0 || factor >= 1.0 {
+            return Err(SketchError::InvalidParameter(
+                "decay factor must be finite and strictly between 0 and 1",
+            ));
+        }
+
+        let scale = |count: u64| (count as f64 * factor).floor() as u64;
+        for counter in &mut self.counters {
+            *counter = scale(*counter);
+        }
+        self.total_count = scale(self.total_count);
+        Ok(())
+    }
+
+    /// Estimates the Shannon entropy of the item distribution, in bits.
+    ///
+    /// Each row is read as a distribution over its nonzero counters, and the
+    /// result is the median of the rows' entropies
+    /// `-sum((c / S) * log2(c / S))`, where `S` is that row's counter sum.
+    /// Rows are normalized by their own sums rather than
+    /// [`Self::total_count`] because conservative updates leave a row's sum at
+    /// or below the stream weight.
+    ///
+    /// The estimate is biased low. Items that share a counter are merged into
+    /// one outcome, and merging outcomes never increases entropy, so no row
+    /// can report more than `log2(width)` bits. The bias is small while the
+    /// number of distinct items is well below [`Self::width`] and grows as the
+    /// table saturates. Returns `0.0` for an empty sketch.
+    pub fn estimated_entropy(&self) -> f64 {
+        if self.total_count == 0 {
+            return 0.0;
+        }
+
+        let mut entropies: Vec<f64> = self
+            .counters
+            .chunks_exact(self.width)
+            .map(|row| {
+                let sum = row.iter().map(|&count| count as f64).sum::<f64>();
+                if sum == 0.0 {
+                    return 0.0;
+                }
+                row.iter()
+                    .filter(|&&count| count > 0)
+                    .map(|&count| {
+                        let probability = count as f64 / sum;
+                        -probability * probability.log2()
+                    })
+                    .sum::<f64>()
+            })
+            .collect();
+        let middle = entropies.len() / 2;
+        *entropies.select_nth_unstable_by(middle, f64::total_cmp).1
+    }
+
     /// Resets all counts while retaining the allocation and hash family.
     pub fn clear(&mut self) {
         self.counters.fill(0);
         self.total_count = 0;
     }
 
+    /// Returns `true` when [`Self::merge`] accepts `other`, which requires
+    /// equal dimensions and seed.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.check_mergeable(other).is_ok()
+    }
+
     /// Adds another compatible sketch into this sketch.
     ///
     /// Compatibility requires equal dimensions and the same family seed.
@@ -337,6 +491,56 @@ impl MinCountSketch {
     /// Returns [`SketchError::IncompatibleSketches`] for a dimension or seed
     /// mismatch.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
+        self.check_mergeable(other)?;
+
+        // Corresponding cells describe the same hash buckets, so addition
+        // combines their stream weights. This remains an upper-bound sketch,
+        // though it is not identical to replaying both streams conservatively.
+        for (left, right) in self.counters.iter_mut().zip(other.counters.iter()) {
+            *left = left.saturating_add(*right);
+        }
+        self.total_count = self.total_count.saturating_add(other.total_count);
+        Ok(())
+    }
+
+    /// Adds another compatible sketch like [`Self::merge`], but fails instead
+    /// of saturating.
+    ///
+    /// Long-lived aggregators can use this to notice that the sketch needs to
+    /// be reset or scaled down with [`Self::decay`] before its counts stop
+    /// being exact. Every counter and the total count are checked before any
+    /// of them changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SketchError::IncompatibleSketches`] for a dimension or seed
+    /// mismatch, and [`SketchError::CounterOverflow`] without changing the
+    /// sketch when any summed counter or the total count would exceed
+    /// [`u64::MAX`].
+    pub fn merge_checked(&mut self, other: &Self) -> Result<(), SketchError> {
+        self.check_mergeable(other)?;
+
+        let total_count = self
+            .total_count
+            .checked_add(other.total_count)
+            .ok_or(SketchError::CounterOverflow)?;
+        if self
+            .counters
+            .iter()
+            .zip(other.counters.iter())
+            .any(|(left, right)| left.checked_add(*right).is_none())
+        {
+            return Err(SketchError::CounterOverflow);
+        }
+
+        for (left, right) in self.counters.iter_mut().zip(other.counters.iter()) {
+            *left += *right;
+        }
+        self.total_count = total_count;
+        Ok(())
+    }
+
+    fn check_mergeable(&self, other: &Self) -> Result<(), SketchError> {
         if self.width != other.width || self.depth() != other.depth() {
             return Err(SketchError::IncompatibleSketches(
                 "width/depth must match for merge",
@@ -347,14 +551,6 @@ impl MinCountSketch {
                 "hash-family seeds must match for merge",
             ));
         }
-
-        // Corresponding cells describe the same hash buckets, so addition
-        // combines their stream weights. This remains an upper-bound sketch,
-        // though it is not identical to replaying both streams conservatively.
-        for (left, right) in self.counters.iter_mut().zip(other.counters.iter()) {
-            *left = left.saturating_add(*right);
-        }
-        self.total_count = self.total_count.saturating_add(other.total_count);
         Ok(())
     }
 
@@ -442,6 +638,19 @@ mod tests {
         assert!((-(sketch.depth() as f64)).exp() <= 0.01);
     }
 
+    #[test]
+    fn recovered_bounds_are_close_to_the_requested_bounds() {
+        for (epsilon, delta) in [(0.01, 0.01), (0.001, 0.1), (0.05, 0.0001)] {
+            let sketch = MinCountSketch::new(epsilon, delta, SEED).unwrap();
+            assert!(sketch.epsilon() <= epsilon && sketch.epsilon() > epsilon / 2.0);
+            assert!(sketch.delta() <= delta && sketch.delta() > delta / std::f64::consts::E);
+        }
+
+        let explicit = MinCountSketch::with_dimensions(1_024, 3, SEED).unwrap();
+        assert_eq!(explicit.epsilon(), std::f64::consts::E / 1_024.0);
+        assert_eq!(explicit.delta(), (-3.0_f64).exp());
+    }
+
     #[test]
     fn constructors_reject_invalid_or_unallocatable_parameters() {
         assert!(MinCountSketch::new(0.0, 0.1, SEED).is_err());
@@ -552,6 +761,81 @@ mod tests {
         assert_eq!(sketch.depth(), 5);
     }
 
+    #[test]
+    fn reset_key_removes_a_known_contribution_and_saturates() {
+        let mut sketch = MinCountSketch::with_dimensions(256, 5, SEED).unwrap();
+        sketch.add(&"reset", 40);
+        sketch.add(&"kept", 25);
+
+        sketch.reset_key(&"reset", 40);
+        assert_eq!(sketch.estimate(&"reset"), 0);
+        assert_eq!(sketch.estimate(&"kept"), 25);
+        assert_eq!(sketch.total_count(), 25);
+
+        sketch.add_u64(7, 3);
+        sketch.reset_key_u64(7, 100);
+        assert_eq!(sketch.estimate_u64(7), 0);
+        assert_eq!(sketch.total_count(), 0);
+    }
+
+    #[test]
+    fn entropy_separates_uniform_and_single_key_streams() {
+        let mut uniform = MinCountSketch::new(0.001, 0.01, SEED).unwrap();
+        let mut skewed = MinCountSketch::new(0.001, 0.01, SEED).unwrap();
+        assert_eq!(uniform.estimated_entropy(), 0.0);
+
+        for key in 0_u64..1_000 {
+            uniform.add(&key, 10);
+        }
+        skewed.add(&"hot", 10_000);
+        let uniform_entropy = uniform.estimated_entropy();
+        let skewed_entropy = skewed.estimated_entropy();
+
+        // The exact entropies are log2(1_000) ~= 9.97 bits and zero.
+        assert!(
+            uniform_entropy > 9.0 && uniform_entropy <= 1_000_f64.log2() + 1e-9,
+            "uniform = {uniform_entropy}"
+        );
+        assert_eq!(skewed_entropy, 0.0);
+
+        skewed.add(&"rare", 10);
+        let skewed_entropy = skewed.estimated_entropy();
+        assert!(skewed_entropy > 0.0 && skewed_entropy < 0.1);
+        assert!(uniform_entropy > skewed_entropy);
+    }
+
+    #[test]
+    fn decay_forgets_counts_geometrically() {
+        let mut sketch = MinCountSketch::with_dimensions(256, 5, SEED).unwrap();
+        sketch.add(&"trending", 1_000_000);
+        sketch.add(&"background", 10);
+
+        let mut expected = 1_000_000.0;
+        for _ in 0..10 {
+            sketch.decay(0.5).unwrap();
+            expected *= 0.5;
+            let estimate = sketch.estimate(&"trending") as f64;
+            assert!(
+                (estimate - expected).abs() <= 1.0,
+                "{estimate} vs {expected}"
+            );
+        }
+        assert_eq!(sketch.total_count(), 976);
+
+        for _ in 0..20 {
+            sketch.decay(0.5).unwrap();
+        }
+        assert_eq!(sketch.estimate(&"trending"), 0);
+        assert_eq!(sketch.total_count(), 0);
+
+        for factor in [0.0, 1.0, -0.5, 1.5, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                sketch.decay(factor),
+                Err(SketchError::InvalidParameter(_))
+            ));
+        }
+    }
+
     #[test]
     fn merge_preserves_upper_bounds_and_checks_configuration() {
         let mut left = MinCountSketch::with_dimensions(64, 5, SEED).unwrap();
@@ -591,4 +875,74 @@ mod tests {
         assert_eq!(sketch.estimate_u64(7), u64::MAX);
         assert_eq!(sketch.total_count(), u64::MAX);
     }
+
+    #[test]
+    fn checked_merge_reports_overflow_without_mutation() {
+        let mut left = MinCountSketch::with_dimensions(32, 5, SEED).unwrap();
+        let mut right = MinCountSketch::with_dimensions(32, 5, SEED).unwrap();
+        left.add_u64(7, u64::MAX - 1);
+        right.add_u64(7, 1);
+        left.merge_checked(&right).unwrap();
+        assert_eq!(left.estimate_u64(7), u64::MAX);
+        assert_eq!(left.total_count(), u64::MAX);
+
+        let counters = left.counters.clone();
+        assert_eq!(
+            left.merge_checked(&right),
+            Err(SketchError::CounterOverflow)
+        );
+        assert_eq!(left.counters, counters);
+        assert_eq!(left.total_count(), u64::MAX);
+
+        let other_seed = MinCountSketch::with_dimensions(32, 5, SEED + 1).unwrap();
+        assert!(matches!(
+            left.merge_checked(&other_seed),
+            Err(SketchError::IncompatibleSketches(_))
+        ));
+
+        left.merge(&right).unwrap();
+        assert_eq!(left.total_count(), u64::MAX);
+    }
+
+    #[test]
+    fn argmax_picks_the_most_frequent_candidate() {
+        let mut sketch = MinCountSketch::with_dimensions(256, 5, SEED).unwrap();
+        sketch.add(&"rare", 3);
+        sketch.add(&"common", 40);
+        sketch.add(&"occasional", 12);
+
+        let candidates = ["rare", "common", "occasional"];
+        assert_eq!(sketch.argmax(&candidates), Some(&"common"));
+        assert_eq!(sketch.argmax::<&str>(&[]), None);
+
+        let tied = ["unseen", "absent"];
+        assert_eq!(sketch.argmax(&tied), Some(&"unseen"));
+    }
+
+    #[test]
+    fn rates_of_a_two_key_stream_sum_to_one() {
+        let mut sketch = MinCountSketch::with_dimensions(256, 5, SEED).unwrap();
+        assert_eq!(sketch.estimate_rate(&"a"), 0.0);
+
+        sketch.add(&"a", 30);
+        sketch.add(&"b", 10);
+        assert_eq!(sketch.estimate_rate(&"a"), 0.75);
+        assert_eq!(sketch.estimate_rate(&"a") + sketch.estimate_rate(&"b"), 1.0);
+    }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let sketches = [
+            MinCountSketch::with_dimensions(16, 3, SEED).unwrap(),
+            MinCountSketch::with_dimensions(16, 5, SEED).unwrap(),
+            MinCountSketch::with_dimensions(32, 3, SEED).unwrap(),
+            MinCountSketch::with_dimensions(16, 3, SEED + 1).unwrap(),
+        ];
+        for left in &sketches {
+            for right in &sketches {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
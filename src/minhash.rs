@@ -28,20 +28,36 @@
 //!
 //! Each [`MinHash`] owns its deterministically derived component seeds and its
 //! signature. Seeds remain precomputed on the insertion hot path without any
-//! global cache or shared mutable state. The concrete hash algorithm behind
-//! [`crate::seeded_hash64`] is an implementation detail, so signatures should
-//! not be treated as a portable persistence format across crate or Rust versions.
+//! global cache or shared mutable state. Component hashes come from the
+//! sketch's [`BuildHasher`], seeded per component. The default
+//! [`DefaultMinHashHasher`] is zero-keyed SipHash-1-3, so default signatures
+//! are reproducible; [`MinHash::with_hasher`] accepts any other `BuildHasher`.
+//! Sketches are only comparable when they use the same hasher, which cannot be
+//! checked at runtime for differently keyed hashers of the same type.
+//!
+//! For debugging and small sets, [`MinHash::with_exact_tracking`] also keeps
+//! the distinct item hashes up to a configured limit so
+//! [`MinHash::exact_jaccard`] can report the true similarity alongside the
+//! estimate.
 //!
 //! [broder]: https://www.cs.princeton.edu/courses/archive/spring13/cos598C/broder97resemblance.pdf
 
-use std::hash::Hash;
+use alloc::{boxed::Box, vec::Vec};
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+use siphasher::sip::SipHasher13;
 
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
 use crate::jacard::JacardIndex;
-use crate::{SketchError, seeded_hash64, splitmix64};
+use crate::{Set, SketchError, splitmix64};
 
 /// Derivation seed for the deterministic default MinHash family.
 const DEFAULT_HASH_FAMILY_SEED: u64 = 0xBF58_476D_1CE4_E5B9;
 
+/// Default [`BuildHasher`] for [`MinHash`]: zero-keyed SipHash-1-3.
+pub type DefaultMinHashHasher = BuildHasherDefault<SipHasher13>;
+
 /// MinHash signature sketch for estimating set similarity.
 ///
 /// # Example
@@ -69,11 +85,14 @@ const DEFAULT_HASH_FAMILY_SEED: u64 = 0xBF58_476D_1CE4_E5B9;
 /// words. Adding an item takes `O(k)` time; comparing or merging sketches
 /// requires matching signature widths and hash families.
 #[derive(Debug, Clone)]
-pub struct MinHash {
+pub struct MinHash<S = DefaultMinHashHasher> {
     derivation_seed: u64,
     component_seeds: Box<[u64]>,
     signature: Vec<u64>,
     observed_any: bool,
+    exact_limit: usize,
+    exact: Option<Set<u64>>,
+    hasher: S,
 }
 
 impl MinHash {
@@ -83,10 +102,76 @@ impl MinHash {
     /// Returns [`SketchError::InvalidParameter`] when `num_hashes == 0` or the
     /// requested component seeds or signature cannot be allocated.
     pub fn new(num_hashes: usize) -> Result<Self, SketchError> {
-        Self::with_derivation_seed(num_hashes, DEFAULT_HASH_FAMILY_SEED)
+        Self::with_hasher(num_hashes, DefaultMinHashHasher::default())
     }
 
+    #[cfg(test)]
     fn with_derivation_seed(num_hashes: usize, derivation_seed: u64) -> Result<Self, SketchError> {
+        Self::with_derivation_seed_and_hasher(
+            num_hashes,
+            derivation_seed,
+            DefaultMinHashHasher::default(),
+        )
+    }
+
+    /// Creates a MinHash sketch that additionally tracks the distinct item
+    /// hashes of up to `max_tracked_items` items.
+    ///
+    /// This is a debugging aid for checking estimates on small sets. Tracking
+    /// stops, and the stored hashes are released, as soon as more than
+    /// `max_tracked_items` distinct items have been added; the signature is
+    /// unaffected either way. A limit of zero disables tracking.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] under the same conditions as
+    /// [`Self::new`].
+    pub fn with_exact_tracking(
+        num_hashes: usize,
+        max_tracked_items: usize,
+    ) -> Result<Self, SketchError> {
+        let mut sketch = Self::new(num_hashes)?;
+        sketch.exact_limit = max_tracked_items;
+        if max_tracked_items > 0 {
+            sketch.exact = Some(Set::default());
+        }
+        Ok(sketch)
+    }
+
+    /// Creates a MinHash sketch from a target worst-case standard error.
+    ///
+    /// For `k` independent ideal MinHash components and true Jaccard similarity
+    /// `J`, the estimator's standard error is `sqrt(J * (1 - J) / k)`. Its
+    /// maximum over `J` is `1 / (2 * sqrt(k))`, so this constructor selects the
+    /// smallest `k` whose worst-case standard error does not exceed the target.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `max_standard_error` is
+    /// non-finite, non-positive, or requires an unrepresentable signature.
+    pub fn with_error_rate(max_standard_error: f64) -> Result<Self, SketchError> {
+        let num_hashes = required_hashes_for_max_standard_error(max_standard_error)?;
+        Self::new(num_hashes)
+    }
+}
+
+impl<S: BuildHasher> MinHash<S> {
+    /// Creates a MinHash sketch like [`MinHash::new`] that hashes items with
+    /// `hasher`.
+    ///
+    /// Sketches built with equal hashers produce identical signatures for the
+    /// same items, so a fixed hasher makes signatures reproducible.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] under the same conditions as
+    /// [`MinHash::new`].
+    pub fn with_hasher(num_hashes: usize, hasher: S) -> Result<Self, SketchError> {
+        Self::with_derivation_seed_and_hasher(num_hashes, DEFAULT_HASH_FAMILY_SEED, hasher)
+    }
+
+    fn with_derivation_seed_and_hasher(
+        num_hashes: usize,
+        derivation_seed: u64,
+        hasher: S,
+    ) -> Result<Self, SketchError> {
         if num_hashes == 0 {
             return Err(SketchError::InvalidParameter(
                 "num_hashes must be greater than zero",
@@ -112,22 +197,15 @@ impl MinHash {
             component_seeds: component_seeds.into_boxed_slice(),
             signature,
             observed_any: false,
+            exact_limit: 0,
+            exact: None,
+            hasher,
         })
     }
 
-    /// Creates a MinHash sketch from a target worst-case standard error.
-    ///
-    /// For `k` independent ideal MinHash components and true Jaccard similarity
-    /// `J`, the estimator's standard error is `sqrt(J * (1 - J) / k)`. Its
-    /// maximum over `J` is `1 / (2 * sqrt(k))`, so this constructor selects the
-    /// smallest `k` whose worst-case standard error does not exceed the target.
-    ///
-    /// # Errors
-    /// Returns [`SketchError::InvalidParameter`] when `max_standard_error` is
-    /// non-finite, non-positive, or requires an unrepresentable signature.
-    pub fn with_error_rate(max_standard_error: f64) -> Result<Self, SketchError> {
-        let num_hashes = required_hashes_for_max_standard_error(max_standard_error)?;
-        Self::new(num_hashes)
+    /// Returns the hasher used for component hashes.
+    pub fn hasher(&self) -> &S {
+        &self.hasher
     }
 
     /// Returns the worst-case standard error under the independent-component
@@ -183,14 +261,46 @@ impl MinHash {
     /// Component seeds are read from the sketch-owned precomputed seed table.
     pub fn add<T: Hash>(&mut self, item: &T) {
         for (index, seed) in self.component_seeds.iter().enumerate() {
-            let hashed = seeded_hash64(item, *seed);
+            let hashed = hash_component(&self.hasher, item, *seed);
             if hashed < self.signature[index] {
                 self.signature[index] = hashed;
             }
+            if index == 0
+                && let Some(exact) = &mut self.exact
+            {
+                exact.insert(hashed);
+                if exact.len() > self.exact_limit {
+                    self.exact = None;
+                }
+            }
         }
         self.observed_any = true;
     }
 
+    /// Returns `true` while the sketch still holds its exact item hashes.
+    pub fn is_tracking_exact(&self) -> bool {
+        self.exact.is_some()
+    }
+
+    /// Returns the exact Jaccard similarity of the tracked item hashes, or
+    /// `None` unless both sketches are still tracking and share a hash family.
+    ///
+    /// Items are identified by their first-component hash, so distinct items
+    /// are conflated only on a 64-bit hash collision. Two empty sets have
+    /// similarity `1.0`, matching [`Self::estimate_jaccard`].
+    pub fn exact_jaccard(&self, other: &Self) -> Option<f64> {
+        if self.derivation_seed != other.derivation_seed {
+            return None;
+        }
+        let (left, right) = (self.exact.as_ref()?, other.exact.as_ref()?);
+        let intersection = left.iter().filter(|hash| right.contains(hash)).count();
+        let union = left.len() + right.len() - intersection;
+        if union == 0 {
+            return Some(1.0);
+        }
+        Some(intersection as f64 / union as f64)
+    }
+
     /// Estimates Jaccard similarity against another MinHash sketch.
     ///
     /// # Errors
@@ -200,6 +310,32 @@ impl MinHash {
         self.estimate_jaccard_signature(&other.signature, other.observed_any, other.derivation_seed)
     }
 
+    /// Estimates `|A \ B|`, the number of items in this set that are absent
+    /// from `other`, from the Jaccard estimate and caller-supplied set sizes.
+    ///
+    /// With Jaccard estimate `J`, the intersection is
+    /// `J * (size_a + size_b) / (1 + J)`, giving the containment of `A` in `B`
+    /// as that intersection divided by `size_a`. The result is
+    /// `size_a * (1 - containment)`, clamped to `[0, size_a]`. Its accuracy
+    /// depends on both the signature width and the accuracy of the size
+    /// hints; when the hints come from a cardinality sketch, their errors add
+    /// to the MinHash error.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when the signature widths
+    /// or hash families differ.
+    pub fn difference_estimate(
+        &self,
+        other: &Self,
+        size_a: u64,
+        size_b: u64,
+    ) -> Result<f64, SketchError> {
+        let jaccard = self.estimate_jaccard(other)?;
+        let size_a = size_a as f64;
+        let intersection = (jaccard * (size_a + size_b as f64) / (1.0 + jaccard)).min(size_a);
+        Ok((size_a - intersection).clamp(0.0, size_a))
+    }
+
     /// Estimates Jaccard against compact signature state retained by another
     /// crate data structure.
     pub(crate) fn estimate_jaccard_signature(
@@ -237,6 +373,15 @@ impl MinHash {
         self.derivation_seed
     }
 
+    /// Returns `true` when `other` has the signature width and hash family
+    /// that [`Self::merge`] and similarity estimates require.
+    ///
+    /// Hashers cannot be compared, so sketches built with differently keyed
+    /// hashers of the same type are reported as compatible.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.derivation_seed == other.derivation_seed && self.num_hashes() == other.num_hashes()
+    }
+
     /// Merges another sketch in-place by taking element-wise minima.
     ///
     /// # Errors
@@ -249,18 +394,61 @@ impl MinHash {
             *left = (*left).min(*right);
         }
         self.observed_any |= other.observed_any;
+
+        match (&mut self.exact, &other.exact) {
+            (Some(exact), Some(other_exact)) => {
+                exact.extend(other_exact.iter().copied());
+                if exact.len() > self.exact_limit {
+                    self.exact = None;
+                }
+            }
+            _ => self.exact = None,
+        }
         Ok(())
     }
 
-    /// Resets the sketch to the empty state.
+    /// Returns a narrower sketch keeping the first `num_hashes` components.
+    ///
+    /// Component seeds depend only on their index and the hash family, so the
+    /// prefix of a wide signature is exactly the signature a natively narrower
+    /// sketch would hold for the same items. Truncating both sides to a shared
+    /// width makes sketches of different widths comparable and mergeable.
+    /// Exact tracking, which is keyed by the first component, carries over.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `num_hashes` is zero or
+    /// greater than [`Self::num_hashes`].
+    pub fn truncated(&self, num_hashes: usize) -> Result<Self, SketchError>
+    where
+        S: Clone,
+    {
+        if num_hashes == 0 || num_hashes > self.num_hashes() {
+            return Err(SketchError::InvalidParameter(
+                "num_hashes must be greater than zero and at most the current width",
+            ));
+        }
+
+        Ok(Self {
+            derivation_seed: self.derivation_seed,
+            component_seeds: self.component_seeds[..num_hashes].into(),
+            signature: self.signature[..num_hashes].to_vec(),
+            observed_any: self.observed_any,
+            exact_limit: self.exact_limit,
+            exact: self.exact.clone(),
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// Resets the sketch to the empty state, resuming exact tracking if it
+    /// was configured.
     pub fn clear(&mut self) {
         self.signature.fill(u64::MAX);
         self.observed_any = false;
+        self.exact = (self.exact_limit > 0).then(Set::default);
     }
 
     fn ensure_compatible(&self, other: &Self, message: &'static str) -> Result<(), SketchError> {
-        if self.derivation_seed != other.derivation_seed || self.num_hashes() != other.num_hashes()
-        {
+        if !self.compatible(other) {
             return Err(SketchError::IncompatibleSketches(message));
         }
         Ok(())
@@ -285,7 +473,21 @@ fn required_hashes_for_max_standard_error(max_standard_error: f64) -> Result<usi
     Ok((required.ceil() as usize).max(1))
 }
 
-impl JacardIndex for MinHash {
+/// Hashes `item` for the component seeded with `seed`.
+///
+/// With [`DefaultMinHashHasher`] this matches [`crate::seeded_hash64`].
+pub(crate) fn hash_component<S: BuildHasher, T: Hash + ?Sized>(
+    hasher: &S,
+    item: &T,
+    seed: u64,
+) -> u64 {
+    let mut state = hasher.build_hasher();
+    seed.hash(&mut state);
+    item.hash(&mut state);
+    state.finish()
+}
+
+impl<S: BuildHasher> JacardIndex for MinHash<S> {
     fn jaccard_index(&self, other: &Self) -> Result<f64, SketchError> {
         self.estimate_jaccard(other)
     }
@@ -293,14 +495,20 @@ impl JacardIndex for MinHash {
 
 #[cfg(test)]
 mod tests {
+    use core::hash::{BuildHasherDefault, Hasher};
+
     use super::{DEFAULT_HASH_FAMILY_SEED, MinHash};
-    use crate::splitmix64;
+    use crate::{seeded_hash64, splitmix64};
 
-    fn sketch_for_range(start: u64, end: u64, num_hashes: usize) -> MinHash {
-        let mut sketch = MinHash::new(num_hashes).unwrap();
+    fn add_range(sketch: &mut MinHash, start: u64, end: u64) {
         for value in start..end {
             sketch.add(&value);
         }
+    }
+
+    fn sketch_for_range(start: u64, end: u64, num_hashes: usize) -> MinHash {
+        let mut sketch = MinHash::new(num_hashes).unwrap();
+        add_range(&mut sketch, start, end);
         sketch
     }
 
@@ -594,6 +802,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn difference_estimate_uses_jaccard_and_size_hints() {
+        let left = sketch_for_range(0, 1_000, 256);
+        let right = sketch_for_range(500, 1_500, 256);
+
+        let difference = left.difference_estimate(&right, 1_000, 1_000).unwrap();
+        assert!(
+            (difference - 500.0).abs() < 100.0,
+            "difference={difference}"
+        );
+
+        assert_eq!(left.difference_estimate(&left, 1_000, 1_000).unwrap(), 0.0);
+        let disjoint = sketch_for_range(10_000, 11_000, 256);
+        assert_eq!(
+            left.difference_estimate(&disjoint, 1_000, 1_000).unwrap(),
+            1_000.0
+        );
+        assert_eq!(left.difference_estimate(&right, 0, 1_000).unwrap(), 0.0);
+
+        let other_family =
+            MinHash::with_derivation_seed(256, DEFAULT_HASH_FAMILY_SEED ^ 1).unwrap();
+        assert!(
+            left.difference_estimate(&other_family, 1_000, 1_000)
+                .is_err()
+        );
+        assert!(
+            left.difference_estimate(&MinHash::new(128).unwrap(), 1_000, 1_000)
+                .is_err()
+        );
+    }
+
     #[test]
     fn merge_rejects_incompatible_sketches() {
         let mut left = MinHash::new(64).unwrap();
@@ -628,6 +867,27 @@ mod tests {
         assert_eq!(clone.estimate_jaccard(&original).unwrap(), 1.0);
     }
 
+    #[test]
+    fn truncation_matches_a_natively_narrower_sketch() {
+        let wide = sketch_for_range(0, 10_000, 256);
+        let legacy = sketch_for_range(5_000, 15_000, 128);
+        assert!(wide.estimate_jaccard(&legacy).is_err());
+
+        let narrowed = wide.truncated(128).unwrap();
+        assert_eq!(narrowed.num_hashes(), 128);
+        assert_eq!(
+            narrowed.signature(),
+            sketch_for_range(0, 10_000, 128).signature()
+        );
+        let estimate = narrowed.estimate_jaccard(&legacy).unwrap();
+        assert!((estimate - 1.0 / 3.0).abs() < 0.15, "estimate = {estimate}");
+
+        assert_eq!(wide.truncated(256).unwrap().signature(), wide.signature());
+        assert!(wide.truncated(0).is_err());
+        assert!(wide.truncated(257).is_err());
+        assert!(MinHash::new(128).unwrap().truncated(64).unwrap().is_empty());
+    }
+
     #[test]
     fn clear_resets_state() {
         let mut sketch = MinHash::new(64).unwrap();
@@ -636,4 +896,117 @@ mod tests {
         assert!(sketch.is_empty());
         assert!(sketch.signature().iter().all(|&value| value == u64::MAX));
     }
+
+    #[test]
+    fn exact_tracking_reports_true_jaccard_for_small_sets() {
+        let mut left = MinHash::with_exact_tracking(64, 32).unwrap();
+        let mut right = MinHash::with_exact_tracking(64, 32).unwrap();
+        for value in ["a", "b", "c", "d", "e"] {
+            left.add(&value);
+        }
+        for value in ["d", "e", "f", "g", "d"] {
+            right.add(&value);
+        }
+
+        // {d, e} shared out of {a, b, c, d, e, f, g}.
+        assert_eq!(left.exact_jaccard(&right), Some(2.0 / 7.0));
+        assert_eq!(right.exact_jaccard(&left), Some(2.0 / 7.0));
+        assert_eq!(left.exact_jaccard(&left), Some(1.0));
+        assert_eq!(
+            MinHash::with_exact_tracking(64, 32)
+                .unwrap()
+                .exact_jaccard(&MinHash::with_exact_tracking(64, 32).unwrap()),
+            Some(1.0)
+        );
+
+        left.merge(&right).unwrap();
+        assert!(left.is_tracking_exact());
+        assert_eq!(left.exact_jaccard(&right), Some(4.0 / 7.0));
+    }
+
+    #[test]
+    fn exact_tracking_stops_above_the_limit() {
+        let mut tracked = MinHash::with_exact_tracking(64, 10).unwrap();
+        let untracked = sketch_for_range(0, 5, 64);
+        add_range(&mut tracked, 0, 10);
+        assert!(tracked.is_tracking_exact());
+        assert_eq!(tracked.exact_jaccard(&untracked), None);
+
+        let reference = sketch_for_range(0, 11, 64);
+        tracked.add(&10_u64);
+        assert!(!tracked.is_tracking_exact());
+        assert_eq!(tracked.exact_jaccard(&tracked), None);
+        assert_eq!(tracked.signature(), reference.signature());
+
+        tracked.clear();
+        assert!(tracked.is_tracking_exact());
+
+        let mut small = MinHash::with_exact_tracking(64, 10).unwrap();
+        let mut other = MinHash::with_exact_tracking(64, 10).unwrap();
+        add_range(&mut small, 0, 6);
+        add_range(&mut other, 6, 12);
+        small.merge(&other).unwrap();
+        assert!(!small.is_tracking_exact());
+    }
+
+    /// Deterministic FNV-1a hasher for checking the `BuildHasher` plumbing.
+    #[derive(Clone, Default)]
+    struct Fnv(u64);
+
+    impl Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            splitmix64(self.0)
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01B3);
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_hasher_gives_reproducible_signatures() {
+        let hasher = BuildHasherDefault::<Fnv>::default();
+        let mut left = MinHash::with_hasher(64, hasher.clone()).unwrap();
+        let mut right = MinHash::with_hasher(64, hasher).unwrap();
+        for value in 0_u64..500 {
+            left.add(&value);
+        }
+        for value in (0_u64..500).rev() {
+            right.add(&value);
+        }
+        assert_eq!(left.signature(), right.signature());
+        assert_ne!(left.signature(), sketch_for_range(0, 500, 64).signature());
+        assert_eq!(left.estimate_jaccard(&right).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn default_hasher_matches_seeded_hash64() {
+        let sketch = sketch_for_range(0, 100, 16);
+        for (index, minimum) in sketch.signature().iter().enumerate() {
+            let seed = splitmix64((index as u64).wrapping_add(DEFAULT_HASH_FAMILY_SEED));
+            let expected = (0_u64..100)
+                .map(|value| seeded_hash64(&value, seed))
+                .min()
+                .unwrap();
+            assert_eq!(*minimum, expected);
+        }
+    }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let sketches = [
+            MinHash::new(64).unwrap(),
+            MinHash::new(65).unwrap(),
+            MinHash::with_derivation_seed(64, DEFAULT_HASH_FAMILY_SEED ^ 1).unwrap(),
+            MinHash::with_exact_tracking(64, 8).unwrap(),
+        ];
+        for left in &sketches {
+            for right in &sketches {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
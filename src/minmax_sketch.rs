@@ -64,19 +64,41 @@
 //! takes cell-wise minima and exactly reproduces the state obtained by inserting
 //! both collections into one empty sketch.
 //!
+//! # Serialization
+//!
+//! With the `serde` feature enabled, a sketch serializes its dimensions,
+//! family seed, value table, and occupancy bitmap. Row seeds and fingerprint
+//! keys are re-derived from the family seed, so a reloaded sketch remains
+//! mergeable with the original. Deserialization rejects tables whose lengths do
+//! not match the dimensions, occupancy bits beyond the table, and unoccupied
+//! cells that hold anything other than `V::default()`.
+//!
+//! For the unsigned integer value types `u8` through `u64`,
+//! [`MinMaxSketch::to_bytes`] writes the same state without `serde`: the magic
+//! `b"SKMM"`, a version byte, the value width in bytes, then width, depth,
+//! seed, the occupancy words, and the row-major values, all little-endian.
+//! [`MinMaxSketch::from_bytes`] applies the same validation, rejects a value
+//! width that differs from the target type, and rejects truncated or trailing
+//! bytes.
+//!
 //! [paper]: https://doi.org/10.1145/3183713.3196894
 
-use std::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use alloc::borrow::Cow;
+use alloc::{boxed::Box, vec::Vec};
+use core::hash::{Hash, Hasher};
 
 use siphasher::sip::SipHasher13;
 
-use crate::{SketchError, splitmix64};
+use crate::{SketchError, splitmix64, take_header, take_u64_le, take_usize_le};
 
 const SPLITMIX_INCREMENT: u64 = 0x9E37_79B9_7F4A_7C15;
 const FINGERPRINT_DOMAIN_A: u64 = 0x6A09_E667_F3BC_C908;
 const FINGERPRINT_DOMAIN_B: u64 = 0xBB67_AE85_84CA_A73B;
 const ROW_DOMAIN: u64 = 0x3C6E_F372_FE94_F82B;
 const OCCUPANCY_WORD_BITS: usize = u64::BITS as usize;
+const BYTES_MAGIC: [u8; 4] = *b"SKMM";
+const BYTES_VERSION: u8 = 1;
 
 /// Approximate mapping from keys to compact ordered values.
 ///
@@ -279,6 +301,12 @@ impl<V: Copy + Default + Ord> MinMaxSketch<V> {
         self.occupied_cells = 0;
     }
 
+    /// Returns `true` when [`Self::merge`] accepts `other`, which requires
+    /// equal dimensions and seed.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.check_mergeable(other).is_ok()
+    }
+
     /// Merges another compatible sketch into this sketch.
     ///
     /// Cell-wise minima are equivalent to inserting both sketches' input pairs
@@ -290,16 +318,7 @@ impl<V: Copy + Default + Ord> MinMaxSketch<V> {
     /// Returns [`SketchError::IncompatibleSketches`] for a dimension or seed
     /// mismatch. An error leaves this sketch unchanged.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
-        if self.width != other.width || self.depth() != other.depth() {
-            return Err(SketchError::IncompatibleSketches(
-                "width/depth must match for merge",
-            ));
-        }
-        if self.family_seed != other.family_seed {
-            return Err(SketchError::IncompatibleSketches(
-                "hash-family seeds must match for merge",
-            ));
-        }
+        self.check_mergeable(other)?;
 
         for index in 0..self.values.len() {
             if !other.is_occupied(index) {
@@ -317,6 +336,53 @@ impl<V: Copy + Default + Ord> MinMaxSketch<V> {
         Ok(())
     }
 
+    /// Merges several compatible sketches into this sketch in one pass.
+    ///
+    /// Every input is validated before any cell changes; each cell then takes
+    /// the minimum over all occupied inputs. The result equals a chain of
+    /// [`Self::merge`] calls. An empty slice is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SketchError::IncompatibleSketches`] if any input differs in
+    /// dimensions or seed. An error leaves this sketch unchanged.
+    pub fn merge_all(&mut self, others: &[&Self]) -> Result<(), SketchError> {
+        for other in others {
+            self.check_mergeable(other)?;
+        }
+
+        for index in 0..self.values.len() {
+            let mut minimum = self.is_occupied(index).then(|| self.values[index]);
+            for other in others.iter().filter(|other| other.is_occupied(index)) {
+                let value = other.values[index];
+                minimum = Some(minimum.map_or(value, |current| current.min(value)));
+            }
+
+            if let Some(minimum) = minimum {
+                if !self.is_occupied(index) {
+                    self.mark_occupied(index);
+                    self.occupied_cells += 1;
+                }
+                self.values[index] = minimum;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_mergeable(&self, other: &Self) -> Result<(), SketchError> {
+        if self.width != other.width || self.depth() != other.depth() {
+            return Err(SketchError::IncompatibleSketches(
+                "width/depth must match for merge",
+            ));
+        }
+        if self.family_seed != other.family_seed {
+            return Err(SketchError::IncompatibleSketches(
+                "hash-family seeds must match for merge",
+            ));
+        }
+        Ok(())
+    }
+
     fn fingerprint<T: Hash + ?Sized>(&self, key: &T) -> u64 {
         // Keyed SipHash reduces an arbitrary Hash implementation to one stable
         // identifier for this sketch family. Each row then mixes only that ID.
@@ -335,6 +401,57 @@ impl<V: Copy + Default + Ord> MinMaxSketch<V> {
         row * self.width + column
     }
 
+    /// Rebuilds a sketch from its encoded state, re-deriving the hash family.
+    fn from_parts(
+        width: usize,
+        depth: usize,
+        seed: u64,
+        values: Vec<V>,
+        occupied: Vec<u64>,
+    ) -> Result<Self, SketchError> {
+        let mut sketch = Self::new(width, depth, seed)?;
+        if values.len() != sketch.values.len() {
+            return Err(SketchError::InvalidParameter(
+                "value table length must equal width * depth",
+            ));
+        }
+        if occupied.len() != sketch.occupied.len() {
+            return Err(SketchError::InvalidParameter(
+                "occupancy bitmap length does not match the value table",
+            ));
+        }
+
+        sketch.values = values;
+        sketch.occupied = occupied;
+
+        // Bits past the last cell would be counted as occupancy, and a value in
+        // an empty cell would surface after the next insertion takes its minimum.
+        let tail_bits = sketch.values.len() % OCCUPANCY_WORD_BITS;
+        if tail_bits != 0
+            && sketch
+                .occupied
+                .last()
+                .is_some_and(|word| word >> tail_bits != 0)
+        {
+            return Err(SketchError::InvalidParameter(
+                "occupancy bitmap marks cells beyond the value table",
+            ));
+        }
+        if (0..sketch.values.len())
+            .any(|index| !sketch.is_occupied(index) && sketch.values[index] != V::default())
+        {
+            return Err(SketchError::InvalidParameter(
+                "unoccupied cells must hold the default value",
+            ));
+        }
+        sketch.occupied_cells = sketch
+            .occupied
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum();
+        Ok(sketch)
+    }
+
     fn is_occupied(&self, index: usize) -> bool {
         let word = index / OCCUPANCY_WORD_BITS;
         let bit = index % OCCUPANCY_WORD_BITS;
@@ -348,6 +465,139 @@ impl<V: Copy + Default + Ord> MinMaxSketch<V> {
     }
 }
 
+/// Serialized form of a [`MinMaxSketch`]; row seeds are re-derived.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "MinMaxSketch")]
+struct MinMaxSketchState<'a, V: Clone> {
+    width: usize,
+    depth: usize,
+    seed: u64,
+    values: Cow<'a, [V]>,
+    occupied: Cow<'a, [u64]>,
+}
+
+#[cfg(feature = "serde")]
+impl<V> serde::Serialize for MinMaxSketch<V>
+where
+    V: Copy + Default + Ord + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MinMaxSketchState {
+            width: self.width,
+            depth: self.depth(),
+            seed: self.family_seed,
+            values: Cow::Borrowed(&self.values),
+            occupied: Cow::Borrowed(&self.occupied),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V> serde::Deserialize<'de> for MinMaxSketch<V>
+where
+    V: Copy + Default + Ord + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = MinMaxSketchState::<V>::deserialize(deserializer)?;
+        Self::from_parts(
+            state.width,
+            state.depth,
+            state.seed,
+            state.values.into_owned(),
+            state.occupied.into_owned(),
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Implements the fixed binary encoding for unsigned integer value types.
+macro_rules! impl_byte_encoding {
+    ($($value:ty),* $(,)?) => {$(
+        impl MinMaxSketch<$value> {
+            /// Encodes the dimensions, seed, occupancy bitmap, and values; see
+            /// [the module documentation](crate::minmax_sketch#serialization).
+            pub fn to_bytes(&self) -> Vec<u8> {
+                const VALUE_BYTES: usize = core::mem::size_of::<$value>();
+                let mut bytes = Vec::with_capacity(
+                    30 + 8 * self.occupied.len() + VALUE_BYTES * self.values.len(),
+                );
+                bytes.extend_from_slice(&BYTES_MAGIC);
+                bytes.push(BYTES_VERSION);
+                bytes.push(VALUE_BYTES as u8);
+                bytes.extend_from_slice(&(self.width as u64).to_le_bytes());
+                bytes.extend_from_slice(&(self.depth() as u64).to_le_bytes());
+                bytes.extend_from_slice(&self.family_seed.to_le_bytes());
+                for word in &self.occupied {
+                    bytes.extend_from_slice(&word.to_le_bytes());
+                }
+                for value in &self.values {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+                bytes
+            }
+
+            /// Decodes a sketch written by [`Self::to_bytes`].
+            ///
+            /// Row seeds and fingerprint keys are re-derived from the stored
+            /// seed, so the result merges with the original.
+            ///
+            /// # Errors
+            /// Returns [`SketchError::InvalidParameter`] when the magic,
+            /// version, or value width does not match, the dimensions are
+            /// invalid for [`Self::new`], the payload does not hold exactly
+            /// the occupancy words and `width * depth` values, or the state
+            /// fails the checks described in the module documentation.
+            pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, SketchError> {
+                const VALUE_BYTES: usize = core::mem::size_of::<$value>();
+                take_header(&mut bytes, BYTES_MAGIC, BYTES_VERSION)?;
+                let (&value_bytes, rest) = bytes
+                    .split_first()
+                    .ok_or(SketchError::InvalidParameter("payload is truncated"))?;
+                if usize::from(value_bytes) != VALUE_BYTES {
+                    return Err(SketchError::InvalidParameter(
+                        "payload value width does not match the value type",
+                    ));
+                }
+                bytes = rest;
+                let width = take_usize_le(&mut bytes)?;
+                let depth = take_usize_le(&mut bytes)?;
+                let seed = take_u64_le(&mut bytes)?;
+
+                // Compare against the payload before allocating tables of the
+                // claimed size.
+                let table_len = width.checked_mul(depth).ok_or(
+                    SketchError::InvalidParameter("width * depth overflows usize"),
+                )?;
+                let occupancy_words = table_len.div_ceil(OCCUPANCY_WORD_BITS);
+                let expected_len = table_len
+                    .checked_mul(VALUE_BYTES)
+                    .and_then(|len| len.checked_add(8 * occupancy_words));
+                if expected_len != Some(bytes.len()) {
+                    return Err(SketchError::InvalidParameter(
+                        "payload length does not match width * depth",
+                    ));
+                }
+                let (occupancy_bytes, value_bytes) = bytes.split_at(8 * occupancy_words);
+                let occupied = occupancy_bytes
+                    .chunks_exact(8)
+                    .map(|chunk| u64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes")))
+                    .collect();
+                let values = value_bytes
+                    .chunks_exact(VALUE_BYTES)
+                    .map(|chunk| {
+                        <$value>::from_le_bytes(chunk.try_into().expect("chunks match the value width"))
+                    })
+                    .collect();
+                Self::from_parts(width, depth, seed, values, occupied)
+            }
+        }
+    )*};
+}
+
+impl_byte_encoding!(u8, u16, u32, u64);
+
 struct SeedStream {
     state: u64,
 }
@@ -654,6 +904,41 @@ mod tests {
         assert_same_state(&left, &before_error);
     }
 
+    #[test]
+    fn merge_all_matches_a_merge_chain_and_validates_first() {
+        let shards: Vec<_> = (0_u64..8)
+            .map(|shard| {
+                let mut sketch = MinMaxSketch::<u16>::new(23, 4, SEED).unwrap();
+                for key in (shard..200).step_by(3) {
+                    sketch.insert_u64(key, (key * 31 + shard * 7) as u16);
+                }
+                sketch
+            })
+            .collect();
+
+        let mut chained = MinMaxSketch::<u16>::new(23, 4, SEED).unwrap();
+        for shard in &shards {
+            chained.merge(shard).unwrap();
+        }
+        let mut merged = MinMaxSketch::<u16>::new(23, 4, SEED).unwrap();
+        merged
+            .merge_all(&shards.iter().collect::<Vec<_>>())
+            .unwrap();
+        assert_same_state(&merged, &chained);
+
+        merged.merge_all(&[]).unwrap();
+        assert_same_state(&merged, &chained);
+
+        let different_seed = MinMaxSketch::<u16>::new(23, 4, SEED + 1).unwrap();
+        assert_eq!(
+            merged.merge_all(&[&shards[0], &different_seed]),
+            Err(SketchError::IncompatibleSketches(
+                "hash-family seeds must match for merge"
+            ))
+        );
+        assert_same_state(&merged, &chained);
+    }
+
     #[test]
     fn merge_is_commutative_associative_idempotent_and_has_an_empty_identity() {
         let mut sketches: Vec<_> = (0_u64..3)
@@ -701,6 +986,94 @@ mod tests {
         assert_same_state(&left_identity, &first);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_preserves_estimates_and_merge_compatibility() {
+        let mut original = MinMaxSketch::<u8>::new(37, 4, SEED).unwrap();
+        for key in 0_u64..200 {
+            original.insert(&key, (key % 251) as u8);
+        }
+
+        let json = serde_json::to_string(&original).unwrap();
+        let mut restored: MinMaxSketch<u8> = serde_json::from_str(&json).unwrap();
+        assert_same_state(&restored, &original);
+        for key in 0_u64..400 {
+            assert_eq!(restored.estimate(&key), original.estimate(&key));
+        }
+
+        restored.merge(&original).unwrap();
+        assert_same_state(&restored, &original);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_inconsistent_state() {
+        let short_values = r#"{"width":2,"depth":2,"seed":1,"values":[0,0,0],"occupied":[0]}"#;
+        assert!(serde_json::from_str::<MinMaxSketch<u8>>(short_values).is_err());
+
+        let missing_word = r#"{"width":2,"depth":2,"seed":1,"values":[0,0,0,0],"occupied":[]}"#;
+        assert!(serde_json::from_str::<MinMaxSketch<u8>>(missing_word).is_err());
+
+        let bit_past_table = r#"{"width":2,"depth":2,"seed":1,"values":[0,0,0,0],"occupied":[16]}"#;
+        assert!(serde_json::from_str::<MinMaxSketch<u8>>(bit_past_table).is_err());
+
+        let value_in_empty_cell =
+            r#"{"width":2,"depth":2,"seed":1,"values":[0,7,0,0],"occupied":[1]}"#;
+        assert!(serde_json::from_str::<MinMaxSketch<u8>>(value_in_empty_cell).is_err());
+
+        let valid = r#"{"width":2,"depth":2,"seed":1,"values":[0,7,0,0],"occupied":[2]}"#;
+        let sketch = serde_json::from_str::<MinMaxSketch<u8>>(valid).unwrap();
+        assert_eq!(sketch.occupied_cells(), 1);
+    }
+
+    #[test]
+    fn byte_round_trip_preserves_estimates_and_merge_compatibility() {
+        let mut original = MinMaxSketch::<u8>::new(37, 4, SEED).unwrap();
+        for key in 0_u64..200 {
+            original.insert(&key, (key % 251) as u8);
+        }
+
+        let mut restored = MinMaxSketch::<u8>::from_bytes(&original.to_bytes()).unwrap();
+        assert_same_state(&restored, &original);
+        for key in 0_u64..400 {
+            assert_eq!(restored.estimate(&key), original.estimate(&key));
+        }
+
+        restored.merge(&original).unwrap();
+        assert_same_state(&restored, &original);
+
+        let mut wide = MinMaxSketch::<u64>::new(5, 3, SEED).unwrap();
+        wide.insert(&"key", u64::MAX - 1);
+        let restored = MinMaxSketch::<u64>::from_bytes(&wide.to_bytes()).unwrap();
+        assert_same_state(&restored, &wide);
+    }
+
+    #[test]
+    fn from_bytes_rejects_inconsistent_payloads() {
+        let mut sketch = MinMaxSketch::<u8>::new(2, 2, SEED).unwrap();
+        sketch.insert_u64(1, 7);
+        let bytes = sketch.to_bytes();
+        assert_eq!(bytes.len(), 30 + 8 + 4);
+        assert!(MinMaxSketch::<u16>::from_bytes(&bytes).is_err());
+
+        let invalid = |edit: &dyn Fn(&mut Vec<u8>)| {
+            let mut payload = bytes.clone();
+            edit(&mut payload);
+            MinMaxSketch::<u8>::from_bytes(&payload).is_err()
+        };
+        assert!(invalid(&|payload| payload[0] = b'X'));
+        assert!(invalid(&|payload| payload[4] = 2));
+        assert!(invalid(&|payload| payload.truncate(20)));
+        assert!(invalid(&|payload| payload.truncate(payload.len() - 1)));
+        assert!(invalid(&|payload| payload.push(0)));
+        // Width zero.
+        assert!(invalid(&|payload| payload[6] = 0));
+        // An occupancy bit past the four-cell table.
+        assert!(invalid(&|payload| payload[30] |= 16));
+        // A value in a cell whose occupancy bit is clear.
+        assert!(invalid(&|payload| payload[30] = 0));
+    }
+
     #[test]
     fn clear_resets_values_and_occupancy_but_retains_configuration() {
         let mut sketch = MinMaxSketch::<u8>::new(13, 5, SEED).unwrap();
@@ -715,4 +1088,20 @@ mod tests {
         assert_eq!(sketch.depth(), 5);
         assert_eq!(sketch.seed(), SEED);
     }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let sketches = [
+            MinMaxSketch::<u8>::new(16, 3, SEED).unwrap(),
+            MinMaxSketch::<u8>::new(16, 5, SEED).unwrap(),
+            MinMaxSketch::<u8>::new(32, 3, SEED).unwrap(),
+            MinMaxSketch::<u8>::new(16, 3, SEED + 1).unwrap(),
+        ];
+        for left in &sketches {
+            for right in &sketches {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
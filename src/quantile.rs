@@ -0,0 +1,146 @@
+// MIT License
+//
+// Copyright (c) 2026 Raja Lehtihet & Wael El Oraiby
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+//! Quantile-sketch trait shared by KLL and t-digest.
+//!
+//! [`crate::kll::KllSketch`] and [`crate::tdigest::TDigest`] answer the same
+//! quantile queries under the crate's empirical inverse-CDF convention.
+//! [`QuantileSketch`] lets pipelines feed and query either one, and
+//! [`ingest_histogram`] loads pre-binned `(value, count)` data through it.
+
+use crate::SketchError;
+
+/// Common API for sketches that summarize a stream of `f64` values for
+/// quantile queries.
+///
+/// # Example
+/// ```rust
+/// use sketches::kll::KllSketch;
+/// use sketches::quantile::{QuantileSketch, ingest_histogram};
+/// use sketches::tdigest::TDigest;
+///
+/// let histogram = [(10.0, 30), (20.0, 40), (30.0, 30)];
+/// let mut kll = KllSketch::new(200).unwrap();
+/// let mut digest = TDigest::new(100.0).unwrap();
+/// ingest_histogram(&mut kll, &histogram).unwrap();
+/// ingest_histogram(&mut digest, &histogram).unwrap();
+///
+/// assert_eq!(QuantileSketch::quantile(&kll, 0.5).unwrap(), 20.0);
+/// assert_eq!(QuantileSketch::quantile(&digest, 0.5).unwrap(), 20.0);
+/// ```
+pub trait QuantileSketch {
+    /// Adds `value` as `count` observations.
+    ///
+    /// Each sketch filters non-finite values as its own weighted add does.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::ObservationCountOverflow`] when the sketch's
+    /// observation count cannot represent the new total.
+    fn add_count(&mut self, value: f64, count: u64) -> Result<(), SketchError>;
+
+    /// Returns the approximate quantile at `q` where `q` is in `[0, 1]`.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid `q` or an empty
+    /// sketch.
+    fn quantile(&self, q: f64) -> Result<f64, SketchError>;
+}
+
+/// Adds every `(value, count)` bucket of a histogram to `sketch`, in order.
+///
+/// Each bucket's observations are attributed to its single representative
+/// value, typically the bucket midpoint, so quantiles cannot resolve
+/// positions within a bucket. Zero-count buckets are skipped.
+///
+/// # Errors
+/// Returns the first error from [`QuantileSketch::add_count`]. Buckets before
+/// the failing one have already been added.
+pub fn ingest_histogram(
+    sketch: &mut dyn QuantileSketch,
+    histogram: &[(f64, u64)],
+) -> Result<(), SketchError> {
+    for &(value, count) in histogram {
+        sketch.add_count(value, count)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuantileSketch, ingest_histogram};
+    use crate::SketchError;
+    use crate::kll::KllSketch;
+    use crate::tdigest::TDigest;
+
+    #[test]
+    fn histograms_give_comparable_medians_across_backends() {
+        // A right-skewed histogram over midpoints 0.5, 1.5, ..., 99.5.
+        let histogram: Vec<(f64, u64)> = (0_u64..100)
+            .map(|bucket| (bucket as f64 + 0.5, 1_000 / (bucket + 1)))
+            .collect();
+        let total: u64 = histogram.iter().map(|&(_, count)| count).sum();
+
+        let mut kll = KllSketch::new(200).unwrap();
+        let mut digest = TDigest::new(100.0).unwrap();
+        let backends: [&mut dyn QuantileSketch; 2] = [&mut kll, &mut digest];
+        for sketch in backends {
+            ingest_histogram(sketch, &histogram).unwrap();
+        }
+        assert_eq!(kll.count(), total);
+        assert_eq!(digest.count(), total);
+
+        // The exact median lies in the bucket where the cumulative count
+        // first exceeds half the total.
+        let mut cumulative = 0;
+        let exact_median = histogram
+            .iter()
+            .find(|&&(_, count)| {
+                cumulative += count;
+                cumulative > total / 2
+            })
+            .unwrap()
+            .0;
+        for median in [
+            QuantileSketch::quantile(&kll, 0.5).unwrap(),
+            QuantileSketch::quantile(&digest, 0.5).unwrap(),
+        ] {
+            assert!(
+                (median - exact_median).abs() <= 2.0,
+                "median={median} exact={exact_median}"
+            );
+        }
+    }
+
+    #[test]
+    fn ingestion_skips_empty_buckets_and_reports_overflow() {
+        let mut kll = KllSketch::new(50).unwrap();
+        ingest_histogram(&mut kll, &[(1.0, 0), (2.0, 3)]).unwrap();
+        assert_eq!(kll.count(), 3);
+        assert_eq!(QuantileSketch::quantile(&kll, 0.0).unwrap(), 2.0);
+
+        assert_eq!(
+            ingest_histogram(&mut kll, &[(5.0, 1), (6.0, u64::MAX)]),
+            Err(SketchError::ObservationCountOverflow)
+        );
+        assert_eq!(kll.count(), 4);
+    }
+}
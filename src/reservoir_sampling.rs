@@ -21,8 +21,16 @@
 // SOFTWARE.
 //
 //! Reservoir sampling for uniform samples from streaming data.
+//!
+//! With the `serde` feature enabled, a reservoir serializes its capacity,
+//! samples, stream length, and generator state, so a reloaded reservoir
+//! continues exactly as the original would have.
 
-use crate::{SketchError, splitmix64};
+use alloc::vec::Vec;
+
+use crate::{SketchError, SplitMix64};
+
+const DEFAULT_SEED: u64 = 0x94D0_49BB_1331_11EB;
 
 /// Fixed-size uniform reservoir sample over a stream.
 ///
@@ -43,7 +51,7 @@ pub struct ReservoirSampling<T> {
     capacity: usize,
     samples: Vec<T>,
     seen: u64,
-    rng_state: u64,
+    rng: SplitMix64,
 }
 
 impl<T> ReservoirSampling<T> {
@@ -52,6 +60,18 @@ impl<T> ReservoirSampling<T> {
     /// # Errors
     /// Returns [`SketchError::InvalidParameter`] when `capacity == 0`.
     pub fn new(capacity: usize) -> Result<Self, SketchError> {
+        Self::with_seed(capacity, DEFAULT_SEED)
+    }
+
+    /// Creates a reservoir whose replacement choices are driven by `seed`.
+    ///
+    /// [`Self::new`] uses a fixed seed, so two reservoirs fed the same stream
+    /// keep the same sample. Give independent reservoirs different seeds when
+    /// their samples should not be correlated.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `capacity == 0`.
+    pub fn with_seed(capacity: usize, seed: u64) -> Result<Self, SketchError> {
         if capacity == 0 {
             return Err(SketchError::InvalidParameter(
                 "capacity must be greater than zero",
@@ -62,7 +82,7 @@ impl<T> ReservoirSampling<T> {
             capacity,
             samples: Vec::with_capacity(capacity),
             seen: 0,
-            rng_state: 0x94D0_49BB_1331_11EB,
+            rng: SplitMix64::new(seed),
         })
     }
 
@@ -86,6 +106,20 @@ impl<T> ReservoirSampling<T> {
         self.seen
     }
 
+    /// Returns the probability that any given stream item is in the sample,
+    /// `min(1, capacity / seen)`.
+    ///
+    /// Every item is retained with the same probability, so `1 /
+    /// inclusion_probability()` is the Horvitz-Thompson weight of each sampled
+    /// item. For example, the sampled sum times that weight is an unbiased
+    /// estimate of the stream total. Returns `1.0` before any item is seen.
+    pub fn inclusion_probability(&self) -> f64 {
+        if self.seen <= self.capacity as u64 {
+            return 1.0;
+        }
+        self.capacity as f64 / self.seen as f64
+    }
+
     /// Returns the sampled items.
     pub fn samples(&self) -> &[T] {
         &self.samples
@@ -100,7 +134,7 @@ impl<T> ReservoirSampling<T> {
             return;
         }
 
-        let replacement_index = self.next_u64() % self.seen;
+        let replacement_index = self.rng.next_u64() % self.seen;
         if replacement_index < self.capacity as u64 {
             self.samples[replacement_index as usize] = item;
         }
@@ -122,14 +156,159 @@ impl<T> ReservoirSampling<T> {
         self.seen = 0;
     }
 
+    /// Clears the sampler and changes its sample size to `new_capacity`.
+    ///
+    /// Like [`Self::clear`], this drops all samples and resets
+    /// [`Self::seen`], so the next stream is sampled from scratch at the new
+    /// size. The random-number state carries over. The sample buffer is
+    /// reallocated to fit the new capacity.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] without changing the sampler
+    /// when `new_capacity == 0`.
+    pub fn resize(&mut self, new_capacity: usize) -> Result<(), SketchError> {
+        if new_capacity == 0 {
+            return Err(SketchError::InvalidParameter(
+                "capacity must be greater than zero",
+            ));
+        }
+
+        self.clear();
+        self.samples.shrink_to(new_capacity);
+        self.samples.reserve_exact(new_capacity);
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
+    /// Returns a copy of the sampled items in a uniformly random order.
+    ///
+    /// Replacements overwrite slots in place, so [`Self::samples`] is a
+    /// uniform sample but not a uniform permutation of it. This applies a
+    /// Fisher-Yates shuffle driven by the internal generator to a copy of the
+    /// sample. The stored sample is left as is, but the generator advances,
+    /// so repeated calls return independent orders.
+    pub fn sampled_shuffled(&mut self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut shuffled = self.samples.clone();
+        for index in (1..shuffled.len()).rev() {
+            let swap_index = (self.rng.next_u64() % (index as u64 + 1)) as usize;
+            shuffled.swap(index, swap_index);
+        }
+        shuffled
+    }
+
+    /// Returns the sample's empirical `q`-quantile as an estimate of the
+    /// stream's, or `None` when `q` is outside `[0, 1]` or no comparable item
+    /// is sampled.
+    ///
+    /// The selected zero-based rank among the `n` sampled items is
+    /// `min(floor(q * n), n - 1)`, the crate-wide empirical inverse-CDF
+    /// convention shared with [`crate::kll::KllSketch`]. Because the sample
+    /// is uniform, the rank error is about `sqrt(q * (1 - q) / n)`, which is
+    /// cheap but far looser than a dedicated quantile sketch of the same
+    /// size. Items not comparable with themselves, such as `NaN`, are
+    /// skipped. This selects from a copy of the sample in expected `O(n)`
+    /// time.
+    pub fn approximate_quantile(&self, q: f64) -> Option<T>
+    where
+        T: PartialOrd + Clone,
+    {
+        if !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        let mut comparable: Vec<T> = self
+            .samples
+            .iter()
+            .filter(|item| item.partial_cmp(item).is_some())
+            .cloned()
+            .collect();
+        if comparable.is_empty() {
+            return None;
+        }
+
+        let rank = ((q * comparable.len() as f64) as usize).min(comparable.len() - 1);
+        let (_, selected, _) = comparable.select_nth_unstable_by(rank, |left, right| {
+            left.partial_cmp(right)
+                .unwrap_or(core::cmp::Ordering::Equal)
+        });
+        Some(selected.clone())
+    }
+
     /// Consumes the sampler and returns the sample buffer.
     pub fn into_samples(self) -> Vec<T> {
         self.samples
     }
 
-    fn next_u64(&mut self) -> u64 {
-        self.rng_state = splitmix64(self.rng_state.wrapping_add(0x9E37_79B9_7F4A_7C15));
-        self.rng_state
+    /// Consumes the sampler and returns the sample sorted ascending.
+    ///
+    /// Unlike [`Self::into_samples`], the order does not depend on which slots
+    /// replacements happened to overwrite, which makes it suitable for
+    /// comparing samples in fixtures.
+    pub fn into_sorted_samples(self) -> Vec<T>
+    where
+        T: Ord,
+    {
+        let mut samples = self.samples;
+        samples.sort();
+        samples
+    }
+}
+
+/// Serialized form of a [`ReservoirSampling`]. `S` is `&[T]` when
+/// serializing and `Vec<T>` when deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "ReservoirSampling")]
+struct ReservoirSamplingState<S> {
+    capacity: usize,
+    samples: S,
+    seen: u64,
+    rng_state: u64,
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for ReservoirSampling<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ReservoirSamplingState {
+            capacity: self.capacity,
+            samples: self.samples.as_slice(),
+            seen: self.seen,
+            rng_state: self.rng.state(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for ReservoirSampling<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let state = ReservoirSamplingState::<Vec<T>>::deserialize(deserializer)?;
+        if state.capacity == 0 {
+            return Err(D::Error::custom(SketchError::InvalidParameter(
+                "capacity must be greater than zero",
+            )));
+        }
+        // The reservoir fills before any replacement, so its length is fixed
+        // by the stream length.
+        let expected_len =
+            u64::try_from(state.capacity).map_or(state.seen, |capacity| capacity.min(state.seen));
+        if state.samples.len() as u64 != expected_len {
+            return Err(D::Error::custom(SketchError::InvalidParameter(
+                "sample count must equal min(capacity, seen)",
+            )));
+        }
+
+        Ok(Self {
+            capacity: state.capacity,
+            samples: state.samples,
+            seen: state.seen,
+            rng: SplitMix64::new(state.rng_state),
+        })
     }
 }
 
@@ -161,6 +340,46 @@ mod tests {
         assert_eq!(reservoir.samples(), &[1, 2, 3, 4]);
     }
 
+    #[test]
+    fn sample_quantiles_approximate_the_stream() {
+        let mut reservoir = ReservoirSampling::new(1_000).unwrap();
+        assert_eq!(reservoir.approximate_quantile(0.5), None);
+
+        for value in 0..10_000 {
+            reservoir.add(f64::from(value));
+        }
+        let median = reservoir.approximate_quantile(0.5).unwrap();
+        assert!((median - 5_000.0).abs() < 500.0, "median = {median}");
+        assert!(reservoir.approximate_quantile(0.1).unwrap() < median);
+        assert!(reservoir.approximate_quantile(0.9).unwrap() > median);
+        assert_eq!(reservoir.approximate_quantile(1.5), None);
+        assert_eq!(reservoir.approximate_quantile(f64::NAN), None);
+
+        let mut exact = ReservoirSampling::new(8).unwrap();
+        exact.extend([3.0, f64::NAN, 1.0, 2.0, 0.0]);
+        assert_eq!(exact.approximate_quantile(0.0), Some(0.0));
+        assert_eq!(exact.approximate_quantile(0.5), Some(2.0));
+        assert_eq!(exact.approximate_quantile(1.0), Some(3.0));
+    }
+
+    #[test]
+    fn sorted_samples_are_ordered_members_of_the_stream() {
+        let stream: Vec<u64> = (0_u64..5_000)
+            .map(|value| (value * 2_654_435_761) % 5_000)
+            .collect();
+        let mut reservoir = ReservoirSampling::new(200).unwrap();
+        reservoir.extend(stream.iter().copied());
+        let unsorted = reservoir.samples().to_vec();
+
+        let sorted = reservoir.into_sorted_samples();
+        assert_eq!(sorted.len(), 200);
+        assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(sorted.iter().all(|value| *value < 5_000));
+        let mut expected = unsorted;
+        expected.sort_unstable();
+        assert_eq!(sorted, expected);
+    }
+
     #[test]
     fn deterministic_for_same_input_stream() {
         let mut left = ReservoirSampling::new(50).unwrap();
@@ -174,6 +393,96 @@ mod tests {
         assert_eq!(left.samples(), right.samples());
     }
 
+    #[test]
+    fn seed_selects_the_sample() {
+        let sample = |seed| {
+            let mut reservoir = ReservoirSampling::with_seed(50, seed).unwrap();
+            reservoir.extend(0_u64..5_000);
+            reservoir.into_samples()
+        };
+
+        assert_eq!(sample(7), sample(7));
+        assert_ne!(sample(7), sample(8));
+        assert!(ReservoirSampling::<u64>::with_seed(0, 7).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_resumes_the_same_sample() {
+        let mut original = ReservoirSampling::new(32).unwrap();
+        original.extend(0_u64..1_000);
+
+        let json = serde_json::to_string(&original).unwrap();
+        let mut restored: ReservoirSampling<u64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.samples(), original.samples());
+        assert_eq!(restored.seen(), original.seen());
+
+        original.extend(1_000_u64..5_000);
+        restored.extend(1_000_u64..5_000);
+        assert_eq!(restored.samples(), original.samples());
+        assert_eq!(restored.seen(), 5_000);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_invalid_reservoirs() {
+        let zero_capacity = r#"{"capacity":0,"samples":[],"seen":0,"rng_state":1}"#;
+        assert!(serde_json::from_str::<ReservoirSampling<u64>>(zero_capacity).is_err());
+
+        let overfull = r#"{"capacity":1,"samples":[1,2],"seen":2,"rng_state":1}"#;
+        assert!(serde_json::from_str::<ReservoirSampling<u64>>(overfull).is_err());
+
+        let underfull = r#"{"capacity":4,"samples":[1],"seen":3,"rng_state":1}"#;
+        assert!(serde_json::from_str::<ReservoirSampling<u64>>(underfull).is_err());
+    }
+
+    #[test]
+    fn shuffled_samples_place_an_item_uniformly() {
+        const RUNS: usize = 10_000;
+        const SLOTS: usize = 8;
+
+        let mut reservoir = ReservoirSampling::new(SLOTS).unwrap();
+        reservoir.extend(0..SLOTS);
+
+        let mut positions = [0_usize; SLOTS];
+        for _ in 0..RUNS {
+            let shuffled = reservoir.sampled_shuffled();
+            let mut sorted = shuffled.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, reservoir.samples());
+
+            positions[shuffled.iter().position(|&value| value == 0).unwrap()] += 1;
+        }
+
+        // Each slot expects 1_250 hits with a standard deviation near 33.
+        let expected = RUNS / SLOTS;
+        for count in positions {
+            assert!(count.abs_diff(expected) < 150, "positions={positions:?}");
+        }
+        assert_eq!(reservoir.samples(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn inclusion_probability_is_capacity_over_stream_length() {
+        let mut reservoir = ReservoirSampling::new(100).unwrap();
+        assert_eq!(reservoir.inclusion_probability(), 1.0);
+
+        let mut streamed = 0_u64;
+        for length in [1_u64, 99, 100, 101, 1_000, 123_457] {
+            reservoir.extend(streamed..length);
+            streamed = length;
+            let expected = (100.0 / length as f64).min(1.0);
+            assert_eq!(
+                reservoir.inclusion_probability(),
+                expected,
+                "length={length}"
+            );
+        }
+
+        reservoir.clear();
+        assert_eq!(reservoir.inclusion_probability(), 1.0);
+    }
+
     #[test]
     fn clear_resets_state() {
         let mut reservoir = ReservoirSampling::new(8).unwrap();
@@ -183,4 +492,29 @@ mod tests {
         assert_eq!(reservoir.seen(), 0);
         assert!(reservoir.is_empty());
     }
+
+    #[test]
+    fn resize_clears_and_samples_at_the_new_capacity() {
+        let mut reservoir = ReservoirSampling::new(10).unwrap();
+        reservoir.extend(0_u64..1_000);
+        assert_eq!(reservoir.len(), 10);
+
+        reservoir.resize(100).unwrap();
+        assert_eq!(reservoir.capacity(), 100);
+        assert_eq!(reservoir.len(), 0);
+        assert_eq!(reservoir.seen(), 0);
+
+        reservoir.extend(1_000_u64..2_000);
+        assert_eq!(reservoir.len(), 100);
+        assert_eq!(reservoir.seen(), 1_000);
+        assert!(reservoir.samples().iter().all(|&value| value >= 1_000));
+
+        assert!(reservoir.resize(0).is_err());
+        assert_eq!(reservoir.capacity(), 100);
+        assert_eq!(reservoir.len(), 100);
+
+        reservoir.resize(4).unwrap();
+        reservoir.extend(0_u64..50);
+        assert_eq!(reservoir.len(), 4);
+    }
 }
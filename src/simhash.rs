@@ -0,0 +1,407 @@
+// MIT License
+//
+// Copyright (c) 2026 Raja Lehtihet & Wael El Oraiby
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+//! SimHash signatures for approximate cosine similarity.
+//!
+//! [Charikar's SimHash][charikar] projects a weighted feature vector onto `b`
+//! random hyperplanes and keeps one sign bit per hyperplane. Each feature
+//! contributes its weight with a pseudo-random sign to every one of `b` signed
+//! accumulators, and the finalized fingerprint sets bit `i` when accumulator
+//! `i` is positive. For two vectors at angle `theta`, each bit differs with
+//! probability `theta / pi`, so the Hamming distance `h` between fingerprints
+//! gives the cosine estimate `cos(pi * h / b)`.
+//!
+//! Feature signs come from a SipHash-1-3 hash of the feature under a per-word
+//! seed: one hash per 64-bit word of the fingerprint supplies the signs of
+//! that word's 64 bits. Because the accumulators are kept rather than only the
+//! finalized bits, two sketches of the same width and seed can be merged by
+//! summing accumulators, which equals sketching the concatenated feature
+//! streams.
+//!
+//! [charikar]: https://www.cs.princeton.edu/courses/archive/spr04/cos598B/bib/CharikarEstim.pdf
+
+use alloc::{vec, vec::Vec};
+use core::f64::consts::PI;
+use core::hash::Hash;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::{SketchError, seeded_hash64, splitmix64};
+
+/// Seed for the deterministic default hyperplane family.
+const DEFAULT_SEED: u64 = 0x94D0_49BB_1331_11EB;
+
+/// SimHash sketch for estimating cosine similarity between feature vectors.
+///
+/// # Example
+/// ```rust
+/// use sketches::simhash::SimHash;
+///
+/// let mut left = SimHash::new(1_024).unwrap();
+/// let mut right = SimHash::new(1_024).unwrap();
+///
+/// for feature in 0_u64..2_000 {
+///     left.add(&feature);
+/// }
+/// for feature in 1_000_u64..3_000 {
+///     right.add(&feature);
+/// }
+///
+/// // Exact cosine is 1_000 / 2_000 = 0.5.
+/// let estimate = left.estimate_cosine(&right).unwrap();
+/// assert!((estimate - 0.5).abs() < 0.1);
+/// ```
+///
+/// # Representation and complexity
+///
+/// A sketch with `b` bits owns `b` signed accumulators. Adding a feature takes
+/// `O(b)` time and `ceil(b / 64)` hashes; comparing or merging sketches takes
+/// `O(b)` time and requires matching widths and seeds.
+#[derive(Debug, Clone)]
+pub struct SimHash {
+    seed: u64,
+    accumulators: Vec<i64>,
+}
+
+impl SimHash {
+    /// Creates a SimHash sketch with a `bits`-bit fingerprint and the default
+    /// hyperplane family.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `bits == 0` or the
+    /// accumulators cannot be allocated.
+    pub fn new(bits: usize) -> Result<Self, SketchError> {
+        Self::with_seed(bits, DEFAULT_SEED)
+    }
+
+    /// Creates a SimHash sketch whose hyperplane family is derived from `seed`.
+    ///
+    /// Only sketches built with the same width and seed can be compared or
+    /// merged.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when `bits == 0` or the
+    /// accumulators cannot be allocated.
+    pub fn with_seed(bits: usize, seed: u64) -> Result<Self, SketchError> {
+        if bits == 0 {
+            return Err(SketchError::InvalidParameter(
+                "bits must be greater than zero",
+            ));
+        }
+
+        let mut accumulators = Vec::new();
+        accumulators
+            .try_reserve_exact(bits)
+            .map_err(|_| SketchError::InvalidParameter("bits is too large to allocate"))?;
+        accumulators.resize(bits, 0);
+
+        Ok(Self { seed, accumulators })
+    }
+
+    /// Returns the fingerprint width in bits.
+    pub fn bits(&self) -> usize {
+        self.accumulators.len()
+    }
+
+    /// Returns the hyperplane-family seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns `true` when every accumulator is zero.
+    ///
+    /// Features whose contributions cancel also leave the sketch empty.
+    pub fn is_empty(&self) -> bool {
+        self.accumulators.iter().all(|&value| value == 0)
+    }
+
+    /// Returns a read-only view of the pre-finalization accumulators.
+    pub fn accumulators(&self) -> &[i64] {
+        &self.accumulators
+    }
+
+    /// Adds one occurrence of `feature` with unit weight.
+    ///
+    /// Each call moves every accumulator by one, so overflow would take
+    /// `2^63` calls; accumulators saturate rather than wrap if it happens.
+    pub fn add<T: Hash + ?Sized>(&mut self, feature: &T) {
+        let accumulators = &mut self.accumulators;
+        for_each_sign(self.seed, accumulators.len(), feature, |index, positive| {
+            let value = &mut accumulators[index];
+            *value = if positive {
+                value.saturating_add(1)
+            } else {
+                value.saturating_sub(1)
+            };
+        });
+    }
+
+    /// Adds `feature` with a signed `weight`.
+    ///
+    /// A zero weight is a no-op, and a negative weight removes a previous
+    /// positive contribution exactly.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::CounterOverflow`] without changing the sketch if
+    /// any accumulator would leave the `i64` range.
+    pub fn add_weighted<T: Hash + ?Sized>(
+        &mut self,
+        feature: &T,
+        weight: i64,
+    ) -> Result<(), SketchError> {
+        let signed = |value: i64, positive: bool| {
+            if positive {
+                value.checked_add(weight)
+            } else {
+                value.checked_sub(weight)
+            }
+        };
+
+        let mut overflow = false;
+        for_each_sign(self.seed, self.bits(), feature, |index, positive| {
+            overflow |= signed(self.accumulators[index], positive).is_none();
+        });
+        if overflow {
+            return Err(SketchError::CounterOverflow);
+        }
+
+        let accumulators = &mut self.accumulators;
+        for_each_sign(self.seed, accumulators.len(), feature, |index, positive| {
+            accumulators[index] =
+                signed(accumulators[index], positive).expect("overflow was checked above");
+        });
+        Ok(())
+    }
+
+    /// Returns the finalized fingerprint, packed little-endian into 64-bit
+    /// words.
+    ///
+    /// Bit `i` is stored at bit `i % 64` of word `i / 64` and is set when
+    /// accumulator `i` is positive. Bits past [`Self::bits`] in the last word
+    /// are zero.
+    pub fn fingerprint(&self) -> Vec<u64> {
+        let mut words = vec![0_u64; self.bits().div_ceil(64)];
+        for (index, &value) in self.accumulators.iter().enumerate() {
+            if value > 0 {
+                words[index / 64] |= 1 << (index % 64);
+            }
+        }
+        words
+    }
+
+    /// Returns the number of differing fingerprint bits.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when the widths or seeds
+    /// differ.
+    pub fn hamming_distance(&self, other: &Self) -> Result<usize, SketchError> {
+        self.check_compatible(other)?;
+        Ok(self
+            .accumulators
+            .iter()
+            .zip(&other.accumulators)
+            .filter(|&(&left, &right)| (left > 0) != (right > 0))
+            .count())
+    }
+
+    /// Estimates the cosine similarity of the two sketched feature vectors.
+    ///
+    /// The estimate is `cos(pi * h / b)` for Hamming distance `h` and width
+    /// `b`, so it lies in `[-1, 1]`. Each bit is an independent Bernoulli
+    /// trial with success probability `theta / pi`, which makes the standard
+    /// error of the angle estimate `pi * sqrt(p * (1 - p) / b)` radians.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when the widths or seeds
+    /// differ.
+    pub fn estimate_cosine(&self, other: &Self) -> Result<f64, SketchError> {
+        let distance = self.hamming_distance(other)?;
+        Ok((PI * distance as f64 / self.bits() as f64).cos())
+    }
+
+    /// Adds another sketch's accumulators into this sketch.
+    ///
+    /// The result equals sketching both feature streams into one sketch.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::IncompatibleSketches`] when the widths or seeds
+    /// differ. Returns [`SketchError::CounterOverflow`] without changing the
+    /// sketch if any summed accumulator would leave the `i64` range.
+    pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
+        self.check_compatible(other)?;
+        if self
+            .accumulators
+            .iter()
+            .zip(&other.accumulators)
+            .any(|(left, right)| left.checked_add(*right).is_none())
+        {
+            return Err(SketchError::CounterOverflow);
+        }
+
+        for (left, right) in self.accumulators.iter_mut().zip(&other.accumulators) {
+            *left += right;
+        }
+        Ok(())
+    }
+
+    /// Resets every accumulator to zero while keeping width and seed.
+    pub fn clear(&mut self) {
+        self.accumulators.fill(0);
+    }
+
+    fn check_compatible(&self, other: &Self) -> Result<(), SketchError> {
+        if self.bits() != other.bits() || self.seed != other.seed {
+            return Err(SketchError::IncompatibleSketches("bits/seed must match"));
+        }
+        Ok(())
+    }
+}
+
+/// Calls `apply(index, positive)` for each of the first `bits` hyperplanes.
+///
+/// Word `w` of the fingerprint draws its 64 signs from one hash of `feature`
+/// under a seed derived from the family seed and `w`.
+fn for_each_sign<T, F>(seed: u64, bits: usize, feature: &T, mut apply: F)
+where
+    T: Hash + ?Sized,
+    F: FnMut(usize, bool),
+{
+    for word in 0..bits.div_ceil(64) {
+        let signs = seeded_hash64(feature, splitmix64(seed.wrapping_add(word as u64)));
+        let start = word * 64;
+        for offset in 0..(bits - start).min(64) {
+            apply(start + offset, (signs >> offset) & 1 == 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimHash;
+    use crate::SketchError;
+
+    fn sketch_range(bits: usize, range: core::ops::Range<u64>) -> SimHash {
+        let mut sketch = SimHash::new(bits).unwrap();
+        for feature in range {
+            sketch.add(&feature);
+        }
+        sketch
+    }
+
+    #[test]
+    fn constructor_validates_bits() {
+        assert!(SimHash::new(0).is_err());
+        let sketch = SimHash::new(70).unwrap();
+        assert_eq!(sketch.bits(), 70);
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.fingerprint(), vec![0, 0]);
+    }
+
+    #[test]
+    fn cosine_estimate_tracks_known_similarity() {
+        // Unit-weight sets of size 2_000 sharing 1_000 features: cosine 0.5.
+        let left = sketch_range(2_048, 0..2_000);
+        let right = sketch_range(2_048, 1_000..3_000);
+        let estimate = left.estimate_cosine(&right).unwrap();
+        assert!((estimate - 0.5).abs() < 0.08, "estimate = {estimate}");
+
+        assert_eq!(left.hamming_distance(&left.clone()).unwrap(), 0);
+        assert_eq!(left.estimate_cosine(&left.clone()).unwrap(), 1.0);
+
+        let disjoint = sketch_range(2_048, 10_000..12_000);
+        let estimate = left.estimate_cosine(&disjoint).unwrap();
+        assert!(estimate.abs() < 0.1, "estimate = {estimate}");
+    }
+
+    #[test]
+    fn weighted_features_follow_the_weighted_cosine() {
+        // left = (3, 1, 0), right = (3, 0, 1) over three feature groups, so the
+        // exact cosine is 9 / 10.
+        let mut left = SimHash::new(2_048).unwrap();
+        let mut right = SimHash::new(2_048).unwrap();
+        for feature in 0_u64..300 {
+            left.add_weighted(&("shared", feature), 3).unwrap();
+            right.add_weighted(&("shared", feature), 3).unwrap();
+            left.add(&("left", feature));
+            right.add(&("right", feature));
+        }
+        let estimate = left.estimate_cosine(&right).unwrap();
+        assert!((estimate - 0.9).abs() < 0.05, "estimate = {estimate}");
+
+        // A negative weight removes an earlier contribution exactly.
+        let before = left.accumulators().to_vec();
+        left.add_weighted(&"transient", 7).unwrap();
+        left.add_weighted(&"transient", -7).unwrap();
+        assert_eq!(left.accumulators(), before);
+    }
+
+    #[test]
+    fn merge_sums_accumulators_and_validates_inputs() {
+        let whole = sketch_range(128, 0..500);
+        let mut merged = sketch_range(128, 0..200);
+        merged.merge(&sketch_range(128, 200..500)).unwrap();
+        assert_eq!(merged.accumulators(), whole.accumulators());
+        assert_eq!(merged.fingerprint(), whole.fingerprint());
+
+        let before = merged.accumulators().to_vec();
+        assert_eq!(
+            merged.merge(&SimHash::new(64).unwrap()),
+            Err(SketchError::IncompatibleSketches("bits/seed must match"))
+        );
+        assert!(merged.merge(&SimHash::with_seed(128, 1).unwrap()).is_err());
+        assert!(merged.hamming_distance(&SimHash::new(64).unwrap()).is_err());
+        assert_eq!(merged.accumulators(), before);
+    }
+
+    #[test]
+    fn checked_updates_reject_overflow_without_modification() {
+        let mut sketch = SimHash::new(100).unwrap();
+        sketch.add_weighted(&"big", i64::MAX).unwrap();
+        let before = sketch.accumulators().to_vec();
+
+        assert_eq!(
+            sketch.add_weighted(&"big", 1),
+            Err(SketchError::CounterOverflow)
+        );
+        assert_eq!(
+            sketch.merge(&sketch.clone()),
+            Err(SketchError::CounterOverflow)
+        );
+        assert_eq!(sketch.accumulators(), before);
+
+        sketch.clear();
+        assert!(sketch.is_empty());
+    }
+
+    #[test]
+    fn fingerprint_packs_positive_accumulators() {
+        let sketch = sketch_range(70, 0..50);
+        let words = sketch.fingerprint();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1] >> 6, 0);
+        for (index, &value) in sketch.accumulators().iter().enumerate() {
+            assert_eq!((words[index / 64] >> (index % 64)) & 1 == 1, value > 0);
+        }
+        let ones: u32 = words.iter().map(|word| word.count_ones()).sum();
+        assert!(ones > 10 && ones < 60);
+    }
+}
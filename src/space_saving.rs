@@ -26,7 +26,8 @@
 //! counter when a previously untracked item arrives after the summary is full.
 //! This implementation accepts unit-weight observations through
 //! [`SpaceSaving::insert`], matching the update model in the [original
-//! Space-Saving paper].
+//! Space-Saving paper]. Weighted observations are accepted through
+//! [`SpaceSaving::add_checked`], which reports overflow instead of saturating.
 //!
 //! # Stream-Summary representation
 //!
@@ -35,7 +36,8 @@
 //! bucket form another doubly linked list. A hash table maps each tracked item
 //! to its counter. Because a unit update moves a counter only from `count` to
 //! `count + 1`, its destination is either the next bucket or a new bucket
-//! inserted immediately after the current one.
+//! inserted immediately after the current one. A weighted update walks forward
+//! from the current bucket past every bucket with a smaller count.
 //!
 //! Rust cannot safely store ordinary references into vectors that may move, so
 //! this implementation uses stable integer handles into private arenas. It has
@@ -43,6 +45,15 @@
 //! unsafe code. Empty buckets are removed immediately and their arena slots are
 //! reused.
 //!
+//! # Eviction order
+//!
+//! When several counters share the minimum count, the replaced counter is the
+//! one that most recently entered the minimum bucket: each bucket keeps its
+//! counters in a linked list with the newest arrival at the head, and
+//! replacement takes that head. The choice depends only on the sequence of
+//! updates, never on item hashes or the hash table's iteration order, so the
+//! same stream always evicts the same items.
+//!
 //! # Complexity
 //!
 //! Let `m` be the number of tracked counters and `k` the requested result size.
@@ -52,13 +63,19 @@
 //! | Operation | Time | Additional space | Why |
 //! | --- | ---: | ---: | --- |
 //! | [`SpaceSaving::insert`] | expected `O(1)` | `O(1)` | One hash lookup and a constant number of link changes |
+//! | [`SpaceSaving::add_checked`] | expected `O(1 + s)` | `O(1)` | Like `insert`, plus a walk over the `s` buckets the counter skips |
 //! | [`SpaceSaving::estimate`] / [`SpaceSaving::estimate_with_error`] / [`SpaceSaving::lower_bound`] | expected `O(1)` | `O(1)` | One hash lookup |
 //! | [`SpaceSaving::top_k`] | `O(min(k, m))` | `O(min(k, m))` | Traverses buckets from largest to smallest and clones only returned items |
+//! | [`SpaceSaving::rank`] | `O(r + 1)` | `O(1)` | Walks the `r` counters listed before the item in `top_k` order |
+//! | [`SpaceSaving::above_count`] | `O(v)` | `O(r)` | Traverses the `v` counters with estimates of at least the threshold and clones the `r` returned items |
 //! | [`SpaceSaving::merge`] | expected `O(m)` | `O(m)` | Hash combination, linear selection, and fixed-pass radix reconstruction |
+//! | [`SpaceSaving::retain`] | `O(m)` | `O(m)` | Filters counters, then rebuilds the Stream-Summary like `merge` |
 //! | [`SpaceSaving::clear`] | `O(m)` | `O(1)` | Drops all tracked items and bucket links |
 //! | Other accessors | `O(1)` | `O(1)` | Read stored fields |
 //!
-//! The retained representation itself uses `O(capacity)` space.
+//! The retained representation itself uses `O(capacity)` space. Without the
+//! `std` feature, the item index is a B-tree map and each lookup costs
+//! `O(log m)` comparisons instead of expected `O(1)` time.
 //!
 //! For a tracked item, the stored estimate is an upper bound and
 //! `estimate - error` is a lower bound on its frequency, provided the exact
@@ -67,14 +84,24 @@
 //! estimates and errors are combined symmetrically, using a full summary's
 //! minimum counter as the bound for an item missing from that summary.
 //!
+//! # Serialization
+//!
+//! With the `serde` feature enabled, a summary serializes its capacity, its
+//! counters as `(item, count, error)` triples ordered as in
+//! [`SpaceSaving::top_k`], and its total stream length. Stream-Summary links
+//! are rebuilt on deserialization, preserving the order of counters within
+//! each bucket so a reloaded summary evicts the same items as the original.
+//! Deserialization rejects a total stream length smaller than the sum of the
+//! counters' guaranteed frequencies `count - error`, unless the total has
+//! saturated at [`u64::MAX`].
+//!
 //! [original Space-Saving paper]: https://www.cs.ucsb.edu/sites/default/files/documents/2005-23.pdf
 //! [parallel Space-Saving construction]: https://arxiv.org/pdf/1401.0702
 
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
 
-use crate::SketchError;
+use crate::{Map, SketchError};
 
 type CounterHandle = usize;
 type BucketHandle = usize;
@@ -105,12 +132,23 @@ struct BucketNode {
     next: Option<BucketHandle>,
 }
 
+/// Requirements for items tracked by [`SpaceSaving`].
+///
+/// The item index is a hash map with the default `std` feature and a B-tree
+/// map without it. Items must satisfy both so that enabling `std` anywhere in
+/// a dependency graph cannot break code written for the other build. Every
+/// `Ord + Hash + Clone` type implements this trait automatically.
+pub trait TrackedItem: Ord + core::hash::Hash + Clone {}
+
+impl<T: Ord + core::hash::Hash + Clone> TrackedItem for T {}
+
 /// Approximate top-k tracker using Space-Saving and Stream-Summary.
 ///
 /// `SpaceSaving<T>` stores up to `capacity` candidate heavy hitters and accepts
-/// one stream observation per call to [`insert`](Self::insert). Weighted or
-/// batched updates are intentionally not part of this API: Stream-Summary's
-/// constant-time link update relies on every counter increasing by exactly one.
+/// one stream observation per call to [`insert`](Self::insert). Weighted
+/// updates through [`add_checked`](Self::add_checked) are also supported, but
+/// lose the constant-time link update: Stream-Summary's `O(1)` bound relies on
+/// every counter increasing by exactly one.
 ///
 /// # Example
 ///
@@ -129,12 +167,13 @@ struct BucketNode {
 #[derive(Debug, Clone)]
 pub struct SpaceSaving<T>
 where
-    T: Eq + Hash + Clone,
+    T: TrackedItem,
 {
     capacity: usize,
     /// Shares each immutable item allocation with its counter node.
-    lookup: HashMap<Arc<T>, CounterHandle>,
-    /// Counter nodes are never removed; full-summary replacement reuses one.
+    lookup: Map<Arc<T>, CounterHandle>,
+    /// Full-summary replacement reuses a counter node; only
+    /// [`SpaceSaving::decrement`] removes one, by swapping in the last node.
     counters: Vec<CounterNode<T>>,
     /// Bucket slots may be removed and subsequently reused.
     buckets: Vec<Option<BucketNode>>,
@@ -146,7 +185,7 @@ where
 
 impl<T> SpaceSaving<T>
 where
-    T: Eq + Hash + Clone,
+    T: TrackedItem,
 {
     /// Creates a sketch with the given number of tracked counters.
     ///
@@ -194,15 +233,97 @@ where
     /// Stream-Summary bucket/counter link changes take expected constant time.
     /// Counts and the total stream length saturate at [`u64::MAX`].
     pub fn insert(&mut self, item: T) {
-        if let Some(&counter) = self.lookup.get(&item) {
-            self.increment_counter(counter);
-        } else if self.counters.len() < self.capacity {
-            self.insert_new_counter(item);
-        } else {
-            self.replace_minimum(item);
+        self.record(item, 1);
+    }
+
+    /// Inserts `count` occurrences of `item`, failing instead of saturating.
+    ///
+    /// A weighted observation follows the same Space-Saving rules as `count`
+    /// repeated calls to [`insert`](Self::insert) on an item that is already
+    /// tracked or replaces the minimum counter. The counter moves directly to
+    /// its new bucket, walking past every bucket with a smaller count. Adding
+    /// zero occurrences is a no-op.
+    ///
+    /// Use this when [`total_count`](Self::total_count) feeds later arithmetic
+    /// such as phi-thresholds, where a saturated total would silently skew the
+    /// result.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::CounterOverflow`] without changing the sketch if
+    /// the total stream length or the item's counter would exceed
+    /// [`u64::MAX`].
+    pub fn add_checked(&mut self, item: T, count: u64) -> Result<(), SketchError> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        let base = match self.lookup.get(&item) {
+            Some(&counter) => self.counters[counter].count,
+            None => self.untracked_upper_bound(),
+        };
+        if base.checked_add(count).is_none() || self.total_count.checked_add(count).is_none() {
+            return Err(SketchError::CounterOverflow);
         }
 
-        self.total_count = self.total_count.saturating_add(1);
+        self.record(item, count);
+        Ok(())
+    }
+
+    /// Inserts `count` occurrences of `item`, reporting any evicted counter.
+    ///
+    /// When `item` is untracked and the summary is full, the minimum counter
+    /// is replaced as described under
+    /// [Eviction order](crate::space_saving#eviction-order), and `on_evict`
+    /// receives the displaced item together with the count it held. The
+    /// callback runs at most once per call and never for tracked items or
+    /// while the summary has spare capacity. Counts saturate as with
+    /// [`insert`](Self::insert), and adding zero occurrences is a no-op.
+    pub fn add_with_eviction<F>(&mut self, item: T, count: u64, mut on_evict: F)
+    where
+        F: FnMut(T, u64),
+    {
+        if count == 0 {
+            return;
+        }
+
+        if let Some((evicted, evicted_count)) = self.record(item, count) {
+            on_evict(Arc::unwrap_or_clone(evicted), evicted_count);
+        }
+    }
+
+    /// Removes `count` occurrences of `item`, for expiring observations from a
+    /// sliding window.
+    ///
+    /// A tracked item's counter is lowered, saturating at zero, and the item
+    /// stops being tracked once it reaches zero. [`Self::total_count`] is
+    /// lowered by `count`, saturating at zero, whether or not the item is
+    /// tracked, since the expired observations were part of the stream either
+    /// way. Untracked items are otherwise ignored. A counter's recorded error
+    /// is capped at its new count, so the error never exceeds the estimate,
+    /// and the affected buckets are relinked in `O(b)` time for the `b`
+    /// buckets between the old and new counts.
+    ///
+    /// This breaks the classic Space-Saving guarantee that every estimate is
+    /// at least the item's true frequency. Observations of an evicted item
+    /// were absorbed into the error of the counter that replaced it, and
+    /// expiring them later cannot take them back out, so estimates and
+    /// [`Self::lower_bound`] are no longer one-sided once decrements are
+    /// mixed with evictions. Decrementing more than was ever inserted
+    /// likewise understates other items.
+    pub fn decrement(&mut self, item: &T, count: u64) {
+        if count == 0 {
+            return;
+        }
+
+        self.total_count = self.total_count.saturating_sub(count);
+        let Some(&counter) = self.lookup.get(item) else {
+            return;
+        };
+        if count >= self.counters[counter].count {
+            self.remove_counter(counter);
+        } else {
+            self.decrement_counter(counter, count);
+        }
     }
 
     /// Returns the estimated count for `item` if it is currently tracked.
@@ -266,6 +387,138 @@ where
         result
     }
 
+    /// Returns the 0-based position of `item` among the tracked items sorted
+    /// by estimated count descending, or `None` if it is not tracked.
+    ///
+    /// Items with equal estimates are ranked in the order [`Self::top_k`]
+    /// lists them, so `top_k(rank + 1)` always ends with `item`. The rank is
+    /// among tracked items only and inherits their estimation error. The
+    /// query walks the Stream-Summary from its maximum bucket, taking
+    /// `O(rank + 1)` time.
+    pub fn rank(&self, item: &T) -> Option<usize> {
+        let &target = self.lookup.get(item)?;
+        let mut rank = 0;
+        let mut bucket = self.maximum_bucket;
+
+        while let Some(bucket_handle) = bucket {
+            let bucket_node = self.bucket(bucket_handle);
+            let mut counter = bucket_node.head;
+
+            while let Some(counter_handle) = counter {
+                if counter_handle == target {
+                    return Some(rank);
+                }
+                rank += 1;
+                counter = self.counters[counter_handle].next;
+            }
+
+            bucket = bucket_node.previous;
+        }
+
+        unreachable!("every tracked counter is linked into a bucket")
+    }
+
+    /// Returns tracked items whose guaranteed frequency is at least `n`.
+    ///
+    /// Each tuple is `(item, estimate, max_error)` and satisfies
+    /// `estimate - max_error >= n`, so before integer saturation every
+    /// returned item's exact frequency is at least `n`. Results are sorted by
+    /// estimated count descending; items with equal estimates may appear in
+    /// any order. The query walks buckets down from the maximum and stops at
+    /// the first bucket whose estimate is below `n`, because no counter there
+    /// can have a lower bound reaching the threshold.
+    pub fn above_count(&self, n: u64) -> Vec<(T, u64, u64)> {
+        let mut result = Vec::new();
+        let mut bucket = self.maximum_bucket;
+
+        while let Some(bucket_handle) = bucket {
+            let bucket_node = self.bucket(bucket_handle);
+            if bucket_node.count < n {
+                break;
+            }
+
+            let mut counter = bucket_node.head;
+            while let Some(counter_handle) = counter {
+                let node = &self.counters[counter_handle];
+                if node.count.saturating_sub(node.error) >= n {
+                    result.push((node.item.as_ref().clone(), node.count, node.error));
+                }
+                counter = node.next;
+            }
+
+            bucket = bucket_node.previous;
+        }
+
+        result
+    }
+
+    /// Keeps only the tracked items for which `predicate(item, estimate)`
+    /// returns `true`.
+    ///
+    /// Each removed counter's estimate is subtracted from
+    /// [`Self::total_count`], saturating at zero, so a removed item is
+    /// forgotten as if it had been tracked since it first arrived. Remaining
+    /// counters keep their estimates, errors, and eviction order.
+    ///
+    /// Freeing counters can leave the summary underfull. An underfull summary
+    /// treats untracked items as exactly absent, so an item evicted before
+    /// this call that reappears afterwards may be undercounted; estimates of
+    /// items tracked throughout remain bounded as before.
+    ///
+    /// The Stream-Summary is rebuilt from the retained counters in `O(m)`
+    /// time and temporary space.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&T, u64) -> bool,
+    {
+        let mut retained = Vec::with_capacity(self.lookup.len());
+        let mut removed_count = 0_u64;
+        let mut bucket = self.maximum_bucket;
+        while let Some(bucket_handle) = bucket {
+            let bucket_node = self.bucket(bucket_handle);
+            let mut counter = bucket_node.head;
+            while let Some(counter_handle) = counter {
+                let node = &self.counters[counter_handle];
+                if predicate(&node.item, node.count) {
+                    retained.push((Arc::clone(&node.item), self.counter_entry(counter_handle)));
+                } else {
+                    removed_count = removed_count.saturating_add(node.count);
+                }
+                counter = node.next;
+            }
+            bucket = bucket_node.previous;
+        }
+        if retained.len() == self.lookup.len() {
+            return;
+        }
+
+        // Rebuilding attaches each counter at its bucket head, so feed them in
+        // reverse to keep the within-bucket order.
+        retained.reverse();
+        let total_count = self.total_count.saturating_sub(removed_count);
+        *self = Self::from_entries(self.capacity, total_count, &retained);
+    }
+
+    /// Keeps only the `keep` tracked items with the largest estimates.
+    ///
+    /// This is a controlled downsize short of [`Self::clear`]: the heaviest
+    /// counters survive with their estimates and errors intact, and the
+    /// dropped counters are handled as in [`Self::retain`], including the
+    /// adjustment of [`Self::total_count`] and the underfull-summary caveat.
+    /// Among counters tied at the cutoff estimate, the ones [`Self::top_k`]
+    /// lists first are kept. Capacity is unchanged, and `keep` at or above
+    /// [`Self::tracked_items`] leaves the sketch unchanged.
+    pub fn compact_to(&mut self, keep: usize) {
+        // `retain` visits counters from the maximum bucket down, in the same
+        // order as `top_k`, so the first `keep` visits are the heaviest.
+        let mut remaining = keep;
+        self.retain(|_, _| {
+            let retained = remaining > 0;
+            remaining = remaining.saturating_sub(1);
+            retained
+        });
+    }
+
     /// Clears tracked counters, Stream-Summary buckets, and total count.
     pub fn clear(&mut self) {
         self.lookup.clear();
@@ -356,7 +609,10 @@ where
     fn empty_with_capacity(capacity: usize) -> Self {
         Self {
             capacity,
-            lookup: HashMap::with_capacity(capacity),
+            #[cfg(feature = "std")]
+            lookup: Map::with_capacity(capacity),
+            #[cfg(not(feature = "std"))]
+            lookup: Map::new(),
             counters: Vec::with_capacity(capacity),
             buckets: Vec::new(),
             free_buckets: Vec::new(),
@@ -366,18 +622,32 @@ where
         }
     }
 
-    fn insert_new_counter(&mut self, item: T) {
-        let bucket = match self.minimum_bucket {
-            None => self.allocate_bucket_after(None, 1),
-            Some(minimum) if self.bucket(minimum).count == 1 => minimum,
-            Some(_) => self.allocate_bucket_after(None, 1),
+    /// Applies a positive weight with saturating counts, returning the item
+    /// and count of any replaced minimum counter.
+    fn record(&mut self, item: T, count: u64) -> Option<(Arc<T>, u64)> {
+        debug_assert!(count > 0);
+        let evicted = if let Some(&counter) = self.lookup.get(&item) {
+            self.increment_counter(counter, count);
+            None
+        } else if self.counters.len() < self.capacity {
+            self.insert_new_counter(item, count);
+            None
+        } else {
+            Some(self.replace_minimum(item, count))
         };
+
+        self.total_count = self.total_count.saturating_add(count);
+        evicted
+    }
+
+    fn insert_new_counter(&mut self, item: T, count: u64) {
+        let bucket = self.bucket_after_with_count(None, count);
         let item = Arc::new(item);
         let counter = self.counters.len();
 
         self.counters.push(CounterNode {
             item: Arc::clone(&item),
-            count: 1,
+            count,
             error: 0,
             bucket,
             previous: None,
@@ -387,7 +657,7 @@ where
         self.lookup.insert(item, counter);
     }
 
-    fn replace_minimum(&mut self, item: T) {
+    fn replace_minimum(&mut self, item: T, count: u64) -> (Arc<T>, u64) {
         let minimum = self
             .minimum_bucket
             .expect("a full summary has a minimum bucket");
@@ -404,13 +674,14 @@ where
         self.counters[counter].item = Arc::clone(&item);
         self.counters[counter].error = minimum_count;
         self.lookup.insert(item, counter);
-        self.increment_counter(counter);
+        self.increment_counter(counter, count);
+        (old_item, minimum_count)
     }
 
-    fn increment_counter(&mut self, counter: CounterHandle) {
+    fn increment_counter(&mut self, counter: CounterHandle, amount: u64) {
         let old_bucket = self.counters[counter].bucket;
         let old_count = self.counters[counter].count;
-        let new_count = old_count.saturating_add(1);
+        let new_count = old_count.saturating_add(amount);
 
         // Saturation leaves the counter in the already-correct maximum-valued
         // bucket and avoids manufacturing another bucket with the same count.
@@ -418,11 +689,7 @@ where
             return;
         }
 
-        let next_bucket = self.bucket(old_bucket).next;
-        let destination = match next_bucket {
-            Some(next) if self.bucket(next).count == new_count => next,
-            _ => self.allocate_bucket_after(Some(old_bucket), new_count),
-        };
+        let destination = self.bucket_after_with_count(Some(old_bucket), new_count);
 
         self.detach_counter(counter);
         self.counters[counter].count = new_count;
@@ -433,6 +700,62 @@ where
         }
     }
 
+    fn decrement_counter(&mut self, counter: CounterHandle, amount: u64) {
+        let old_bucket = self.counters[counter].bucket;
+        let new_count = self.counters[counter].count - amount;
+        debug_assert!(new_count > 0);
+
+        let destination = self.bucket_before_with_count(old_bucket, new_count);
+
+        self.detach_counter(counter);
+        self.counters[counter].count = new_count;
+        self.counters[counter].error = self.counters[counter].error.min(new_count);
+        self.attach_counter(counter, destination);
+
+        if self.bucket(old_bucket).head.is_none() {
+            self.remove_bucket(old_bucket);
+        }
+    }
+
+    /// Unlinks a counter and moves the last counter node into its slot, so
+    /// the arena stays dense.
+    fn remove_counter(&mut self, counter: CounterHandle) {
+        let bucket = self.counters[counter].bucket;
+        self.detach_counter(counter);
+        if self.bucket(bucket).head.is_none() {
+            self.remove_bucket(bucket);
+        }
+
+        let removed = self.counters.swap_remove(counter);
+        let unmapped = self.lookup.remove(removed.item.as_ref());
+        debug_assert_eq!(unmapped, Some(counter));
+
+        if counter == self.counters.len() {
+            return;
+        }
+        let (item, previous, next, moved_bucket) = {
+            let moved = &self.counters[counter];
+            (
+                Arc::clone(&moved.item),
+                moved.previous,
+                moved.next,
+                moved.bucket,
+            )
+        };
+        *self
+            .lookup
+            .get_mut(item.as_ref())
+            .expect("every counter node is indexed") = counter;
+        if let Some(previous) = previous {
+            self.counters[previous].next = Some(counter);
+        } else {
+            self.bucket_mut(moved_bucket).head = Some(counter);
+        }
+        if let Some(next) = next {
+            self.counters[next].previous = Some(counter);
+        }
+    }
+
     fn attach_counter(&mut self, counter: CounterHandle, bucket: BucketHandle) {
         let old_head = self.bucket(bucket).head;
         {
@@ -466,9 +789,47 @@ where
         self.counters[counter].next = None;
     }
 
+    /// Returns the bucket holding `count`, allocating it if needed, by walking
+    /// forward from `previous` (or from the front when `previous` is `None`).
+    /// A unit increment stops at the first step because it cannot skip an
+    /// integer-valued bucket.
+    fn bucket_after_with_count(
+        &mut self,
+        mut previous: Option<BucketHandle>,
+        count: u64,
+    ) -> BucketHandle {
+        loop {
+            let next = match previous {
+                Some(previous) => self.bucket(previous).next,
+                None => self.minimum_bucket,
+            };
+            match next {
+                Some(next) if self.bucket(next).count < count => previous = Some(next),
+                Some(next) if self.bucket(next).count == count => return next,
+                _ => return self.allocate_bucket_after(previous, count),
+            }
+        }
+    }
+
+    /// Returns the bucket holding `count`, allocating it if needed, by walking
+    /// backward from `next`, whose count must exceed `count`.
+    fn bucket_before_with_count(&mut self, next: BucketHandle, count: u64) -> BucketHandle {
+        debug_assert!(count < self.bucket(next).count);
+        let mut previous = self.bucket(next).previous;
+        loop {
+            match previous {
+                Some(handle) if self.bucket(handle).count > count => {
+                    previous = self.bucket(handle).previous;
+                }
+                Some(handle) if self.bucket(handle).count == count => return handle,
+                _ => return self.allocate_bucket_after(previous, count),
+            }
+        }
+    }
+
     /// Allocates a bucket immediately after `previous`, or at the front when
-    /// `previous` is `None`. Callers know this exact position because unit
-    /// increments cannot skip an integer-valued bucket.
+    /// `previous` is `None`. Callers must pass the position that keeps bucket
+    /// counts strictly increasing.
     fn allocate_bucket_after(
         &mut self,
         previous: Option<BucketHandle>,
@@ -626,24 +987,117 @@ where
                 offsets[byte] += 1;
             }
 
-            std::mem::swap(&mut order, &mut scratch);
+            core::mem::swap(&mut order, &mut scratch);
         }
 
         order
     }
 }
 
+/// Serialized form of a [`SpaceSaving`] summary. `I` is `&T` when
+/// serializing and `T` when deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "SpaceSaving")]
+struct SpaceSavingState<I> {
+    capacity: usize,
+    counters: Vec<(I, u64, u64)>,
+    total_count: u64,
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for SpaceSaving<T>
+where
+    T: TrackedItem + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut counters = Vec::with_capacity(self.lookup.len());
+        let mut bucket = self.maximum_bucket;
+        while let Some(bucket_handle) = bucket {
+            let bucket_node = self.bucket(bucket_handle);
+            let mut counter = bucket_node.head;
+            while let Some(counter_handle) = counter {
+                let node = &self.counters[counter_handle];
+                counters.push((node.item.as_ref(), node.count, node.error));
+                counter = node.next;
+            }
+            bucket = bucket_node.previous;
+        }
+
+        SpaceSavingState {
+            capacity: self.capacity,
+            counters,
+            total_count: self.total_count,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for SpaceSaving<T>
+where
+    T: TrackedItem + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let state = SpaceSavingState::<T>::deserialize(deserializer)?;
+        if state.capacity == 0 {
+            return Err(D::Error::custom(SketchError::InvalidParameter(
+                "capacity must be greater than zero",
+            )));
+        }
+        if state.counters.len() > state.capacity {
+            return Err(D::Error::custom(SketchError::InvalidParameter(
+                "counter count must not exceed capacity",
+            )));
+        }
+
+        // Counters are stored from the head of the largest bucket. Rebuilding
+        // attaches each counter at its bucket head, so feed them in reverse to
+        // restore the original within-bucket order.
+        let mut entries = Vec::with_capacity(state.counters.len());
+        let mut guaranteed = 0_u128;
+        for (item, count, error) in state.counters.into_iter().rev() {
+            if count == 0 || error > count {
+                return Err(D::Error::custom(SketchError::InvalidParameter(
+                    "counters require 0 <= error <= count and count > 0",
+                )));
+            }
+            guaranteed += u128::from(count - error);
+            entries.push((Arc::new(item), CounterEntry { count, error }));
+        }
+        // Every guaranteed occurrence is part of the stream. A saturated total
+        // no longer bounds the stream length, so it cannot be checked.
+        if state.total_count != u64::MAX && guaranteed > u128::from(state.total_count) {
+            return Err(D::Error::custom(SketchError::InvalidParameter(
+                "total_count must cover the counters' guaranteed frequencies",
+            )));
+        }
+
+        // A repeated item overwrites its lookup entry, leaving an orphaned
+        // counter behind.
+        let summary = Self::from_entries(state.capacity, state.total_count, &entries);
+        if summary.lookup.len() != summary.counters.len() {
+            return Err(D::Error::custom(SketchError::InvalidParameter(
+                "counter items must be distinct",
+            )));
+        }
+        Ok(summary)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{HashMap, HashSet};
     use std::fmt::Debug;
-    use std::hash::Hash;
 
-    use super::SpaceSaving;
+    use super::{SpaceSaving, TrackedItem};
+    use crate::SketchError;
 
     fn insert_repeated<T>(sketch: &mut SpaceSaving<T>, item: T, count: u64)
     where
-        T: Eq + Hash + Clone,
+        T: TrackedItem,
     {
         for _ in 0..count {
             sketch.insert(item.clone());
@@ -652,7 +1106,7 @@ mod tests {
 
     fn assert_stream_summary_invariants<T>(sketch: &SpaceSaving<T>)
     where
-        T: Eq + Hash + Clone + Debug,
+        T: TrackedItem + Debug,
     {
         assert_eq!(sketch.lookup.len(), sketch.counters.len());
         assert!(sketch.lookup.len() <= sketch.capacity);
@@ -766,6 +1220,58 @@ mod tests {
         assert!(sketch.top_k(0).is_empty());
     }
 
+    #[test]
+    fn top_k_matches_a_full_sort_of_the_tracked_counters() {
+        let mut sketch = SpaceSaving::new(256).unwrap();
+        for index in 0_u64..20_000 {
+            sketch.insert((index * index) % 1_009);
+        }
+
+        let mut sorted: Vec<_> = sketch
+            .lookup
+            .values()
+            .map(|&counter| {
+                let node = &sketch.counters[counter];
+                (*node.item.as_ref(), node.count, node.error)
+            })
+            .collect();
+        sorted.sort_unstable_by_key(|entry| core::cmp::Reverse(entry.1));
+
+        for k in [0, 1, 10, 100, 256, 1_000] {
+            let top = sketch.top_k(k);
+            assert_eq!(top.len(), k.min(sorted.len()));
+            assert!(
+                top.iter()
+                    .zip(&sorted)
+                    .all(|(returned, expected)| returned.1 == expected.1)
+            );
+            assert!(top.iter().all(|entry| sorted.contains(entry)));
+            assert_eq!(top, sketch.top_k(sorted.len())[..top.len()]);
+        }
+    }
+
+    #[test]
+    fn rank_matches_frequency_order_and_top_k_positions() {
+        let mut sketch = SpaceSaving::new(8).unwrap();
+        for (item, count) in [("c", 3), ("a", 9), ("d", 1), ("b", 5)] {
+            insert_repeated(&mut sketch, item, count);
+        }
+        assert_eq!(sketch.rank(&"a"), Some(0));
+        assert_eq!(sketch.rank(&"b"), Some(1));
+        assert_eq!(sketch.rank(&"c"), Some(2));
+        assert_eq!(sketch.rank(&"d"), Some(3));
+        assert_eq!(sketch.rank(&"absent"), None);
+
+        let mut crowded = SpaceSaving::new(64).unwrap();
+        for index in 0_u64..5_000 {
+            crowded.insert((index * index) % 211);
+        }
+        let order = crowded.top_k(crowded.tracked_items());
+        for (position, (item, _, _)) in order.iter().enumerate() {
+            assert_eq!(crowded.rank(item), Some(position));
+        }
+    }
+
     #[test]
     fn high_cardinality_replacements_preserve_stream_summary_links() {
         let mut sketch = SpaceSaving::new(64).unwrap();
@@ -812,6 +1318,35 @@ mod tests {
         assert_stream_summary_invariants(&sketch);
     }
 
+    #[test]
+    fn above_count_returns_only_items_with_qualifying_lower_bounds() {
+        let mut exact = SpaceSaving::new(4).unwrap();
+        insert_repeated(&mut exact, "a", 10);
+        insert_repeated(&mut exact, "b", 5);
+        insert_repeated(&mut exact, "c", 4);
+        insert_repeated(&mut exact, "d", 1);
+
+        assert_eq!(exact.above_count(5), vec![("a", 10, 0), ("b", 5, 0)]);
+        assert_eq!(exact.above_count(11), Vec::new());
+        assert_eq!(exact.above_count(0).len(), 4);
+
+        let mut replaced = SpaceSaving::new(2).unwrap();
+        insert_repeated(&mut replaced, "a", 10);
+        insert_repeated(&mut replaced, "b", 4);
+        replaced.insert("c");
+        assert_eq!(replaced.estimate_with_error(&"c"), Some((5, 4)));
+
+        // "c" has estimate 5 but only guarantees one occurrence.
+        assert_eq!(replaced.above_count(5), vec![("a", 10, 0)]);
+        assert_eq!(replaced.above_count(1), vec![("a", 10, 0), ("c", 5, 4)]);
+        assert!(
+            SpaceSaving::<u64>::new(2)
+                .unwrap()
+                .above_count(0)
+                .is_empty()
+        );
+    }
+
     #[test]
     fn merge_preserves_capacity_one_source_error() {
         let mut left = SpaceSaving::new(1).unwrap();
@@ -994,6 +1529,70 @@ mod tests {
         assert_stream_summary_invariants(&left);
     }
 
+    #[test]
+    fn weighted_adds_match_repeated_inserts() {
+        let stream: Vec<(u64, u64)> = (0_u64..400).map(|i| ((i * 7) % 23, i % 5 + 1)).collect();
+        let mut weighted = SpaceSaving::new(8).unwrap();
+        let mut repeated = SpaceSaving::new(8).unwrap();
+        let mut exact = HashMap::new();
+        for &(item, count) in &stream {
+            weighted.add_checked(item, count).unwrap();
+            *exact.entry(item).or_insert(0) += count;
+            assert_stream_summary_invariants(&weighted);
+        }
+        assert_valid_bounds(&weighted, &exact);
+
+        // While nothing is evicted, weights are interchangeable with repeats.
+        for &(item, count) in stream.iter().filter(|(item, _)| *item < 8) {
+            insert_repeated(&mut repeated, item, count);
+        }
+        let mut direct = SpaceSaving::new(8).unwrap();
+        for &(item, count) in stream.iter().filter(|(item, _)| *item < 8) {
+            direct.add_checked(item, count).unwrap();
+        }
+        assert_eq!(direct.top_k(8), repeated.top_k(8));
+        assert_eq!(direct.total_count(), repeated.total_count());
+
+        let before = weighted.top_k(8);
+        weighted.add_checked(99, 0).unwrap();
+        assert_eq!(weighted.top_k(8), before);
+    }
+
+    #[test]
+    fn add_checked_reports_overflow_without_modification() {
+        let mut sketch = SpaceSaving::new(2).unwrap();
+        sketch.add_checked("big", u64::MAX).unwrap();
+        assert_eq!(sketch.total_count(), u64::MAX);
+
+        assert_eq!(
+            sketch.add_checked("big", 1),
+            Err(SketchError::CounterOverflow)
+        );
+        assert_eq!(
+            sketch.add_checked("other", 1),
+            Err(SketchError::CounterOverflow)
+        );
+        assert_eq!(sketch.estimate(&"big"), Some(u64::MAX));
+        assert_eq!(sketch.tracked_items(), 1);
+        assert_eq!(sketch.total_count(), u64::MAX);
+        assert_stream_summary_invariants(&sketch);
+
+        // A full summary checks the replaced minimum's counter as well.
+        let mut full = SpaceSaving::new(1).unwrap();
+        full.add_checked(0_u64, u64::MAX - 1).unwrap();
+        let mut small_total = full.clone();
+        small_total.total_count = 1;
+        assert_eq!(
+            small_total.add_checked(1, 2),
+            Err(SketchError::CounterOverflow)
+        );
+        small_total.add_checked(1, 1).unwrap();
+        assert_eq!(
+            small_total.estimate_with_error(&1),
+            Some((u64::MAX, u64::MAX - 1))
+        );
+    }
+
     #[test]
     fn merge_rejects_mismatched_capacity_without_modification() {
         let mut left: SpaceSaving<String> = SpaceSaving::new(4).unwrap();
@@ -1009,6 +1608,239 @@ mod tests {
         assert_stream_summary_invariants(&left);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_resumes_the_same_stream_summary() {
+        let stream: Vec<u64> = (0_u64..4_000).map(|i| (i * i + 7 * i) % 97).collect();
+        let (first, second) = stream.split_at(1_500);
+
+        let mut original = SpaceSaving::new(16).unwrap();
+        for &item in first {
+            original.insert(item);
+        }
+
+        let json = serde_json::to_string(&original).unwrap();
+        let mut restored: SpaceSaving<u64> = serde_json::from_str(&json).unwrap();
+        assert_stream_summary_invariants(&restored);
+        assert_eq!(restored.total_count(), original.total_count());
+        assert_eq!(restored.top_k(16), original.top_k(16));
+
+        for &item in second {
+            original.insert(item);
+            restored.insert(item);
+        }
+        assert_stream_summary_invariants(&restored);
+        assert_eq!(restored.total_count(), original.total_count());
+        assert_eq!(restored.top_k(16), original.top_k(16));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_after_decrement_keeps_error_within_count() {
+        let mut sketch = SpaceSaving::new(1).unwrap();
+        insert_repeated(&mut sketch, "evicted", 5);
+        insert_repeated(&mut sketch, "kept", 3);
+        assert_eq!(sketch.estimate_with_error(&"kept"), Some((8, 5)));
+
+        sketch.decrement(&"kept", 5);
+        assert_eq!(sketch.estimate_with_error(&"kept"), Some((3, 3)));
+
+        let json = serde_json::to_string(&sketch).unwrap();
+        let restored: SpaceSaving<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.top_k(1), vec![("kept".to_string(), 3, 3)]);
+        assert_eq!(restored.total_count(), sketch.total_count());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_invalid_summaries() {
+        let zero_capacity = r#"{"capacity":0,"counters":[],"total_count":0}"#;
+        assert!(serde_json::from_str::<SpaceSaving<u64>>(zero_capacity).is_err());
+
+        let over_capacity = r#"{"capacity":1,"counters":[[1,2,0],[2,1,0]],"total_count":3}"#;
+        assert!(serde_json::from_str::<SpaceSaving<u64>>(over_capacity).is_err());
+
+        let duplicate = r#"{"capacity":2,"counters":[[1,2,0],[1,1,0]],"total_count":3}"#;
+        assert!(serde_json::from_str::<SpaceSaving<u64>>(duplicate).is_err());
+
+        let excess_error = r#"{"capacity":2,"counters":[[1,2,3]],"total_count":2}"#;
+        assert!(serde_json::from_str::<SpaceSaving<u64>>(excess_error).is_err());
+
+        let short_total = r#"{"capacity":2,"counters":[[1,5,1],[2,3,0]],"total_count":6}"#;
+        assert!(serde_json::from_str::<SpaceSaving<u64>>(short_total).is_err());
+
+        let covering_total = r#"{"capacity":2,"counters":[[1,5,1],[2,3,0]],"total_count":7}"#;
+        assert!(serde_json::from_str::<SpaceSaving<u64>>(covering_total).is_ok());
+    }
+
+    #[test]
+    fn retain_drops_counters_and_their_counts() {
+        let mut sketch = SpaceSaving::new(4).unwrap();
+        insert_repeated(&mut sketch, "a", 8);
+        insert_repeated(&mut sketch, "b", 5);
+        insert_repeated(&mut sketch, "c", 2);
+        insert_repeated(&mut sketch, "d", 1);
+        assert_eq!(sketch.total_count(), 16);
+
+        sketch.retain(|_, count| count >= 5);
+        assert_stream_summary_invariants(&sketch);
+        assert_eq!(sketch.tracked_items(), 2);
+        assert_eq!(sketch.total_count(), 13);
+        assert_eq!(sketch.estimate(&"a"), Some(8));
+        assert_eq!(sketch.estimate(&"c"), None);
+        assert_eq!(sketch.top_k(4), vec![("a", 8, 0), ("b", 5, 0)]);
+
+        sketch.retain(|&item, _| item != "a");
+        assert_stream_summary_invariants(&sketch);
+        assert_eq!(sketch.tracked_items(), 1);
+        assert_eq!(sketch.total_count(), 5);
+
+        sketch.insert("e");
+        sketch.insert("b");
+        assert_eq!(sketch.top_k(4), vec![("b", 6, 0), ("e", 1, 0)]);
+        assert_eq!(sketch.total_count(), 7);
+        assert_stream_summary_invariants(&sketch);
+    }
+
+    #[test]
+    fn compact_to_keeps_the_heaviest_counters() {
+        let mut sketch = SpaceSaving::new(4).unwrap();
+        insert_repeated(&mut sketch, "a", 8);
+        insert_repeated(&mut sketch, "b", 2);
+        insert_repeated(&mut sketch, "c", 5);
+        insert_repeated(&mut sketch, "d", 1);
+        let top_two = sketch.top_k(2);
+
+        sketch.compact_to(4);
+        assert_eq!(sketch.tracked_items(), 4);
+        assert_eq!(sketch.total_count(), 16);
+
+        sketch.compact_to(2);
+        assert_stream_summary_invariants(&sketch);
+        assert_eq!(sketch.top_k(4), top_two);
+        assert_eq!(sketch.top_k(4), vec![("a", 8, 0), ("c", 5, 0)]);
+        assert_eq!(sketch.total_count(), 13);
+        assert_eq!(sketch.capacity(), 4);
+
+        sketch.compact_to(0);
+        assert!(sketch.top_k(4).is_empty());
+        assert_eq!(sketch.total_count(), 0);
+        assert_stream_summary_invariants(&sketch);
+    }
+
+    #[test]
+    fn retain_preserves_eviction_order_within_buckets() {
+        let mut retained = SpaceSaving::new(4).unwrap();
+        let mut reference = SpaceSaving::new(4).unwrap();
+        for item in [1_u64, 99, 2, 3] {
+            retained.insert(item);
+        }
+        for item in [1_u64, 2, 3] {
+            reference.insert(item);
+        }
+        retained.retain(|&item, _| item != 99);
+        assert_eq!(retained.total_count(), 3);
+        for item in 10_u64..20 {
+            retained.insert(item);
+            reference.insert(item);
+        }
+        assert_eq!(retained.top_k(4), reference.top_k(4));
+        assert_stream_summary_invariants(&retained);
+    }
+
+    #[test]
+    fn ties_evict_the_newest_minimum_counter_on_every_run() {
+        for _ in 0..16 {
+            let mut sketch = SpaceSaving::new(3).unwrap();
+            for item in ["a", "b", "c", "d", "e"] {
+                sketch.insert(item);
+            }
+
+            // "c" and then "b" were the newest counters at count one.
+            assert_eq!(sketch.estimate(&"a"), Some(1));
+            assert_eq!(sketch.estimate(&"b"), None);
+            assert_eq!(sketch.estimate(&"c"), None);
+            assert_eq!(sketch.estimate_with_error(&"d"), Some((2, 1)));
+            assert_eq!(sketch.estimate_with_error(&"e"), Some((2, 1)));
+            assert_stream_summary_invariants(&sketch);
+        }
+    }
+
+    #[test]
+    fn add_with_eviction_reports_the_replaced_minimum() {
+        let mut sketch = SpaceSaving::new(3).unwrap();
+        let mut evicted = Vec::new();
+        for (item, count) in [("a", 5), ("b", 2), ("c", 3), ("a", 1)] {
+            sketch.add_with_eviction(item, count, |item, count| evicted.push((item, count)));
+        }
+        sketch.add_with_eviction("d", 0, |item, count| evicted.push((item, count)));
+        assert!(evicted.is_empty());
+
+        sketch.add_with_eviction("d", 1, |item, count| evicted.push((item, count)));
+        assert_eq!(evicted, vec![("b", 2)]);
+        assert_eq!(sketch.estimate_with_error(&"d"), Some((3, 2)));
+        assert_eq!(sketch.estimate(&"b"), None);
+
+        // "d" reached the minimum count of three after "c", so it goes next.
+        sketch.add_with_eviction("e", 4, |item, count| evicted.push((item, count)));
+        assert_eq!(evicted, vec![("b", 2), ("d", 3)]);
+        assert_eq!(sketch.total_count(), 16);
+        assert_stream_summary_invariants(&sketch);
+    }
+
+    #[test]
+    fn decrement_lowers_counters_and_drops_exhausted_items() {
+        let mut sketch = SpaceSaving::new(4).unwrap();
+        insert_repeated(&mut sketch, "steady", 7);
+        insert_repeated(&mut sketch, "other", 3);
+        let before = sketch.total_count();
+
+        sketch.add_checked("burst", 100).unwrap();
+        sketch.decrement(&"burst", 100);
+        assert_eq!(sketch.estimate(&"burst"), None);
+        assert_eq!(sketch.tracked_items(), 2);
+        assert_eq!(sketch.total_count(), before);
+        assert_stream_summary_invariants(&sketch);
+
+        sketch.decrement(&"steady", 5);
+        assert_eq!(sketch.top_k(4), vec![("other", 3, 0), ("steady", 2, 0)]);
+        sketch.decrement(&"other", 2);
+        sketch.decrement(&"steady", 1);
+        assert_eq!(sketch.estimate(&"other"), Some(1));
+        assert_eq!(sketch.estimate(&"steady"), Some(1));
+        assert_stream_summary_invariants(&sketch);
+
+        sketch.decrement(&"missing", 1);
+        sketch.decrement(&"steady", 0);
+        assert_eq!(sketch.total_count(), 1);
+        sketch.decrement(&"steady", 50);
+        assert_eq!(sketch.top_k(4), vec![("other", 1, 0)]);
+        assert_eq!(sketch.total_count(), 0);
+        assert_stream_summary_invariants(&sketch);
+
+        // Freed slots are refilled before any eviction.
+        for item in ["a", "b", "c"] {
+            sketch.insert(item);
+        }
+        assert_eq!(sketch.tracked_items(), 4);
+        assert_stream_summary_invariants(&sketch);
+    }
+
+    #[test]
+    fn decrement_keeps_links_valid_under_churn() {
+        let mut sketch = SpaceSaving::new(32).unwrap();
+        for index in 0_u64..5_000 {
+            sketch.add_checked(index % 97, index % 5 + 1).unwrap();
+            if index % 3 == 0 {
+                sketch.decrement(&((index * 7) % 97), index % 4 + 1);
+            }
+            if index % 250 == 0 {
+                assert_stream_summary_invariants(&sketch);
+            }
+        }
+        assert_stream_summary_invariants(&sketch);
+    }
+
     #[test]
     fn clear_resets_state_and_allows_reuse() {
         let mut sketch = SpaceSaving::new(3).unwrap();
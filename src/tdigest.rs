@@ -37,13 +37,25 @@
 //! centroid array. Keeping the buffer ordered costs `O(log B)` per addition,
 //! where `B` is bounded by roughly `10 * compression`, but lets read-only
 //! quantile queries traverse all current data without cloning or sorting.
+//! [`TDigest::with_buffer_factor`] replaces the factor of 10 for callers that
+//! prefer a smaller buffer and more frequent merges.
+//!
+//! Alongside the centroids, the digest keeps a running mean and sum of squared
+//! deviations using West's weighted form of Welford's update, and combines
+//! them on merge with the pairwise formula of Chan, Golub, and LeVeque. These
+//! moments are exact up to floating-point rounding and back
+//! [`TDigest::variance`] and [`TDigest::std_dev`].
 //!
 //! [t-digest paper]: https://arxiv.org/pdf/1902.04023
 
-use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 use crate::SketchError;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
+use crate::quantile::QuantileSketch;
 
 const BUFFER_MULTIPLIER: f64 = 10.0;
 
@@ -107,6 +119,12 @@ pub struct TDigest {
     total_weight: f64,
     min: f64,
     max: f64,
+    /// Buffer capacity as a multiple of `compression`.
+    buffer_factor: f64,
+    /// Running weighted mean of every added value.
+    mean: f64,
+    /// Running weighted sum of squared deviations from `mean`.
+    m2: f64,
 }
 
 impl TDigest {
@@ -119,11 +137,32 @@ impl TDigest {
     /// Returns [`SketchError::InvalidParameter`] for non-finite or too-small
     /// compression values.
     pub fn new(compression: f64) -> Result<Self, SketchError> {
+        Self::with_buffer_factor(compression, BUFFER_MULTIPLIER)
+    }
+
+    /// Creates a digest whose addition buffer holds up to
+    /// `ceil(compression * buffer_factor)` values before it is merged into the
+    /// centroids.
+    ///
+    /// [`Self::new`] uses a factor of 10. Smaller factors merge more often,
+    /// which bounds memory more tightly at the cost of more merge passes; the
+    /// merged centroids, and therefore accuracy, follow `compression` either
+    /// way.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid compression or a
+    /// `buffer_factor` that is not finite and at least 1.
+    pub fn with_buffer_factor(compression: f64, buffer_factor: f64) -> Result<Self, SketchError> {
         if !compression.is_finite() || compression < 10.0 {
             return Err(SketchError::InvalidParameter(
                 "compression must be finite and greater than or equal to 10",
             ));
         }
+        if !buffer_factor.is_finite() || buffer_factor < 1.0 {
+            return Err(SketchError::InvalidParameter(
+                "buffer_factor must be finite and at least 1",
+            ));
+        }
 
         Ok(Self {
             compression,
@@ -133,9 +172,74 @@ impl TDigest {
             total_weight: 0.0,
             min: f64::INFINITY,
             max: f64::NEG_INFINITY,
+            buffer_factor,
+            mean: 0.0,
+            m2: 0.0,
         })
     }
 
+    /// Reconstructs a digest from exported `(mean, weight)` centroids.
+    ///
+    /// Centroids may be given in any order; they are stably sorted by mean and
+    /// kept as-is without recompression, so importing the output of
+    /// [`Self::centroids`] reproduces the exported quantiles. Total weight is
+    /// recomputed from the centroids. Centroid lists do not carry the exact
+    /// observed extrema, so the first and last centroid means stand in for the
+    /// minimum and maximum. These coincide whenever the terminal centroids are
+    /// singletons, as they usually are after compression. Likewise, the
+    /// variance is seeded from the centroid means alone; it omits the spread
+    /// within each centroid and so underestimates the original variance.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid compression,
+    /// non-finite means, weights that are not finite and positive, or a total
+    /// weight that is not finite.
+    pub fn from_centroids(
+        compression: f64,
+        centroids: Vec<(f64, f64)>,
+    ) -> Result<Self, SketchError> {
+        let mut digest = Self::new(compression)?;
+        let mut total_weight = 0.0;
+        for &(mean, weight) in &centroids {
+            if !mean.is_finite() {
+                return Err(SketchError::InvalidParameter(
+                    "centroid means must be finite",
+                ));
+            }
+            if !weight.is_finite() || weight <= 0.0 {
+                return Err(SketchError::InvalidParameter(
+                    "centroid weights must be finite and positive",
+                ));
+            }
+            total_weight += weight;
+        }
+        if !total_weight.is_finite() {
+            return Err(SketchError::InvalidParameter(
+                "total centroid weight must be finite",
+            ));
+        }
+
+        digest.centroids = centroids
+            .into_iter()
+            .map(|(mean, weight)| Centroid { mean, weight })
+            .collect();
+        digest
+            .centroids
+            .sort_by(|left, right| left.mean.total_cmp(&right.mean));
+        if let (Some(first), Some(last)) = (digest.centroids.first(), digest.centroids.last()) {
+            digest.min = first.mean;
+            digest.max = last.mean;
+        }
+        let mut seen_weight = 0.0;
+        for index in 0..digest.centroids.len() {
+            let Centroid { mean, weight } = digest.centroids[index];
+            digest.combine_moments(seen_weight, weight, mean, 0.0);
+            seen_weight += weight;
+        }
+        digest.total_weight = total_weight;
+        Ok(digest)
+    }
+
     /// Creates a digest from a target quantile error heuristic.
     ///
     /// Internally uses: `compression = ceil(10 / error)`.
@@ -158,11 +262,27 @@ impl TDigest {
         self.compression
     }
 
+    /// Returns the buffer capacity as a multiple of [`Self::compression`].
+    pub fn buffer_factor(&self) -> f64 {
+        self.buffer_factor
+    }
+
     /// Returns the number of merged and buffered centroids currently tracked.
     pub fn centroid_count(&self) -> usize {
         self.centroids.len() + self.buffered.len()
     }
 
+    /// Returns a copy of all merged and buffered centroids as `(mean, weight)`
+    /// pairs ordered by mean.
+    ///
+    /// Pending additions appear as unit-weight centroids; the digest is not
+    /// compressed by this call.
+    pub fn centroids(&self) -> Vec<(f64, f64)> {
+        self.ordered_centroids()
+            .map(|centroid| (centroid.mean, centroid.weight))
+            .collect()
+    }
+
     /// Returns the total observed weight rounded to `u64`.
     pub fn count(&self) -> u64 {
         self.total_weight.round() as u64
@@ -184,7 +304,58 @@ impl TDigest {
 
         self.min = self.min.min(value);
         self.max = self.max.max(value);
-        self.add_weighted(value, 1.0);
+        self.combine_moments(self.total_weight, 1.0, value, 0.0);
+        self.push_weighted(value, 1.0);
+    }
+
+    /// Adds `value` with observation weight `weight`, as if it had been added
+    /// `weight` times.
+    ///
+    /// This is the natural entry point for pre-aggregated `(value, count)`
+    /// data. Non-finite values and non-finite or non-positive weights are
+    /// ignored. Fractional weights are accepted; [`Self::count`] rounds the
+    /// accumulated weight.
+    pub fn add_weighted(&mut self, value: f64, weight: f64) {
+        if !value.is_finite() || !weight.is_finite() || weight <= 0.0 {
+            return;
+        }
+
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.combine_moments(self.total_weight, weight, value, 0.0);
+        self.push_weighted(value, weight);
+    }
+
+    /// Returns the weighted population variance of every added value, or
+    /// `None` for an empty digest.
+    ///
+    /// Unlike quantiles, this does not depend on the centroids: it is computed
+    /// from running moments that are exact up to floating-point rounding, and
+    /// it divides by the total weight. Values near the ends of the finite
+    /// `f64` range can make the result infinite.
+    pub fn variance(&self) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        Some((self.m2 / self.total_weight).max(0.0))
+    }
+
+    /// Returns the square root of [`Self::variance`], or `None` for an empty
+    /// digest.
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Returns the weighted sum of every added value, or `0` for an empty
+    /// digest.
+    ///
+    /// This is the running mean times the total weight, so it is exact up to
+    /// floating-point rounding and independent of the centroids.
+    pub fn sum(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.mean * self.total_weight
     }
 
     /// Returns the approximate quantile for `q` in `[0, 1]`.
@@ -297,6 +468,193 @@ impl TDigest {
         Ok(self.max)
     }
 
+    /// Returns the approximate quantile for `q` together with a half-width
+    /// describing the digest's local resolution at that rank.
+    ///
+    /// The estimate is [`Self::quantile`]. The half-width is half the value
+    /// gap between the two centroids whose midpoint ranks bracket `q * N`,
+    /// with the retained minimum and maximum standing in beyond the first and
+    /// last centroids. Heavier centroids and sparser data both widen that
+    /// gap, so it is small in dense regions and grows in sparse tails. The
+    /// half-width is zero at `q = 0` and `q = 1` and wherever both bracketing
+    /// centroids are single exact samples.
+    ///
+    /// This is a heuristic error bar rather than a guarantee: it reflects how
+    /// far apart the digest's summaries are around the target, not the
+    /// sampling error of the underlying stream.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid `q` or empty
+    /// digests.
+    pub fn quantile_with_bound(&self, q: f64) -> Result<(f64, f64), SketchError> {
+        let estimate = self.quantile(q)?;
+        if q == 0.0 || q == 1.0 {
+            return Ok((estimate, 0.0));
+        }
+
+        let index = q * self.total_weight;
+        let mut weight_so_far = 0.0;
+        let mut left = Centroid {
+            mean: self.min,
+            weight: 1.0,
+        };
+        for right in self.ordered_centroids() {
+            if weight_so_far + right.weight * 0.5 >= index {
+                return Ok((estimate, bracket_half_width(left, right)));
+            }
+            weight_so_far += right.weight;
+            left = right;
+        }
+
+        let right = Centroid {
+            mean: self.max,
+            weight: 1.0,
+        };
+        Ok((estimate, bracket_half_width(left, right)))
+    }
+
+    /// Returns the approximate quantile for `q` together with the estimated
+    /// number of observations at or below it, `round(q * N)`.
+    ///
+    /// The count is the target rank that [`Self::quantile`] approximates, not
+    /// a count of the values that compare at or below the returned estimate.
+    /// For exact samples it is within one of that count, and ties are not
+    /// resolved.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] for invalid `q` or empty
+    /// digests.
+    pub fn quantile_with_count(&self, q: f64) -> Result<(f64, u64), SketchError> {
+        let estimate = self.quantile(q)?;
+        Ok((estimate, (q * self.total_weight).round() as u64))
+    }
+
+    /// Returns the approximate cumulative fraction of weight at or below
+    /// each of `values`, in input order.
+    ///
+    /// This inverts the interpolation used by [`Self::quantile`], following
+    /// the reference `MergingDigest`: a value equal to one or more centroid
+    /// means counts half of their weight, values between centroids interpolate
+    /// across the span between their midpoint ranks with singleton centroids
+    /// kept as exact steps, and the retained minimum and maximum anchor both
+    /// tails. Values below the minimum return `0` and values above the
+    /// maximum return `1`.
+    ///
+    /// The merged and buffered centroids are collected once in mean order
+    /// with their cumulative weights, and each value is then located by
+    /// binary search, so `v` values cost `O(c + v log c)` for `c` centroids.
+    ///
+    /// An empty value slice returns an empty vector, including for an empty
+    /// digest.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when any value is non-finite,
+    /// or when a non-empty value slice is used with an empty digest.
+    pub fn cdf_many(&self, values: &[f64]) -> Result<Vec<f64>, SketchError> {
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(SketchError::InvalidParameter("cdf values must be finite"));
+        }
+        if values.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.centroid_count() == 0 {
+            return Err(SketchError::InvalidParameter(
+                "cdf is undefined for an empty digest",
+            ));
+        }
+
+        let centroids: Vec<Centroid> = self.ordered_centroids().collect();
+        let mut weight_before = Vec::with_capacity(centroids.len() + 1);
+        let mut cumulative = 0.0;
+        weight_before.push(cumulative);
+        for centroid in &centroids {
+            cumulative += centroid.weight;
+            weight_before.push(cumulative);
+        }
+
+        Ok(values
+            .iter()
+            .map(|&value| self.cdf_with(&centroids, &weight_before, value))
+            .collect())
+    }
+
+    /// Returns `(quantile, value)` pairs for a Prometheus summary.
+    ///
+    /// Each value is [`Self::quantile`] at the paired quantile, so values are
+    /// non-decreasing. Together with [`Self::count`] and [`Self::sum`] this is
+    /// everything a summary metric exposes. An empty quantile slice returns
+    /// an empty vector, including for an empty digest.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] when the quantiles are not
+    /// finite, not in `[0, 1]`, or not sorted in ascending order, or when a
+    /// non-empty quantile slice is used with an empty digest.
+    pub fn prometheus_summary(&self, quantiles: &[f64]) -> Result<Vec<(f64, f64)>, SketchError> {
+        if quantiles.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(SketchError::InvalidParameter(
+                "summary quantiles must be sorted in ascending order",
+            ));
+        }
+
+        quantiles
+            .iter()
+            .map(|&q| Ok((q, self.quantile(q)?)))
+            .collect()
+    }
+
+    /// Checks the digest's internal consistency.
+    ///
+    /// Every merged and buffered centroid must have a finite mean and a finite,
+    /// positive weight, the merged centroids must be ordered by mean, and the
+    /// centroid weights must sum to the recorded total weight up to a relative
+    /// rounding tolerance of `1e-9`. The checks always pass for a digest built
+    /// through the public API, so a failure points at corrupted state. This
+    /// takes `O(centroid_count)` time.
+    ///
+    /// # Errors
+    /// Returns [`SketchError::InvalidParameter`] naming the first violated
+    /// invariant.
+    pub fn validate(&self) -> Result<(), SketchError> {
+        if self
+            .centroids
+            .windows(2)
+            .any(|pair| pair[0].mean.total_cmp(&pair[1].mean) == Ordering::Greater)
+        {
+            return Err(SketchError::InvalidParameter(
+                "merged centroids must be ordered by mean",
+            ));
+        }
+
+        let mut weight_sum = 0.0;
+        for centroid in self.ordered_centroids() {
+            if !centroid.mean.is_finite() {
+                return Err(SketchError::InvalidParameter(
+                    "centroid means must be finite",
+                ));
+            }
+            if !centroid.weight.is_finite() || centroid.weight <= 0.0 {
+                return Err(SketchError::InvalidParameter(
+                    "centroid weights must be finite and positive",
+                ));
+            }
+            weight_sum += centroid.weight;
+        }
+
+        let tolerance = 1e-9 * self.total_weight.abs().max(1.0);
+        if !self.total_weight.is_finite() || (weight_sum - self.total_weight).abs() > tolerance {
+            return Err(SketchError::InvalidParameter(
+                "centroid weights must sum to the total weight",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` when [`Self::merge`] accepts `other`, which requires
+    /// equal compression.
+    pub fn compatible(&self, other: &Self) -> bool {
+        (self.compression - other.compression).abs() <= f64::EPSILON
+    }
+
     /// Merges another digest into this one.
     ///
     /// Centroids are recompressed and the exact observed minimum and maximum
@@ -305,7 +663,7 @@ impl TDigest {
     /// # Errors
     /// Returns [`SketchError::IncompatibleSketches`] when compression differs.
     pub fn merge(&mut self, other: &Self) -> Result<(), SketchError> {
-        if (self.compression - other.compression).abs() > f64::EPSILON {
+        if !self.compatible(other) {
             return Err(SketchError::IncompatibleSketches(
                 "compression must match for merge",
             ));
@@ -314,10 +672,11 @@ impl TDigest {
         if !other.is_empty() {
             self.min = self.min.min(other.min);
             self.max = self.max.max(other.max);
+            self.combine_moments(self.total_weight, other.total_weight, other.mean, other.m2);
         }
 
         for centroid in other.ordered_centroids() {
-            self.add_weighted(centroid.mean, centroid.weight);
+            self.push_weighted(centroid.mean, centroid.weight);
         }
         self.compress();
         Ok(())
@@ -331,9 +690,22 @@ impl TDigest {
         self.total_weight = 0.0;
         self.min = f64::INFINITY;
         self.max = f64::NEG_INFINITY;
+        self.mean = 0.0;
+        self.m2 = 0.0;
     }
 
-    fn add_weighted(&mut self, value: f64, weight: f64) {
+    /// Folds a group of `weight` observations with the given mean and sum of
+    /// squared deviations into the running moments, which currently summarize
+    /// `base_weight` observations.
+    fn combine_moments(&mut self, base_weight: f64, weight: f64, mean: f64, m2: f64) {
+        let combined_weight = base_weight + weight;
+        let delta = mean - self.mean;
+        self.mean += delta * (weight / combined_weight);
+        self.m2 += m2 + delta * delta * (base_weight * weight / combined_weight);
+    }
+
+    /// Buffers a weighted value without touching the observed extrema.
+    fn push_weighted(&mut self, value: f64, weight: f64) {
         if !value.is_finite() || !weight.is_finite() || weight <= 0.0 {
             return;
         }
@@ -356,8 +728,57 @@ impl TDigest {
         }
     }
 
+    /// Evaluates one CDF point for [`Self::cdf_many`]. `weight_before[i]` is
+    /// the total weight of `centroids[..i]`.
+    fn cdf_with(&self, centroids: &[Centroid], weight_before: &[f64], value: f64) -> f64 {
+        let total = self.total_weight;
+        if value < self.min {
+            return 0.0;
+        }
+        if value > self.max {
+            return 1.0;
+        }
+
+        let first_equal = centroids.partition_point(|centroid| centroid.mean < value);
+        let after_equal = centroids.partition_point(|centroid| centroid.mean <= value);
+        let weight = if first_equal < after_equal {
+            let equal_weight = weight_before[after_equal] - weight_before[first_equal];
+            weight_before[first_equal] + equal_weight * 0.5
+        } else if after_equal == 0 {
+            let first = centroids[0];
+            if value == self.min {
+                0.5
+            } else {
+                let fraction = span_fraction(self.min, value, first.mean);
+                1.0 + fraction * (first.weight * 0.5 - 1.0)
+            }
+        } else if after_equal == centroids.len() {
+            let last = centroids[centroids.len() - 1];
+            if value == self.max {
+                total - 0.5
+            } else {
+                let fraction = 1.0 - span_fraction(last.mean, value, self.max);
+                total - (1.0 + fraction * (last.weight * 0.5 - 1.0))
+            }
+        } else {
+            let (left, right) = (centroids[after_equal - 1], centroids[after_equal]);
+            let before = weight_before[after_equal - 1];
+            let left_excluded = if left.weight == 1.0 { 0.5 } else { 0.0 };
+            let right_excluded = if right.weight == 1.0 { 0.5 } else { 0.0 };
+            if left.weight == 1.0 && right.weight == 1.0 {
+                before + 1.0
+            } else {
+                let interval = (left.weight + right.weight) * 0.5 - left_excluded - right_excluded;
+                let fraction = span_fraction(left.mean, value, right.mean);
+                before + left.weight * 0.5 + left_excluded + interval * fraction
+            }
+        };
+
+        (weight / total).clamp(0.0, 1.0)
+    }
+
     fn buffer_limit(&self) -> usize {
-        (self.compression * BUFFER_MULTIPLIER).ceil() as usize
+        (self.compression * self.buffer_factor).ceil() as usize
     }
 
     fn ordered_centroids(&self) -> OrderedCentroids<'_> {
@@ -400,8 +821,8 @@ impl TDigest {
             return;
         }
 
-        let old = std::mem::take(&mut self.centroids);
-        let buffered = std::mem::take(&mut self.buffered);
+        let old = core::mem::take(&mut self.centroids);
+        let buffered = core::mem::take(&mut self.buffered);
         let capacity = old.len() + buffered.len();
         let mut old = old.into_iter().peekable();
         let mut buffered = buffered.into_iter().peekable();
@@ -452,8 +873,8 @@ impl TDigest {
 }
 
 struct OrderedCentroids<'a> {
-    merged: std::iter::Peekable<std::slice::Iter<'a, Centroid>>,
-    buffered: std::iter::Peekable<std::collections::btree_map::Iter<'a, BufferedKey, f64>>,
+    merged: core::iter::Peekable<core::slice::Iter<'a, Centroid>>,
+    buffered: core::iter::Peekable<alloc::collections::btree_map::Iter<'a, BufferedKey, f64>>,
 }
 
 impl Iterator for OrderedCentroids<'_> {
@@ -480,6 +901,25 @@ impl Iterator for OrderedCentroids<'_> {
     }
 }
 
+/// Returns half the value gap between two ordered centroids, or zero when
+/// both are single exact samples.
+fn bracket_half_width(left: Centroid, right: Centroid) -> f64 {
+    if left.weight <= 1.0 && right.weight <= 1.0 {
+        return 0.0;
+    }
+    (right.mean * 0.5 - left.mean * 0.5).max(0.0)
+}
+
+/// Returns where `value` lies between `left` and `right` as a fraction in
+/// `[0, 1]`, halving every operand so the differences cannot overflow.
+fn span_fraction(left: f64, value: f64, right: f64) -> f64 {
+    let span = right * 0.5 - left * 0.5;
+    if span <= 0.0 {
+        return 1.0;
+    }
+    ((value * 0.5 - left * 0.5) / span).clamp(0.0, 1.0)
+}
+
 /// Evaluates a convex combination without overflowing for finite endpoints.
 ///
 /// Same-sign endpoints use a bounded difference. Opposite-sign endpoints use
@@ -534,11 +974,23 @@ fn weighted_average(left: f64, left_weight: f64, right: f64, right_weight: f64)
     )
 }
 
+impl QuantileSketch for TDigest {
+    fn add_count(&mut self, value: f64, count: u64) -> Result<(), SketchError> {
+        self.add_weighted(value, count as f64);
+        Ok(())
+    }
+
+    fn quantile(&self, q: f64) -> Result<f64, SketchError> {
+        self.quantile(q)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
-    use super::{Centroid, TDigest, finite_lerp, weighted_average};
+    use super::{BUFFER_MULTIPLIER, Centroid, TDigest, finite_lerp, weighted_average};
+    use crate::SketchError;
 
     fn assert_close(actual: f64, expected: f64) {
         assert!(
@@ -675,6 +1127,9 @@ mod tests {
             total_weight: 8.0,
             min: -f64::MAX,
             max: f64::MAX,
+            buffer_factor: BUFFER_MULTIPLIER,
+            mean: 0.0,
+            m2: 0.0,
         };
         assert_eq!(between_centroids.quantile(0.5).unwrap(), 0.0);
 
@@ -695,6 +1150,9 @@ mod tests {
             total_weight: 8.0,
             min: -f64::MAX,
             max: f64::MAX,
+            buffer_factor: BUFFER_MULTIPLIER,
+            mean: 0.0,
+            m2: 0.0,
         };
         assert!(left_endpoint.quantile(0.1875).unwrap().is_finite());
 
@@ -715,6 +1173,9 @@ mod tests {
             total_weight: 8.0,
             min: -f64::MAX,
             max: f64::MAX,
+            buffer_factor: BUFFER_MULTIPLIER,
+            mean: 0.0,
+            m2: 0.0,
         };
         assert!(right_endpoint.quantile(0.8125).unwrap().is_finite());
     }
@@ -845,6 +1306,9 @@ mod tests {
             total_weight: 8.0,
             min: -2.0,
             max: 12.0,
+            buffer_factor: BUFFER_MULTIPLIER,
+            mean: 0.0,
+            m2: 0.0,
         };
 
         for (q, expected) in [
@@ -875,6 +1339,9 @@ mod tests {
             total_weight: 8.0,
             min: 0.0,
             max: 10.0,
+            buffer_factor: BUFFER_MULTIPLIER,
+            mean: 0.0,
+            m2: 0.0,
         };
 
         assert_eq!(digest.quantile(0.0).unwrap(), 0.0);
@@ -904,6 +1371,9 @@ mod tests {
             total_weight: 6.0,
             min: -2.0,
             max: 20.0,
+            buffer_factor: BUFFER_MULTIPLIER,
+            mean: 0.0,
+            m2: 0.0,
         };
 
         assert_eq!(digest.quantile(5.0 / 6.0 - 1e-12).unwrap(), 10.0);
@@ -973,6 +1443,129 @@ mod tests {
         assert!(p99 > 9_000.0);
     }
 
+    #[test]
+    fn quantile_bound_is_narrower_in_dense_regions_than_sparse_tails() {
+        let mut exact = TDigest::new(100.0).unwrap();
+        for value in 0..50 {
+            exact.add(f64::from(value));
+        }
+        assert_eq!(exact.quantile_with_bound(0.5).unwrap(), (25.0, 0.0));
+
+        // Exponential quantiles: dense near zero, increasingly sparse above.
+        const N: usize = 100_000;
+        let mut digest = TDigest::new(100.0).unwrap();
+        for i in 0..N {
+            let u = (i as f64 + 0.5) / N as f64;
+            digest.add(-(1.0 - u).ln());
+        }
+
+        let (median, median_width) = digest.quantile_with_bound(0.5).unwrap();
+        let (tail, tail_width) = digest.quantile_with_bound(0.999).unwrap();
+        assert_eq!(median, digest.quantile(0.5).unwrap());
+        assert_eq!(tail, digest.quantile(0.999).unwrap());
+        assert!(median_width > 0.0);
+        assert!(
+            median_width < tail_width,
+            "median_width={median_width} tail_width={tail_width}"
+        );
+        assert!((median - core::f64::consts::LN_2).abs() <= median_width);
+        assert!((tail - 1_000_f64.ln()).abs() <= tail_width);
+
+        assert_eq!(digest.quantile_with_bound(0.0).unwrap().1, 0.0);
+        assert_eq!(digest.quantile_with_bound(1.0).unwrap().1, 0.0);
+        assert!(digest.quantile_with_bound(1.5).is_err());
+        assert!(
+            TDigest::new(100.0)
+                .unwrap()
+                .quantile_with_bound(0.5)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn quantile_with_count_reports_the_target_rank() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        for value in 0..10_000 {
+            digest.add(f64::from(value));
+        }
+
+        let (median, below) = digest.quantile_with_count(0.5).unwrap();
+        assert_eq!(median, digest.quantile(0.5).unwrap());
+        assert_eq!(below, 5_000);
+        assert!((median - 5_000.0).abs() < 100.0, "median={median}");
+
+        let (p95, below) = digest.quantile_with_count(0.95).unwrap();
+        assert_eq!(below, 9_500);
+        assert!((p95 - 9_500.0).abs() < 50.0, "p95={p95}");
+
+        assert_eq!(digest.quantile_with_count(0.0).unwrap(), (0.0, 0));
+        assert_eq!(digest.quantile_with_count(1.0).unwrap(), (9_999.0, 10_000));
+        assert!(digest.quantile_with_count(-0.1).is_err());
+        assert!(
+            TDigest::new(100.0)
+                .unwrap()
+                .quantile_with_count(0.5)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn cdf_many_inverts_quantiles_in_input_order() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        for i in 0..100_000_u64 {
+            digest.add(((i * 7_919) % 100_000) as f64);
+        }
+
+        let quartiles = [0.25, 0.5, 0.75].map(|q| digest.quantile(q).unwrap());
+        let fractions = digest.cdf_many(&quartiles).unwrap();
+        for (fraction, expected) in fractions.iter().zip([0.25, 0.5, 0.75]) {
+            assert!(
+                (fraction - expected).abs() < 0.01,
+                "fractions={fractions:?}"
+            );
+        }
+
+        let values = [99_999.0, -5.0, 50_000.0, 1e9, 50_000.0, 0.0, 25_000.0];
+        let fractions = digest.cdf_many(&values).unwrap();
+        assert_eq!(fractions[0], 1.0 - 0.5 / 100_000.0);
+        assert_eq!(fractions[1], 0.0);
+        assert_eq!(fractions[3], 1.0);
+        assert_eq!(fractions[2], fractions[4]);
+        assert_eq!(fractions[5], 0.5 / 100_000.0);
+        assert!((fractions[2] - 0.5).abs() < 0.01);
+        assert!((fractions[6] - 0.25).abs() < 0.01);
+
+        let sweep: Vec<f64> = (0..=1_000).map(|i| f64::from(i) * 100.0).collect();
+        let fractions = digest.cdf_many(&sweep).unwrap();
+        assert!(fractions.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn cdf_many_counts_singletons_exactly_and_validates_input() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        for value in 0..10 {
+            digest.add(f64::from(value));
+        }
+        assert_eq!(
+            digest.cdf_many(&[4.5, 4.0, 0.0, 9.0, -1.0]).unwrap(),
+            vec![0.5, 0.45, 0.05, 0.95, 0.0]
+        );
+
+        assert!(digest.cdf_many(&[1.0, f64::NAN]).is_err());
+        assert!(digest.cdf_many(&[f64::INFINITY]).is_err());
+        let empty = TDigest::new(100.0).unwrap();
+        assert_eq!(empty.cdf_many(&[]).unwrap(), Vec::<f64>::new());
+        assert!(empty.cdf_many(&[0.0]).is_err());
+
+        let mut extreme = TDigest::new(10.0).unwrap();
+        for i in 0..1_000 {
+            extreme.add(if i % 2 == 0 { f64::MAX } else { -f64::MAX });
+        }
+        let fractions = extreme.cdf_many(&[-f64::MAX, 0.0, f64::MAX]).unwrap();
+        assert!(fractions.iter().all(|fraction| fraction.is_finite()));
+        assert!(fractions.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
     #[test]
     fn merge_combines_streams() {
         let mut left = TDigest::new(80.0).unwrap();
@@ -997,6 +1590,209 @@ mod tests {
         assert!(left.merge(&right).is_err());
     }
 
+    #[test]
+    fn centroid_export_round_trips_quantiles() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        for index in 0_u64..25_013 {
+            digest.add(((index * 7_919) % 25_013) as f64);
+        }
+        assert!(!digest.buffered.is_empty());
+
+        let centroids = digest.centroids();
+        assert_eq!(centroids.len(), digest.centroid_count());
+        assert!(centroids.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_close(
+            centroids.iter().map(|&(_, weight)| weight).sum(),
+            digest.total_weight,
+        );
+
+        let restored = TDigest::from_centroids(100.0, centroids.clone()).unwrap();
+        assert_eq!(restored.centroids(), centroids);
+        assert_eq!(restored.count(), digest.count());
+        for q in [
+            0.0, 0.001, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 0.999, 1.0,
+        ] {
+            assert_eq!(restored.quantile(q).unwrap(), digest.quantile(q).unwrap());
+        }
+    }
+
+    #[test]
+    fn from_centroids_sorts_and_rejects_invalid_centroids() {
+        assert!(TDigest::from_centroids(5.0, vec![(1.0, 1.0)]).is_err());
+        assert!(TDigest::from_centroids(100.0, vec![(f64::NAN, 1.0)]).is_err());
+        assert!(TDigest::from_centroids(100.0, vec![(1.0, f64::INFINITY)]).is_err());
+        assert!(TDigest::from_centroids(100.0, vec![(1.0, -1.0)]).is_err());
+        assert!(TDigest::from_centroids(100.0, vec![(1.0, 0.0)]).is_err());
+        assert!(TDigest::from_centroids(100.0, vec![(1.0, f64::MAX), (2.0, f64::MAX)]).is_err());
+
+        let unordered =
+            TDigest::from_centroids(100.0, vec![(3.0, 1.0), (1.0, 2.0), (2.0, 1.5)]).unwrap();
+        assert_eq!(
+            unordered.centroids(),
+            vec![(1.0, 2.0), (2.0, 1.5), (3.0, 1.0)]
+        );
+        assert_eq!(unordered.quantile(0.0).unwrap(), 1.0);
+        assert_eq!(unordered.quantile(1.0).unwrap(), 3.0);
+        assert_close(unordered.total_weight, 4.5);
+
+        let empty = TDigest::from_centroids(100.0, Vec::new()).unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.quantile(0.5).is_err());
+    }
+
+    #[test]
+    fn validate_accepts_built_digests_and_rejects_corrupted_ones() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        assert!(digest.validate().is_ok());
+        for index in 0_u64..10_007 {
+            digest.add(((index * 7_919) % 10_007) as f64);
+        }
+        assert!(!digest.buffered.is_empty());
+        assert!(digest.validate().is_ok());
+
+        let mut other = TDigest::new(100.0).unwrap();
+        other.add_weighted(-5.0, 3.5);
+        digest.merge(&other).unwrap();
+        assert!(digest.validate().is_ok());
+
+        let valid = TDigest::from_centroids(100.0, vec![(1.0, 2.0), (2.0, 1.5)]).unwrap();
+        assert!(valid.validate().is_ok());
+
+        let mut inconsistent = valid.clone();
+        inconsistent.total_weight = 4.0;
+        assert_eq!(
+            inconsistent.validate(),
+            Err(SketchError::InvalidParameter(
+                "centroid weights must sum to the total weight"
+            ))
+        );
+
+        let mut unordered = valid.clone();
+        unordered.centroids.swap(0, 1);
+        assert_eq!(
+            unordered.validate(),
+            Err(SketchError::InvalidParameter(
+                "merged centroids must be ordered by mean"
+            ))
+        );
+
+        for corrupt_weight in [-1.0, 0.0, f64::NAN, f64::INFINITY] {
+            let mut bad_weight = valid.clone();
+            bad_weight.centroids[1].weight = corrupt_weight;
+            assert!(bad_weight.validate().is_err());
+        }
+
+        let mut bad_mean = valid;
+        bad_mean.centroids[1].mean = f64::NAN;
+        assert!(bad_mean.validate().is_err());
+    }
+
+    #[test]
+    fn variance_matches_exact_population_variance() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        assert_eq!(digest.variance(), None);
+        assert_eq!(digest.std_dev(), None);
+
+        for value in 1..=1_000 {
+            digest.add(f64::from(value));
+        }
+        let exact = (1_000.0 * 1_000.0 - 1.0) / 12.0;
+        assert_close(digest.variance().unwrap() / exact, 1.0);
+        assert_close(digest.std_dev().unwrap() / exact.sqrt(), 1.0);
+
+        let mut weighted = TDigest::new(100.0).unwrap();
+        weighted.add_weighted(2.0, 3.0);
+        weighted.add_weighted(6.0, 1.0);
+        assert_close(weighted.variance().unwrap(), 3.0);
+
+        weighted.clear();
+        assert_eq!(weighted.variance(), None);
+    }
+
+    #[test]
+    fn merged_variance_matches_direct_ingestion() {
+        let mut direct = TDigest::new(100.0).unwrap();
+        let mut left = TDigest::new(100.0).unwrap();
+        let mut right = TDigest::new(100.0).unwrap();
+        for value in 0..5_000 {
+            let value = f64::from(value % 97) * 1.5 - 20.0;
+            direct.add(value);
+            if value < 10.0 {
+                left.add(value);
+            } else {
+                right.add(value);
+            }
+        }
+
+        left.merge(&right).unwrap();
+        assert_close(left.variance().unwrap() / direct.variance().unwrap(), 1.0);
+        left.merge(&TDigest::new(100.0).unwrap()).unwrap();
+        assert_close(left.variance().unwrap() / direct.variance().unwrap(), 1.0);
+
+        let singletons = TDigest::from_centroids(100.0, vec![(1.0, 1.0), (3.0, 1.0)]).unwrap();
+        assert_close(singletons.variance().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn prometheus_summary_reports_monotonic_quantiles_count_and_sum() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        assert_eq!(digest.sum(), 0.0);
+        assert!(digest.prometheus_summary(&[]).unwrap().is_empty());
+        assert!(digest.prometheus_summary(&[0.5]).is_err());
+
+        for value in 1..=10_000 {
+            digest.add(f64::from(value));
+        }
+        let summary = digest.prometheus_summary(&[0.5, 0.9, 0.99]).unwrap();
+        assert_eq!(
+            summary.iter().map(|&(q, _)| q).collect::<Vec<_>>(),
+            vec![0.5, 0.9, 0.99]
+        );
+        assert!(summary.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        for &(q, value) in &summary {
+            assert!((value - q * 10_000.0).abs() <= 50.0, "q={q} value={value}");
+        }
+        assert_eq!(digest.count(), 10_000);
+        assert_close(digest.sum(), 50_005_000.0);
+
+        assert!(digest.prometheus_summary(&[0.9, 0.5]).is_err());
+        assert!(digest.prometheus_summary(&[0.5, 1.5]).is_err());
+        assert!(digest.prometheus_summary(&[f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn smaller_buffer_factor_keeps_fewer_centroids() {
+        assert!(TDigest::with_buffer_factor(100.0, 0.5).is_err());
+        assert!(TDigest::with_buffer_factor(100.0, f64::NAN).is_err());
+        assert!(TDigest::with_buffer_factor(5.0, 2.0).is_err());
+
+        let mut standard = TDigest::new(100.0).unwrap();
+        let mut tight = TDigest::with_buffer_factor(100.0, 1.0).unwrap();
+        assert_eq!(standard.buffer_factor(), 10.0);
+        assert_eq!(tight.buffer_factor(), 1.0);
+
+        let (mut standard_peak, mut tight_peak) = (0, 0);
+        for index in 0..20_000 {
+            let value = f64::from((index * 7_919) % 20_000);
+            standard.add(value);
+            tight.add(value);
+            standard_peak = standard_peak.max(standard.centroid_count());
+            tight_peak = tight_peak.max(tight.centroid_count());
+        }
+        assert!(
+            tight_peak * 2 < standard_peak,
+            "tight={tight_peak} standard={standard_peak}"
+        );
+
+        for q in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let estimate = tight.quantile(q).unwrap();
+            assert!(
+                (estimate - q * 20_000.0).abs() <= 200.0,
+                "q={q} estimate={estimate}"
+            );
+        }
+    }
+
     #[test]
     fn clear_resets_state() {
         let mut digest = TDigest::new(50.0).unwrap();
@@ -1010,4 +1806,58 @@ mod tests {
         assert_eq!(digest.quantile(0.0).unwrap(), 9.0);
         assert_eq!(digest.quantile(1.0).unwrap(), 9.0);
     }
+
+    #[test]
+    fn add_weighted_matches_repeated_adds() {
+        let mut weighted = TDigest::new(100.0).unwrap();
+        let mut repeated = TDigest::new(100.0).unwrap();
+        for bucket in 0..50 {
+            let value = f64::from(bucket) * 0.5;
+            weighted.add_weighted(value, 1_000.0);
+            for _ in 0..1_000 {
+                repeated.add(value);
+            }
+        }
+
+        assert_eq!(weighted.count(), repeated.count());
+        for q in [0.0, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 1.0] {
+            let left = weighted.quantile(q).unwrap();
+            let right = repeated.quantile(q).unwrap();
+            assert!(
+                (left - right).abs() <= 0.1,
+                "q={q} weighted={left} repeated={right}"
+            );
+        }
+    }
+
+    #[test]
+    fn add_weighted_ignores_invalid_input() {
+        let mut digest = TDigest::new(100.0).unwrap();
+        digest.add_weighted(f64::NAN, 1.0);
+        digest.add_weighted(1.0, 0.0);
+        digest.add_weighted(1.0, -2.0);
+        digest.add_weighted(1.0, f64::INFINITY);
+        assert!(digest.is_empty());
+
+        digest.add_weighted(-3.0, 2.0);
+        digest.add_weighted(4.0, 6.0);
+        assert_eq!(digest.count(), 8);
+        assert_eq!(digest.quantile(0.0).unwrap(), -3.0);
+        assert_eq!(digest.quantile(1.0).unwrap(), 4.0);
+    }
+
+    #[test]
+    fn compatible_matches_merge_acceptance() {
+        let digests = [
+            TDigest::new(100.0).unwrap(),
+            TDigest::new(200.0).unwrap(),
+            TDigest::new(100.0).unwrap(),
+        ];
+        for left in &digests {
+            for right in &digests {
+                let merged = left.clone().merge(right);
+                assert_eq!(left.compatible(right), merged.is_ok());
+            }
+        }
+    }
 }
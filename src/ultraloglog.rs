@@ -54,8 +54,11 @@
 //! [Ertl 2017]: https://arxiv.org/pdf/1702.01284
 //! [Hash4j]: https://github.com/dynatrace-oss/hash4j
 
-use std::hash::Hash;
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
 
+#[cfg(not(any(feature = "std", test)))]
+use crate::float_math::FloatMath;
 use crate::jacard::{InclusionExclusionEstimates, JacardIndex, inclusion_exclusion_estimates};
 use crate::{SketchError, seeded_hash64};
 
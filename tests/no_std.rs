@@ -0,0 +1,129 @@
+// MIT License
+//
+// Copyright (c) 2026 Raja Lehtihet & Wael El Oraiby
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// Integration tests link the library without `cfg(test)`, so running them with
+// `--no-default-features` checks the genuine `no_std` build: libm-backed float
+// math and B-tree indexes. Each test compares against `std` float functions
+// here, so both builds must agree.
+
+use sketches::bloom_filter::BloomFilter;
+use sketches::count_sketch::CountSketch;
+use sketches::cuckoo_filter::CuckooFilter;
+use sketches::hyperloglog::HyperLogLog;
+use sketches::kll::KllSketch;
+use sketches::space_saving::{SpaceSaving, TrackedItem};
+use sketches::tdigest::TDigest;
+use sketches::ultraloglog::UltraLogLog;
+
+const SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+fn assert_relative_eq(actual: f64, expected: f64, tolerance: f64) {
+    let error = (actual - expected).abs() / expected;
+    assert!(
+        error <= tolerance,
+        "actual={actual} expected={expected} error={error}"
+    );
+}
+
+#[test]
+fn sizing_formulas_match_std_float_math() {
+    let (items, rate) = (1_000_usize, 0.01_f64);
+    let bit_len = (-(items as f64) * rate.ln() / core::f64::consts::LN_2.powi(2)).ceil() as usize;
+    assert_eq!(BloomFilter::optimal_bit_len(items, rate), Ok(bit_len));
+    let num_hashes = ((bit_len as f64 / items as f64) * core::f64::consts::LN_2).round() as u32;
+    assert_eq!(
+        BloomFilter::optimal_num_hashes(bit_len, items),
+        Ok(num_hashes)
+    );
+
+    let (epsilon, delta) = (0.1_f64, 0.01_f64);
+    let sketch = CountSketch::new(epsilon, delta, SEED).unwrap();
+    let width = ((8.0 / (epsilon * epsilon)).ceil() as usize).next_power_of_two();
+    let depth = (2.0 * (1.0 / delta).ln() / (16.0_f64 / 7.0).ln()).ceil() as usize | 1;
+    assert_eq!((sketch.width(), sketch.depth()), (width, depth));
+}
+
+#[test]
+fn cardinality_estimates_stay_within_their_error() {
+    let mut hll = HyperLogLog::new(12).unwrap();
+    let mut ull = UltraLogLog::new(12).unwrap();
+    for value in 0_u64..50_000 {
+        hll.add(&value);
+        ull.add(&value);
+    }
+    assert_relative_eq(
+        hll.estimate(),
+        50_000.0,
+        3.0 * hll.expected_relative_error(),
+    );
+    assert_relative_eq(
+        ull.estimate(),
+        50_000.0,
+        3.0 * ull.expected_relative_error(),
+    );
+}
+
+#[test]
+fn quantile_sketches_track_a_uniform_stream() {
+    let mut digest = TDigest::new(100.0).unwrap();
+    let mut kll = KllSketch::new(200).unwrap();
+    for value in 1..=10_000 {
+        digest.add(f64::from(value));
+        kll.add(f64::from(value));
+    }
+    assert_relative_eq(digest.quantile(0.5).unwrap(), 5_000.0, 0.01);
+    assert_relative_eq(digest.quantile(0.99).unwrap(), 9_900.0, 0.01);
+    assert_relative_eq(kll.quantile(0.5).unwrap(), 5_000.0, 0.03);
+}
+
+#[test]
+fn filters_keep_inserted_items() {
+    let mut filter = CuckooFilter::new(1_000, 0.001).unwrap();
+    let mut bloom = BloomFilter::new(1_000, 0.01).unwrap();
+    for value in 0_u64..1_000 {
+        assert!(filter.insert(&value));
+        bloom.insert(&value);
+    }
+    assert!((0_u64..1_000).all(|value| filter.contains(&value) && bloom.contains(&value)));
+}
+
+fn heavy_hitters<T: TrackedItem>(stream: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut summary = SpaceSaving::new(4).unwrap();
+    for item in stream {
+        summary.insert(item);
+    }
+    summary
+        .top_k(2)
+        .into_iter()
+        .map(|(item, _, _)| item)
+        .collect()
+}
+
+#[test]
+fn space_saving_accepts_the_same_items_in_every_build() {
+    let stream = (0..100).flat_map(|round| {
+        ["hot", "warm", "hot"]
+            .into_iter()
+            .chain([["a", "b", "c", "d"][round % 4]])
+    });
+    assert_eq!(heavy_hitters(stream), ["hot", "warm"]);
+}